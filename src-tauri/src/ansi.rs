@@ -0,0 +1,308 @@
+// ==================== ANSI 转义序列解析 ====================
+
+use serde::{Deserialize, Serialize};
+
+/// 标准 16 色名称（前端按主题映射为具体颜色）
+const NAMED_COLORS: [&str; 16] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "cyan",
+    "white",
+    "bright-black",
+    "bright-red",
+    "bright-green",
+    "bright-yellow",
+    "bright-blue",
+    "bright-magenta",
+    "bright-cyan",
+    "bright-white",
+];
+
+/// 文本样式
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TextStyle {
+    /// 前景色：标准色名称（如 `red`、`bright-green`）或 `#rrggbb`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fg: Option<String>,
+    /// 背景色，格式同前景色
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bg: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub bold: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub underline: bool,
+}
+
+impl TextStyle {
+    fn is_plain(&self) -> bool {
+        *self == TextStyle::default()
+    }
+}
+
+/// 带样式的文本片段
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StyledSegment {
+    pub text: String,
+    #[serde(flatten)]
+    pub style: TextStyle,
+}
+
+/// 解析后的一行文本
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StyledLine {
+    /// 去除转义序列后的纯文本
+    pub text: String,
+    /// 样式片段，整行都没有样式时为空
+    pub segments: Vec<StyledSegment>,
+}
+
+impl StyledLine {
+    /// 无样式的一行文本
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            segments: Vec::new(),
+        }
+    }
+
+    /// 在行首添加无样式的前缀
+    pub fn prepend(&mut self, prefix: &str) {
+        self.text.insert_str(0, prefix);
+        if !self.segments.is_empty() {
+            self.segments.insert(
+                0,
+                StyledSegment {
+                    text: prefix.to_string(),
+                    style: TextStyle::default(),
+                },
+            );
+        }
+    }
+}
+
+/// ANSI 解析器
+///
+/// 只保留 SGR（颜色、粗体、下划线）样式，光标移动、清屏、OSC 等其它控制序列直接丢弃。
+/// 样式状态会跨行保留，每个输出流应使用独立的解析器。
+#[derive(Debug, Default)]
+pub struct AnsiParser {
+    style: TextStyle,
+}
+
+impl AnsiParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 解析一行输出
+    pub fn parse(&mut self, line: &str) -> StyledLine {
+        // 回车会把光标移回行首（进度条等），只保留最后一次覆盖写入的内容
+        let line = match line.trim_end_matches('\r').rsplit_once('\r') {
+            Some((_, last)) => last,
+            None => line,
+        };
+
+        let mut segments: Vec<StyledSegment> = Vec::new();
+        let mut current = String::new();
+        let mut chars = line.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c != '\x1b' {
+                // 其它 C0 控制字符（制表符除外）不显示
+                if !c.is_control() || c == '\t' {
+                    current.push(c);
+                }
+                continue;
+            }
+
+            match chars.next() {
+                // CSI：ESC [ 参数 最终字节
+                Some('[') => {
+                    let mut params = String::new();
+                    let mut final_byte = None;
+                    for c in chars.by_ref() {
+                        if ('\x40'..='\x7e').contains(&c) {
+                            final_byte = Some(c);
+                            break;
+                        }
+                        params.push(c);
+                    }
+                    if final_byte == Some('m') {
+                        if !current.is_empty() {
+                            push_segment(&mut segments, std::mem::take(&mut current), &self.style);
+                        }
+                        self.apply_sgr(&params);
+                    }
+                }
+                // OSC：ESC ] ... 以 BEL 或 ESC \ 结束
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' {
+                            break;
+                        }
+                        if c == '\x1b' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                // 字符集选择等带一个参数的序列
+                Some('(') | Some(')') => {
+                    chars.next();
+                }
+                // 其它两字节序列（如 ESC 7 / ESC 8 / ESC M）
+                _ => {}
+            }
+        }
+
+        if !current.is_empty() {
+            push_segment(&mut segments, current, &self.style);
+        }
+
+        let text: String = segments.iter().map(|segment| segment.text.as_str()).collect();
+        if segments.iter().all(|segment| segment.style.is_plain()) {
+            segments.clear();
+        }
+
+        StyledLine { text, segments }
+    }
+
+    /// 应用 SGR 参数
+    fn apply_sgr(&mut self, params: &str) {
+        let codes: Vec<u32> = if params.is_empty() {
+            vec![0]
+        } else {
+            params
+                .split([';', ':'])
+                .map(|code| code.parse().unwrap_or(0))
+                .collect()
+        };
+
+        let mut iter = codes.into_iter();
+        while let Some(code) = iter.next() {
+            match code {
+                0 => self.style = TextStyle::default(),
+                1 => self.style.bold = true,
+                4 => self.style.underline = true,
+                22 => self.style.bold = false,
+                24 => self.style.underline = false,
+                30..=37 => self.style.fg = Some(NAMED_COLORS[(code - 30) as usize].to_string()),
+                90..=97 => self.style.fg = Some(NAMED_COLORS[(code - 90 + 8) as usize].to_string()),
+                40..=47 => self.style.bg = Some(NAMED_COLORS[(code - 40) as usize].to_string()),
+                100..=107 => self.style.bg = Some(NAMED_COLORS[(code - 100 + 8) as usize].to_string()),
+                39 => self.style.fg = None,
+                49 => self.style.bg = None,
+                38 | 48 => {
+                    let color = parse_extended_color(&mut iter);
+                    if code == 38 {
+                        self.style.fg = color;
+                    } else {
+                        self.style.bg = color;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// 追加片段，与上一个片段样式相同时合并
+fn push_segment(segments: &mut Vec<StyledSegment>, text: String, style: &TextStyle) {
+    match segments.last_mut() {
+        Some(last) if last.style == *style => last.text.push_str(&text),
+        _ => segments.push(StyledSegment {
+            text,
+            style: style.clone(),
+        }),
+    }
+}
+
+/// 解析 256 色（`5;n`）或真彩色（`2;r;g;b`）参数
+fn parse_extended_color(iter: &mut impl Iterator<Item = u32>) -> Option<String> {
+    match iter.next()? {
+        5 => {
+            let index = iter.next()?;
+            Some(color_256(index.min(255) as u8))
+        }
+        2 => {
+            let r = iter.next()?.min(255);
+            let g = iter.next()?.min(255);
+            let b = iter.next()?.min(255);
+            Some(format!("#{:02x}{:02x}{:02x}", r, g, b))
+        }
+        _ => None,
+    }
+}
+
+/// 256 色调色板转换为颜色字符串（前 16 色使用标准色名称）
+fn color_256(index: u8) -> String {
+    match index {
+        0..=15 => NAMED_COLORS[index as usize].to_string(),
+        16..=231 => {
+            let index = index - 16;
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            format!(
+                "#{:02x}{:02x}{:02x}",
+                level(index / 36),
+                level((index / 6) % 6),
+                level(index % 6)
+            )
+        }
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            format!("#{:02x}{:02x}{:02x}", gray, gray, gray)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sgr_segments() {
+        let mut parser = AnsiParser::new();
+        let line = parser.parse("\x1b[1;32mCompiling\x1b[0m sigil v0.1.0 \x1b[38;5;208mwarn\x1b[m");
+
+        assert_eq!(line.text, "Compiling sigil v0.1.0 warn");
+        assert_eq!(line.segments.len(), 3);
+        assert_eq!(line.segments[0].text, "Compiling");
+        assert_eq!(line.segments[0].style.fg.as_deref(), Some("green"));
+        assert!(line.segments[0].style.bold);
+        assert!(line.segments[1].style.is_plain());
+        assert_eq!(line.segments[2].style.fg.as_deref(), Some("#ff8700"));
+    }
+
+    #[test]
+    fn test_strip_control_sequences() {
+        let mut parser = AnsiParser::new();
+
+        let line = parser.parse("\x1b[2K\x1b[1Gbuilding...\x1b]0;title\x07 done");
+        assert_eq!(line.text, "building... done");
+        assert!(line.segments.is_empty());
+
+        // 回车覆盖写入只保留最后的内容
+        let line = parser.parse("10%\r50%\r100%");
+        assert_eq!(line.text, "100%");
+    }
+
+    #[test]
+    fn test_style_carries_across_lines() {
+        let mut parser = AnsiParser::new();
+        parser.parse("\x1b[4;41;97mfirst");
+
+        let mut line = parser.parse("second\x1b[0m");
+        assert_eq!(line.segments.len(), 1);
+        assert!(line.segments[0].style.underline);
+        assert_eq!(line.segments[0].style.bg.as_deref(), Some("red"));
+        assert_eq!(line.segments[0].style.fg.as_deref(), Some("bright-white"));
+
+        line.prepend("[PowerShell] ");
+        assert_eq!(line.text, "[PowerShell] second");
+        assert!(line.segments[0].style.is_plain());
+    }
+}
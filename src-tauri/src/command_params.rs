@@ -0,0 +1,412 @@
+// ==================== 命令参数（占位符）====================
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 参数类型
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ParameterType {
+    #[default]
+    String,
+    Number,
+    Integer,
+    Boolean,
+    Select,
+}
+
+impl ParameterType {
+    fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "string" => Some(ParameterType::String),
+            "number" => Some(ParameterType::Number),
+            "integer" => Some(ParameterType::Integer),
+            "boolean" => Some(ParameterType::Boolean),
+            "select" => Some(ParameterType::Select),
+            _ => None,
+        }
+    }
+}
+
+/// 命令参数定义
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CommandParameter {
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub param_type: ParameterType,
+    #[serde(default)]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// 可选值（仅 select 类型使用）
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+/// 参数值的引用方式（取决于最终执行命令的 shell）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuoteStyle {
+    /// sh -c
+    Posix,
+    /// cmd /C
+    Cmd,
+    /// Windows 批处理文件（UAC 提升执行时使用）
+    Batch,
+    /// pwsh / powershell -Command
+    PowerShell,
+    /// 不经过 shell，由 `shell::split_command_line` 拆分为参数
+    Argv,
+}
+
+/// 命令中的占位符：`{{name}}`、`{{name:type}}`、`{{name=default}}`、`{{name:type=default}}`
+struct Placeholder {
+    start: usize,
+    end: usize,
+    parameter: CommandParameter,
+}
+
+/// 解析命令文本中的所有占位符
+///
+/// 名称不是合法参数名的 `{{...}}`（如 `docker ps --format '{{.Names}}'` 中的 Go 模板）按原样保留。
+fn parse_placeholders(template: &str) -> Result<Vec<Placeholder>, String> {
+    let mut placeholders = Vec::new();
+    let mut offset = 0;
+
+    while let Some(pos) = template[offset..].find("{{") {
+        let start = offset + pos;
+        let Some(len) = template[start + 2..].find("}}") else {
+            return Err(format!("参数占位符未闭合: {}", &template[start..]));
+        };
+        let end = start + 2 + len + 2;
+        let spec = template[start + 2..end - 2].trim();
+
+        let (head, default) = match spec.split_once('=') {
+            Some((head, default)) => (head.trim(), Some(default.to_string())),
+            None => (spec, None),
+        };
+        let (name, type_name) = match head.split_once(':') {
+            Some((name, type_name)) => (name.trim(), Some(type_name.trim())),
+            None => (head, None),
+        };
+
+        if !is_valid_name(name) {
+            offset = start + 2;
+            continue;
+        }
+
+        let param_type = match type_name {
+            Some(type_name) => ParameterType::from_str(type_name)
+                .ok_or_else(|| format!("参数 {} 的类型无效: {}", name, type_name))?,
+            None => ParameterType::String,
+        };
+
+        placeholders.push(Placeholder {
+            start,
+            end,
+            parameter: CommandParameter {
+                name: name.to_string(),
+                param_type,
+                default,
+                ..Default::default()
+            },
+        });
+        offset = end;
+    }
+
+    Ok(placeholders)
+}
+
+/// 检查参数名是否合法
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// 获取命令的参数定义
+///
+/// 以命令文本中的占位符为准（按首次出现的顺序），已保存的参数定义覆盖占位符中的内联定义。
+pub fn resolve_parameters(
+    template: &str,
+    saved: &[CommandParameter],
+) -> Result<Vec<CommandParameter>, String> {
+    let mut parameters: Vec<CommandParameter> = Vec::new();
+
+    for placeholder in parse_placeholders(template)? {
+        if parameters.iter().any(|p| p.name == placeholder.parameter.name) {
+            continue;
+        }
+        let parameter = saved
+            .iter()
+            .find(|p| p.name == placeholder.parameter.name)
+            .cloned()
+            .unwrap_or(placeholder.parameter);
+        parameters.push(parameter);
+    }
+
+    Ok(parameters)
+}
+
+/// 将前端传入的参数值统一转换为字符串（null 视为未填写）
+pub fn normalize_values(values: HashMap<String, serde_json::Value>) -> HashMap<String, String> {
+    values
+        .into_iter()
+        .filter_map(|(name, value)| match value {
+            serde_json::Value::Null => None,
+            serde_json::Value::String(s) => Some((name, s)),
+            other => Some((name, other.to_string())),
+        })
+        .collect()
+}
+
+/// 校验参数值并渲染最终命令
+pub fn render_command(
+    template: &str,
+    saved: &[CommandParameter],
+    values: &HashMap<String, String>,
+    style: QuoteStyle,
+) -> Result<String, String> {
+    let placeholders = parse_placeholders(template)?;
+    if placeholders.is_empty() {
+        return Ok(template.to_string());
+    }
+
+    let parameters = resolve_parameters(template, saved)?;
+
+    // 先校验所有参数，再替换
+    let mut rendered_values: HashMap<&str, String> = HashMap::new();
+    for parameter in &parameters {
+        let value = values
+            .get(&parameter.name)
+            .filter(|v| !v.is_empty())
+            .or(parameter.default.as_ref())
+            .ok_or_else(|| format!("缺少参数: {}", parameter.name))?;
+
+        validate_value(parameter, value)?;
+        rendered_values.insert(parameter.name.as_str(), quote_value(value, style)?);
+    }
+
+    let mut result = String::with_capacity(template.len());
+    let mut last = 0;
+    for placeholder in &placeholders {
+        result.push_str(&template[last..placeholder.start]);
+        result.push_str(&rendered_values[placeholder.parameter.name.as_str()]);
+        last = placeholder.end;
+    }
+    result.push_str(&template[last..]);
+
+    Ok(result)
+}
+
+/// 按类型校验参数值
+fn validate_value(parameter: &CommandParameter, value: &str) -> Result<(), String> {
+    let valid = match parameter.param_type {
+        ParameterType::String => true,
+        ParameterType::Number => value.trim().parse::<f64>().map(f64::is_finite).unwrap_or(false),
+        ParameterType::Integer => value.trim().parse::<i64>().is_ok(),
+        ParameterType::Boolean => matches!(value, "true" | "false"),
+        ParameterType::Select => parameter.options.iter().any(|option| option == value),
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "参数 {} 的值无效（类型: {:?}）: {}",
+            parameter.name, parameter.param_type, value
+        ))
+    }
+}
+
+/// 按 shell 规则引用参数值
+fn quote_value(value: &str, style: QuoteStyle) -> Result<String, String> {
+    if value.contains(['\r', '\n', '\0']) {
+        return Err("参数值不能包含换行或空字符".to_string());
+    }
+
+    let is_safe = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:@+,=".contains(c));
+
+    match style {
+        QuoteStyle::Posix => {
+            if is_safe {
+                Ok(value.to_string())
+            } else {
+                Ok(format!("'{}'", value.replace('\'', "'\\''")))
+            }
+        }
+        QuoteStyle::PowerShell => {
+            if is_safe {
+                Ok(value.to_string())
+            } else {
+                // PowerShell 把弯引号也视为单引号，同样需要重复一次
+                let mut quoted = String::with_capacity(value.len() + 2);
+                quoted.push('\'');
+                for c in value.chars() {
+                    if matches!(c, '\'' | '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}') {
+                        quoted.push(c);
+                    }
+                    quoted.push(c);
+                }
+                quoted.push('\'');
+                Ok(quoted)
+            }
+        }
+        QuoteStyle::Argv => {
+            if is_safe {
+                Ok(value.to_string())
+            } else {
+                Ok(format!("'{}'", value.replace('\'', "'\"'\"'")))
+            }
+        }
+        QuoteStyle::Cmd | QuoteStyle::Batch => {
+            if is_safe {
+                return Ok(value.to_string());
+            }
+            // 先按 CommandLineToArgvW 规则加引号，再转义 cmd 的元字符，
+            // 避免参数值中的引号打乱 cmd 的引号状态
+            let quoted = quote_windows_arg(value);
+            let mut escaped = String::with_capacity(quoted.len() * 2);
+            for c in quoted.chars() {
+                match c {
+                    '%' if style == QuoteStyle::Batch => escaped.push_str("%%"),
+                    '(' | ')' | '%' | '!' | '^' | '"' | '<' | '>' | '&' | '|' => {
+                        escaped.push('^');
+                        escaped.push(c);
+                    }
+                    _ => escaped.push(c),
+                }
+            }
+            Ok(escaped)
+        }
+    }
+}
+
+/// 按 CommandLineToArgvW 规则为参数加引号
+pub fn quote_windows_arg(value: &str) -> String {
+    let mut result = String::from('"');
+    let mut backslashes = 0;
+
+    for c in value.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                result.push_str(&"\\".repeat(backslashes * 2 + 1));
+                result.push('"');
+                backslashes = 0;
+            }
+            _ => {
+                result.push_str(&"\\".repeat(backslashes));
+                result.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+
+    result.push_str(&"\\".repeat(backslashes * 2));
+    result.push('"');
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_resolve_parameters() {
+        let saved = vec![CommandParameter {
+            name: "env".to_string(),
+            param_type: ParameterType::Select,
+            options: vec!["dev".to_string(), "prod".to_string()],
+            ..Default::default()
+        }];
+        let parameters = resolve_parameters(
+            "git checkout {{branch}} && PORT={{port:number=3000}} run {{env}} {{branch}}",
+            &saved,
+        )
+        .unwrap();
+
+        assert_eq!(parameters.len(), 3);
+        assert_eq!(parameters[0].name, "branch");
+        assert_eq!(parameters[1].param_type, ParameterType::Number);
+        assert_eq!(parameters[1].default.as_deref(), Some("3000"));
+        assert_eq!(parameters[2].param_type, ParameterType::Select);
+    }
+
+    #[test]
+    fn test_render_posix() {
+        let rendered = render_command(
+            "git checkout {{branch}} && serve --port {{port:integer=3000}}",
+            &[],
+            &values(&[("branch", "feature/it's new")]),
+            QuoteStyle::Posix,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rendered,
+            "git checkout 'feature/it'\\''s new' && serve --port 3000"
+        );
+    }
+
+    #[test]
+    fn test_render_cmd() {
+        let template = "echo {{msg}}";
+        let input = values(&[("msg", "a\" & calc & \"%PATH%")]);
+
+        let rendered = render_command(template, &[], &input, QuoteStyle::Cmd).unwrap();
+        assert_eq!(rendered, "echo ^\"a\\^\" ^& calc ^& \\^\"^%PATH^%^\"");
+
+        let rendered = render_command(template, &[], &input, QuoteStyle::Batch).unwrap();
+        assert!(rendered.contains("%%PATH%%"));
+    }
+
+    #[test]
+    fn test_render_validation() {
+        let template = "run {{count:integer}} {{verbose:boolean=false}}";
+
+        assert!(render_command(template, &[], &values(&[]), QuoteStyle::Posix)
+            .unwrap_err()
+            .contains("缺少参数"));
+        assert!(render_command(template, &[], &values(&[("count", "abc")]), QuoteStyle::Posix).is_err());
+        assert!(render_command(
+            template,
+            &[],
+            &values(&[("count", "3"), ("verbose", "yes")]),
+            QuoteStyle::Posix
+        )
+        .is_err());
+        assert_eq!(
+            render_command(template, &[], &values(&[("count", "3")]), QuoteStyle::Posix).unwrap(),
+            "run 3 false"
+        );
+        assert!(render_command("run {{bad", &[], &values(&[]), QuoteStyle::Posix).is_err());
+    }
+
+    #[test]
+    fn test_non_parameter_braces_are_literal() {
+        let template = "docker ps --format '{{.Names}}' --filter name={{name}} && helm get {{ .Values }}";
+        assert_eq!(resolve_parameters(template, &[]).unwrap().len(), 1);
+        assert_eq!(
+            render_command(template, &[], &values(&[("name", "web")]), QuoteStyle::Posix).unwrap(),
+            "docker ps --format '{{.Names}}' --filter name=web && helm get {{ .Values }}"
+        );
+        assert_eq!(
+            render_command("docker ps --format '{{.Names}}'", &[], &values(&[]), QuoteStyle::Posix)
+                .unwrap(),
+            "docker ps --format '{{.Names}}'"
+        );
+    }
+}
@@ -1,10 +1,36 @@
-use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
-use std::io::Read;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ffi::OsStr;
+use std::io::{Read, Write};
 use std::path::PathBuf;
-use std::process::{Child, Command, Stdio};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter, EventTarget, Manager};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::ansi::{AnsiParser, StyledLine};
+use crate::command_params::{self, CommandParameter, QuoteStyle};
+use crate::config::ConfigManager;
+use crate::constants;
+use crate::db::{self, Database, FinishCommandRun, NewCommandRun};
+use crate::dotenv;
+use crate::health::{Health, HealthCheckConfig, HealthChecker, HealthTracker};
+use crate::log_buffer::{merged_page, LogBuffer, LogLine, LogPage, LogStream};
+use crate::process_tree::{self, StopMode};
+use crate::pty::{self, ChildProcess, PtyConfig};
+use crate::readiness::{ReadinessChecker, ReadinessConfig};
+#[cfg(target_os = "linux")]
+use crate::resource_limits::RunCgroup;
+use crate::resource_limits::{self, LimitedResource, ResourceLimits};
+use crate::restart::{RestartPlan, RestartPolicy};
+use crate::run_log::RunLogStore;
+use crate::run_queue::{ConcurrencyLimits, QueueEntry, RunQueue, RunningCounts};
+use crate::shell::{self, Shell};
+use crate::success::{FailureReason, SuccessChecker, SuccessRule};
+use crate::trigger::{OutputTrigger, OutputTriggerSet, TriggerAction};
 
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
@@ -12,18 +38,29 @@ use std::os::windows::process::CommandExt;
 #[cfg(target_os = "windows")]
 use windows::core::PCWSTR;
 #[cfg(target_os = "windows")]
-use windows::Win32::Foundation::{CloseHandle, HANDLE};
+use windows::Win32::Foundation::{CloseHandle, HANDLE, INVALID_HANDLE_VALUE};
 #[cfg(target_os = "windows")]
 use windows::Win32::Globalization::{
     GetOEMCP, MultiByteToWideChar, MULTI_BYTE_TO_WIDE_CHAR_FLAGS,
 };
 #[cfg(target_os = "windows")]
+use windows::Win32::System::IO::{CreateIoCompletionPort, GetQueuedCompletionStatus, OVERLAPPED};
+#[cfg(target_os = "windows")]
 use windows::Win32::System::JobObjects::{
-    AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
-    SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+    AssignProcessToJobObject, CreateJobObjectW, JobObjectAssociateCompletionPortInformation,
+    JobObjectBasicAccountingInformation, JobObjectCpuRateControlInformation,
+    JobObjectExtendedLimitInformation, QueryInformationJobObject, SetInformationJobObject,
+    JOBOBJECT_ASSOCIATE_COMPLETION_PORT, JOBOBJECT_BASIC_ACCOUNTING_INFORMATION,
+    JOBOBJECT_CPU_RATE_CONTROL_INFORMATION, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+    JOB_OBJECT_CPU_RATE_CONTROL_ENABLE, JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP,
+    JOB_OBJECT_LIMIT_ACTIVE_PROCESS, JOB_OBJECT_LIMIT_JOB_MEMORY,
     JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
 };
 #[cfg(target_os = "windows")]
+use windows::Win32::System::SystemServices::{
+    JOB_OBJECT_MSG_ACTIVE_PROCESS_LIMIT, JOB_OBJECT_MSG_JOB_MEMORY_LIMIT,
+};
+#[cfg(target_os = "windows")]
 use windows::Win32::System::Threading::{OpenProcess, PROCESS_SET_QUOTA, PROCESS_TERMINATE};
 
 // ==================== 数据结构定义 ====================
@@ -32,11 +69,48 @@ use windows::Win32::System::Threading::{OpenProcess, PROCESS_SET_QUOTA, PROCESS_
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum CommandStatus {
-    Idle,    // 空闲状态
-    Running, // 正在运行
-    Success, // 执行成功
-    Failed,  // 执行失败
-    Stopped, // 被停止
+    Idle,     // 空闲状态
+    Queued,   // 超出并发限制，排队等待
+    Starting, // 已启动，等待就绪检查通过
+    Running,  // 正在运行（未配置就绪检查）
+    Ready,    // 正在运行且已就绪
+    Success,  // 执行成功
+    Failed,   // 执行失败
+    Stopping, // 正在停止
+    Stopped,  // 被停止
+    TimedOut, // 运行超时被终止
+}
+
+impl CommandStatus {
+    /// 进程是否仍在运行（包括等待就绪和正在停止）
+    pub fn is_active(&self) -> bool {
+        matches!(
+            self,
+            CommandStatus::Starting
+                | CommandStatus::Running
+                | CommandStatus::Ready
+                | CommandStatus::Stopping
+        )
+    }
+}
+
+/// 运行触发方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RunTrigger {
+    Manual,   // 手动执行
+    Restart,  // 自动重启
+    Schedule, // 定时执行
+    Pipeline, // 流水线
+    Trigger,  // 输出触发器
+}
+
+/// 停止结果：进程是自行退出还是被强制终止
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StopResult {
+    Exited,      // 收到停止请求后自行退出
+    ForceKilled, // 被强制终止
 }
 
 /// 命令运行时状态
@@ -47,14 +121,103 @@ pub struct CommandState {
     pub pid: Option<u32>,
     pub start_time: Option<String>,
     pub exit_code: Option<i32>,
+    /// 运行 ID（运行历史记录的 ID，无法记录运行历史时为负数的临时 ID，排队中为排队 ID，
+    /// 没有运行时为 0）
+    pub run_id: i64,
+    /// 仅在命令被停止（手动或超时）后有值
+    pub stop_result: Option<StopResult>,
+    /// 连续自动重启的次数
+    pub restart_count: u32,
+    /// 下一次自动重启的时间（等待重启时有值）
+    pub next_retry_at: Option<String>,
+    /// 健康检查结果（未配置健康检查或尚未得出结果时为空）
+    pub health: Option<Health>,
+    /// 按成功判定规则判定为失败的原因（仅在执行失败且不是被停止时有值）
+    pub failure_reason: Option<FailureReason>,
+    /// 排队位置（从 1 开始，仅排队中有值）
+    pub queue_position: Option<usize>,
 }
 
-/// 日志行数据
+impl CommandState {
+    fn new(command_id: i64, run_id: i64, status: CommandStatus) -> Self {
+        Self {
+            command_id,
+            status,
+            pid: None,
+            start_time: None,
+            exit_code: None,
+            run_id,
+            stop_result: None,
+            restart_count: 0,
+            next_retry_at: None,
+            health: None,
+            failure_reason: None,
+            queue_position: None,
+        }
+    }
+}
+
+/// 排队中的运行（run-queue-changed 事件数据）
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct LogLine {
+pub struct QueuedRun {
+    pub queue_id: i64,
     pub command_id: i64,
+    pub command_name: String,
+    pub concurrency_group: Option<String>,
+    /// 排队位置（从 1 开始）
+    pub position: usize,
+    pub queued_at: String,
+    pub trigger: RunTrigger,
+}
+
+/// 命令超时事件数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandTimeoutEvent {
+    pub command_id: i64,
+    pub run_id: i64,
+    pub timeout_secs: u64,
+    pub force_killed: bool,
+}
+
+/// 命令未在超时时间内就绪事件数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandNotReadyEvent {
+    pub command_id: i64,
+    pub run_id: i64,
+    pub timeout_secs: u64,
+}
+
+/// 命令健康状态变化事件数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandHealthEvent {
+    pub command_id: i64,
+    pub run_id: i64,
+    pub health: Health,
+    /// 连续失败的检查次数
+    pub consecutive_failures: u32,
+}
+
+/// 输出触发器触发事件数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandTriggerEvent {
+    pub command_id: i64,
+    pub run_id: i64,
+    /// 触发器序号
+    pub trigger: usize,
+    pub pattern: String,
+    /// 匹配的日志行
     pub line: String,
-    pub stream: String, // "stdout" or "stderr"
+}
+
+/// 命令自动重启事件数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandRestartingEvent {
+    pub command_id: i64,
+    pub restart_count: u32,
+    /// 0 表示不限制
+    pub max_retries: u32,
+    pub delay_secs: u64,
+    pub next_retry_at: String,
 }
 
 /// 命令执行参数
@@ -65,19 +228,160 @@ pub struct ExecuteCommandParams {
     pub command: String,
     pub sudo: bool,
     pub working_directory: Option<String>,
+    pub url: Option<String>,
     pub notification_when_finished: bool,
+    pub env: BTreeMap<String, String>,
+    pub env_files: Vec<String>,
+    pub parameters: Vec<CommandParameter>,
+    pub parameter_values: HashMap<String, String>,
+    pub timeout_secs: Option<u64>,
+    pub graceful_timeout_secs: Option<u64>,
+    pub stop_mode: StopMode,
+    pub stop_command: Option<String>,
+    pub restart_policy: RestartPolicy,
+    pub force_color: bool,
+    pub pty: PtyConfig,
+    pub interactive: bool,
+    /// 就绪检查配置
+    pub readiness: ReadinessConfig,
+    /// 健康检查配置
+    pub health_check: HealthCheckConfig,
+    /// 输出触发器
+    pub output_triggers: Vec<OutputTrigger>,
+    /// 成功判定规则
+    pub success_rule: SuccessRule,
+    /// 执行命令使用的 shell
+    pub shell: Shell,
+    /// 允许同时运行的实例数（为空时只允许一个）
+    pub max_instances: Option<u32>,
+    /// 并发组名称
+    pub concurrency_group: Option<String>,
+    /// 资源限制
+    pub resource_limits: ResourceLimits,
+}
+
+impl ExecuteCommandParams {
+    /// 根据已保存的命令构建执行参数
+    pub fn from_command(command: db::Command, parameter_values: HashMap<String, String>) -> Self {
+        Self {
+            command_id: command.id,
+            command_name: command.name,
+            command: command.command,
+            sudo: command.sudo,
+            working_directory: command.working_directory,
+            url: command.url,
+            notification_when_finished: command.notification_when_finished,
+            env: command.env,
+            env_files: command.env_files,
+            parameters: command.parameters,
+            parameter_values,
+            timeout_secs: command.timeout_secs,
+            graceful_timeout_secs: command.graceful_timeout_secs,
+            stop_mode: command.stop_mode,
+            stop_command: command.stop_command,
+            restart_policy: command.restart_policy,
+            force_color: command.force_color,
+            pty: command.pty,
+            interactive: command.interactive,
+            readiness: command.readiness,
+            health_check: command.health_check,
+            output_triggers: command.output_triggers,
+            success_rule: command.success_rule,
+            shell: command.shell,
+            max_instances: command.max_instances,
+            concurrency_group: command.concurrency_group,
+            resource_limits: command.resource_limits,
+        }
+    }
 }
 
 // ==================== Windows Job Object 包装 ====================
 
+/// Job Object 及接收其通知的完成端口（配置了内存或进程数限制时才有）
 #[cfg(target_os = "windows")]
-struct JobHandle(HANDLE);
+struct JobHandle {
+    job: HANDLE,
+    port: Option<HANDLE>,
+}
 
 #[cfg(target_os = "windows")]
 impl Drop for JobHandle {
     fn drop(&mut self) {
         unsafe {
-            let _ = CloseHandle(self.0);
+            let _ = CloseHandle(self.job);
+            if let Some(port) = self.port {
+                let _ = CloseHandle(port);
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl JobHandle {
+    /// 创建完成端口并关联到 Job，失败时不接收通知
+    unsafe fn associate_completion_port(job: HANDLE) -> Option<HANDLE> {
+        let port = match CreateIoCompletionPort(INVALID_HANDLE_VALUE, HANDLE::default(), 0, 1) {
+            Ok(port) => port,
+            Err(e) => {
+                log::warn!("创建完成端口失败: {:?}", e);
+                return None;
+            }
+        };
+        let info = JOBOBJECT_ASSOCIATE_COMPLETION_PORT {
+            CompletionKey: std::ptr::null_mut(),
+            CompletionPort: port,
+        };
+        if let Err(e) = SetInformationJobObject(
+            job,
+            JobObjectAssociateCompletionPortInformation,
+            &info as *const _ as *const std::ffi::c_void,
+            std::mem::size_of::<JOBOBJECT_ASSOCIATE_COMPLETION_PORT>() as u32,
+        ) {
+            log::warn!("关联 Job Object 完成端口失败: {:?}", e);
+            let _ = CloseHandle(port);
+            return None;
+        }
+        Some(port)
+    }
+
+    /// 运行期间触发的资源限制
+    ///
+    /// Job Object 超出内存或进程数限制时只会使分配内存或创建进程失败，
+    /// 从完成端口取出运行期间的通知判断。
+    fn exceeded(&self) -> Option<LimitedResource> {
+        let port = self.port?;
+        let mut exceeded = None;
+        unsafe {
+            let mut message = 0u32;
+            let mut key = 0usize;
+            let mut overlapped: *mut OVERLAPPED = std::ptr::null_mut();
+            while GetQueuedCompletionStatus(port, &mut message, &mut key, &mut overlapped, 0)
+                .is_ok()
+            {
+                match message {
+                    JOB_OBJECT_MSG_JOB_MEMORY_LIMIT => return Some(LimitedResource::Memory),
+                    JOB_OBJECT_MSG_ACTIVE_PROCESS_LIMIT => {
+                        exceeded = Some(LimitedResource::Processes)
+                    }
+                    _ => {}
+                }
+            }
+        }
+        exceeded
+    }
+
+    /// Job 中是否已没有进程（查询失败时按没有处理）
+    fn is_empty(&self) -> bool {
+        unsafe {
+            let mut info: JOBOBJECT_BASIC_ACCOUNTING_INFORMATION = std::mem::zeroed();
+            QueryInformationJobObject(
+                self.job,
+                JobObjectBasicAccountingInformation,
+                &mut info as *mut _ as *mut std::ffi::c_void,
+                std::mem::size_of::<JOBOBJECT_BASIC_ACCOUNTING_INFORMATION>() as u32,
+                None,
+            )
+            .map_or(true, |_| info.ActiveProcesses == 0)
         }
     }
 }
@@ -117,19 +421,117 @@ fn is_elevated() -> bool {
 #[derive(Debug, Clone)]
 struct CommandInfo {
     params: ExecuteCommandParams,
+    /// 渲染后的自定义停止命令
+    stop_command_line: Option<String>,
+    /// 最终使用的环境变量和工作目录（停止命令沿用）
+    env: BTreeMap<String, String>,
+    working_dir: Option<PathBuf>,
     #[cfg(target_os = "windows")]
     temp_files: Option<(PathBuf, PathBuf, PathBuf, PathBuf)>, // (batch_file, output_file, error_file, exit_file)
 }
 
+/// 停止原因
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StopReason {
+    User,      // 用户手动停止
+    Timeout,   // 运行超时
+    NotReady,  // 就绪检查超时
+    Unhealthy, // 健康检查失败
+}
+
+impl StopReason {
+    /// 实际使用的停止方式：超时总是先请求优雅退出（宽限期后强制终止），不使用命令配置的停止方式
+    fn stop_mode(self, configured: StopMode) -> StopMode {
+        match self {
+            StopReason::Timeout => StopMode::Graceful,
+            _ => configured,
+        }
+    }
+
+    /// 被停止的运行的最终状态
+    fn final_status(self) -> CommandStatus {
+        match self {
+            StopReason::Timeout => CommandStatus::TimedOut,
+            StopReason::User => CommandStatus::Stopped,
+            StopReason::NotReady | StopReason::Unhealthy => CommandStatus::Failed,
+        }
+    }
+}
+
+/// 进行中的停止请求
+#[derive(Debug, Clone)]
+struct StopRequest {
+    reason: StopReason,
+    requested_at: Instant,
+    force_killed: bool,
+    /// 发起停止时的后代进程（可能已脱离进程组，用于停止后清理）
+    #[cfg(unix)]
+    descendants: Vec<process_tree::ProcessIdentity>,
+}
+
+/// 自动重启状态
+#[derive(Debug, Clone, Default)]
+struct RestartState {
+    restart_count: u32,
+    next_retry_at: Option<String>,
+}
+
+/// 运行中命令的标准输入
+struct CommandInput {
+    writer: Box<dyn Write + Send>,
+    /// 换行符（伪终端为 `\r`，管道为 `\n`）
+    newline: &'static str,
+    /// 是否将输入回显到日志（伪终端会自行回显输入，无需重复记录）
+    echo: bool,
+}
+
+/// 运行中命令的输出触发器
+struct RunTriggers {
+    set: OutputTriggerSet,
+    command_name: String,
+    url: Option<String>,
+    /// 是否有触发器将本次运行标记为失败
+    marked_failed: bool,
+}
+
+/// 一次匹配触发的触发器
+struct FiredTrigger {
+    index: usize,
+    pattern: String,
+    actions: Vec<TriggerAction>,
+    command_name: String,
+    url: Option<String>,
+}
+
 /// 命令运行器 - 管理所有命令的执行状态
+///
+/// 同一命令可以同时运行多个实例，运行中的状态、进程、日志等都以运行 ID 为键。
 pub struct CommandRunner {
     states: Arc<Mutex<HashMap<i64, CommandState>>>,
-    processes: Arc<Mutex<HashMap<i64, Child>>>,
+    processes: Arc<Mutex<HashMap<i64, ChildProcess>>>,
+    inputs: Arc<Mutex<HashMap<i64, CommandInput>>>,
     command_infos: Arc<Mutex<HashMap<i64, CommandInfo>>>,
-    logs: Arc<Mutex<HashMap<i64, Vec<String>>>>,
-    starting: Arc<Mutex<HashSet<i64>>>,
+    logs: Arc<Mutex<HashMap<i64, LogBuffer>>>,
+    stopping: Arc<Mutex<HashMap<i64, StopRequest>>>,
+    triggers: Arc<Mutex<HashMap<i64, RunTriggers>>>,
+    success_checkers: Arc<Mutex<HashMap<i64, SuccessChecker>>>,
     #[cfg(target_os = "windows")]
     job_objects: Arc<Mutex<HashMap<i64, JobHandle>>>,
+    #[cfg(target_os = "linux")]
+    cgroups: Arc<Mutex<HashMap<i64, RunCgroup>>>,
+    /// 每个命令的运行实例（运行 ID，按启动顺序）
+    instances: Arc<Mutex<HashMap<i64, Vec<i64>>>>,
+    /// 运行的后续运行：排队 ID 或自动重启前的运行 ID -> 启动的运行 ID（或再次排队的排队 ID）
+    successors: Arc<Mutex<HashMap<i64, i64>>>,
+    // 以下按命令 ID
+    starting: Arc<Mutex<HashSet<i64>>>,
+    restarts: Arc<Mutex<HashMap<i64, RestartState>>>,
+    /// 等待并发名额的运行
+    queue: Arc<Mutex<RunQueue<(ExecuteCommandParams, RunTrigger)>>>,
+    /// 检查并发限制并启动运行期间持有，避免同时启动的运行超出限制
+    launch_lock: Arc<Mutex<()>>,
+    /// 临时运行 ID 和排队 ID（从 -1 开始递减）
+    next_temp_run_id: Arc<AtomicI64>,
     app_handle: AppHandle,
 }
 
@@ -152,61 +554,302 @@ impl CommandRunner {
         Self {
             states: Arc::new(Mutex::new(HashMap::new())),
             processes: Arc::new(Mutex::new(HashMap::new())),
+            inputs: Arc::new(Mutex::new(HashMap::new())),
             command_infos: Arc::new(Mutex::new(HashMap::new())),
             logs: Arc::new(Mutex::new(HashMap::new())),
-            starting: Arc::new(Mutex::new(HashSet::new())),
+            stopping: Arc::new(Mutex::new(HashMap::new())),
+            triggers: Arc::new(Mutex::new(HashMap::new())),
+            success_checkers: Arc::new(Mutex::new(HashMap::new())),
             #[cfg(target_os = "windows")]
             job_objects: Arc::new(Mutex::new(HashMap::new())),
+            #[cfg(target_os = "linux")]
+            cgroups: Arc::new(Mutex::new(HashMap::new())),
+            instances: Arc::new(Mutex::new(HashMap::new())),
+            successors: Arc::new(Mutex::new(HashMap::new())),
+            starting: Arc::new(Mutex::new(HashSet::new())),
+            restarts: Arc::new(Mutex::new(HashMap::new())),
+            queue: Arc::new(Mutex::new(RunQueue::default())),
+            launch_lock: Arc::new(Mutex::new(())),
+            next_temp_run_id: Arc::new(AtomicI64::new(-1)),
             app_handle,
         }
     }
 
     /// 获取命令状态
+    ///
+    /// 有多个运行实例时依次选择最近启动的运行中实例、最早排队的运行和最近一次运行。
     pub fn get_state(&self, command_id: i64) -> Option<CommandState> {
-        let states = self.states.lock().unwrap();
-        states.get(&command_id).cloned()
+        let instances = self.get_instances(command_id);
+        instances
+            .iter()
+            .rev()
+            .find(|state| state.status.is_active())
+            .or_else(|| {
+                instances
+                    .iter()
+                    .find(|state| state.status == CommandStatus::Queued)
+            })
+            .or_else(|| {
+                instances
+                    .iter()
+                    .rev()
+                    .find(|state| state.status != CommandStatus::Queued)
+            })
+            .cloned()
     }
 
     /// 获取所有命令状态
     pub fn get_all_states(&self) -> HashMap<i64, CommandState> {
-        let states = self.states.lock().unwrap();
-        states.clone()
+        let mut command_ids: HashSet<i64> = {
+            let instances = self.instances.lock().unwrap();
+            instances.keys().copied().collect()
+        };
+        {
+            let queue = self.queue.lock().unwrap();
+            command_ids.extend(queue.iter().map(|(_, entry)| entry.command_id));
+        }
+        command_ids
+            .into_iter()
+            .filter_map(|command_id| Some((command_id, self.get_state(command_id)?)))
+            .collect()
     }
 
-    /// 更新命令状态
-    fn update_state(
+    /// 获取命令的所有运行实例（按启动顺序，排队中的运行在最后）
+    pub fn get_instances(&self, command_id: i64) -> Vec<CommandState> {
+        let run_ids = self.instance_ids(command_id);
+        let mut instances: Vec<CommandState> = {
+            let states = self.states.lock().unwrap();
+            run_ids
+                .iter()
+                .filter_map(|run_id| states.get(run_id).cloned())
+                .collect()
+        };
+
+        let queue = self.queue.lock().unwrap();
+        instances.extend(
+            queue
+                .iter()
+                .filter(|(_, entry)| entry.command_id == command_id)
+                .map(|(position, entry)| CommandState {
+                    queue_position: Some(position),
+                    ..CommandState::new(command_id, entry.id, CommandStatus::Queued)
+                }),
+        );
+        instances
+    }
+
+    /// 运行中的进程（命令 ID、运行 ID、进程 ID），用于采样资源占用
+    pub fn running_processes(&self) -> Vec<(i64, i64, u32)> {
+        let pids: Vec<(i64, u32)> = {
+            let processes = self.processes.lock().unwrap();
+            processes
+                .iter()
+                .map(|(run_id, child)| (*run_id, child.id()))
+                .collect()
+        };
+        let infos = self.command_infos.lock().unwrap();
+        pids.into_iter()
+            .filter_map(|(run_id, pid)| {
+                let info = infos.get(&run_id)?;
+                Some((info.params.command_id, run_id, pid))
+            })
+            .collect()
+    }
+
+    /// 获取运行实例的状态
+    pub fn get_instance_state(&self, run_id: i64) -> Option<CommandState> {
+        if let Some(state) = self.states.lock().unwrap().get(&run_id) {
+            return Some(state.clone());
+        }
+        // 排队中的运行
+        let queue = self.queue.lock().unwrap();
+        let state = queue
+            .iter()
+            .find(|(_, entry)| entry.id == run_id)
+            .map(|(position, entry)| CommandState {
+                queue_position: Some(position),
+                ..CommandState::new(entry.command_id, entry.id, CommandStatus::Queued)
+            });
+        state
+    }
+
+    /// 跟随运行的后续运行
+    ///
+    /// 排队的运行启动后、失败的运行自动重启后会产生新的运行 ID，
+    /// 返回 `run_id`（运行 ID 或排队 ID）最新的后续运行，没有后续时返回其本身。
+    pub fn follow_run(&self, run_id: i64) -> i64 {
+        let successors = self.successors.lock().unwrap();
+        let mut run_id = run_id;
+        while let Some(&next) = successors.get(&run_id) {
+            run_id = next;
+        }
+        run_id
+    }
+
+    /// 命令最近一次已结束的运行
+    fn latest_finished(&self, command_id: i64) -> Option<CommandState> {
+        self.get_instances(command_id).into_iter().rev().find(|state| {
+            !state.status.is_active() && state.status != CommandStatus::Queued
+        })
+    }
+
+    /// 命令的运行实例 ID（按启动顺序）
+    fn instance_ids(&self, command_id: i64) -> Vec<i64> {
+        let instances = self.instances.lock().unwrap();
+        instances.get(&command_id).cloned().unwrap_or_default()
+    }
+
+    /// 登记新的运行实例
+    ///
+    /// 同时清理该命令已结束的运行实例，新实例的日志序号从该命令已有的日志继续。
+    /// `previous` 为本次运行接续的排队 ID 或自动重启前的运行 ID。
+    fn add_instance(
         &self,
-        command_id: i64,
+        params: &ExecuteCommandParams,
+        run_id: i64,
         status: CommandStatus,
         pid: Option<u32>,
-        exit_code: Option<i32>,
+        previous: &[i64],
     ) {
-        let mut states = self.states.lock().unwrap();
-
-        let start_time = if status == CommandStatus::Running {
-            Some(chrono::Local::now().to_rfc3339())
-        } else {
-            states.get(&command_id).and_then(|s| s.start_time.clone())
+        let command_id = params.command_id;
+        let capacity = match self.app_handle.try_state::<Database>() {
+            Some(database) => ConfigManager::get_log_buffer_capacity(&database),
+            None => constants::command::MAX_LOG_LINES,
+        };
+        let restart = {
+            let restarts = self.restarts.lock().unwrap();
+            restarts.get(&command_id).cloned().unwrap_or_default()
         };
-
         let state = CommandState {
-            command_id,
-            status: status.clone(),
             pid,
-            start_time,
-            exit_code,
+            start_time: Some(chrono::Local::now().to_rfc3339()),
+            restart_count: restart.restart_count,
+            next_retry_at: restart.next_retry_at,
+            ..CommandState::new(command_id, run_id, status)
         };
 
-        states.insert(command_id, state.clone());
+        {
+            let mut instances = self.instances.lock().unwrap();
+            let mut states = self.states.lock().unwrap();
+            let mut logs = self.logs.lock().unwrap();
+
+            let run_ids = instances.entry(command_id).or_default();
+            // 与该命令已有的日志缓冲共用序号，同时运行的实例不会产生重复的序号
+            let buffer = LogBuffer::for_run(
+                command_id,
+                run_id,
+                capacity,
+                run_ids.iter().find_map(|id| logs.get(id)),
+            );
+            run_ids.retain(|id| {
+                let active = states.get(id).is_some_and(|state| state.status.is_active());
+                if !active {
+                    states.remove(id);
+                    logs.remove(id);
+                }
+                active
+            });
+            run_ids.push(run_id);
+            states.insert(run_id, state.clone());
+            logs.insert(run_id, buffer);
+
+            // 与登记实例在同一临界区内记录后续运行，跟随的一方不会看到中间状态
+            let mut successors = self.successors.lock().unwrap();
+            for &id in previous {
+                successors.insert(id, run_id);
+            }
+            // 已指向本次运行接续的排队 ID 的记录直接指向本次运行
+            for next in successors.values_mut() {
+                if previous.contains(next) {
+                    *next = run_id;
+                }
+            }
+            // 清理指向已清理的运行或已不在队列中的排队 ID 的记录
+            let queue = self.queue.lock().unwrap();
+            successors.retain(|_, next| {
+                states.contains_key(next) || queue.iter().any(|(_, entry)| entry.id == *next)
+            });
+        }
+
+        self.emit_state(state);
+    }
 
-        // 发送状态变化事件到前端（广播到所有窗口）
+    /// 更新运行实例状态
+    fn update_state(
+        &self,
+        run_id: i64,
+        status: CommandStatus,
+        pid: Option<u32>,
+        exit_code: Option<i32>,
+        stop_result: Option<StopResult>,
+        failure_reason: Option<FailureReason>,
+    ) {
+        let state = {
+            let mut states = self.states.lock().unwrap();
+            let Some(previous) = states.get(&run_id) else {
+                return;
+            };
+            // 停止过程中保留健康状态，结束时清空
+            let health = if status == CommandStatus::Stopping {
+                previous.health
+            } else {
+                None
+            };
+            let restart = {
+                let restarts = self.restarts.lock().unwrap();
+                restarts.get(&previous.command_id).cloned().unwrap_or_default()
+            };
+
+            let state = CommandState {
+                command_id: previous.command_id,
+                status,
+                pid,
+                start_time: previous.start_time.clone(),
+                exit_code,
+                run_id,
+                stop_result,
+                restart_count: restart.restart_count,
+                next_retry_at: restart.next_retry_at,
+                health,
+                failure_reason,
+                queue_position: None,
+            };
+            states.insert(run_id, state.clone());
+            state
+        };
+
+        self.emit_state(state);
+    }
+
+    /// 发送运行实例及其所属命令的状态变化事件（广播到所有窗口）
+    fn emit_state(&self, state: CommandState) {
+        let command_id = state.command_id;
         let _ = self
             .app_handle
-            .emit_to(EventTarget::Any, "command-status-changed", state);
+            .emit_to(EventTarget::Any, "command-instance-status-changed", state);
+        if let Some(state) = self.get_state(command_id) {
+            let _ = self
+                .app_handle
+                .emit_to(EventTarget::Any, "command-status-changed", state);
+        }
     }
 
     /// 执行命令
-    pub fn execute(&self, params: ExecuteCommandParams) -> Result<(), String> {
+    ///
+    /// 除自动重启外，执行命令会取消等待中的自动重启并重置重启计数。
+    /// 超出并发限制时进入运行队列，有空闲名额后按先后顺序启动。
+    /// 返回本次运行的运行 ID，进入队列时返回排队 ID。
+    pub fn execute(&self, params: ExecuteCommandParams, trigger: RunTrigger) -> Result<i64, String> {
+        self.execute_after(params, trigger, None)
+    }
+
+    /// 执行命令，`previous` 为本次运行接续的运行（自动重启前的运行 ID）
+    fn execute_after(
+        &self,
+        params: ExecuteCommandParams,
+        trigger: RunTrigger,
+        previous: Option<i64>,
+    ) -> Result<i64, String> {
         let _starting_guard = {
             let mut set = self.starting.lock().unwrap();
             if set.contains(&params.command_id) {
@@ -219,13 +862,239 @@ impl CommandRunner {
             }
         };
 
-        // 检查命令是否已在运行
-        {
-            let states = self.states.lock().unwrap();
-            if let Some(state) = states.get(&params.command_id) {
-                if state.status == CommandStatus::Running {
-                    return Err("命令正在运行中".to_string());
+        // 检查同时运行的实例数（包括排队中的，默认只允许一个）
+        let max_instances = params.max_instances.filter(|max| *max > 0).unwrap_or(1) as usize;
+        let running = self
+            .get_instances(params.command_id)
+            .iter()
+            .filter(|state| state.status.is_active() || state.status == CommandStatus::Queued)
+            .count();
+        if running >= max_instances {
+            if max_instances == 1 {
+                return Err("命令正在运行中".to_string());
+            }
+            return Err(format!("命令已有 {} 个实例在运行", running));
+        }
+
+        if trigger != RunTrigger::Restart {
+            let mut restarts = self.restarts.lock().unwrap();
+            restarts.remove(&params.command_id);
+        }
+
+        let limits = self.concurrency_limits();
+        let _launch = self.launch_lock.lock().unwrap();
+        // 先启动排在前面且有名额的运行，保证先进先出
+        self.start_queued_runs(&limits);
+        if limits.allows(params.concurrency_group.as_deref(), &self.running_counts()) {
+            self.launch(params, trigger, previous.as_slice())
+        } else {
+            Ok(self.enqueue(params, trigger, previous))
+        }
+    }
+
+    /// 获取并发限制
+    fn concurrency_limits(&self) -> ConcurrencyLimits {
+        self.app_handle
+            .try_state::<Database>()
+            .map(|database| ConfigManager::get_concurrency_limits(&database))
+            .unwrap_or_default()
+    }
+
+    /// 正在运行的命令数（按并发组统计）
+    fn running_counts(&self) -> RunningCounts {
+        let infos = self.command_infos.lock().unwrap();
+        let mut counts = RunningCounts::default();
+        for info in infos.values() {
+            counts.add(info.params.concurrency_group.as_deref());
+        }
+        counts
+    }
+
+    /// 加入运行队列，返回排队 ID
+    fn enqueue(
+        &self,
+        params: ExecuteCommandParams,
+        trigger: RunTrigger,
+        previous: Option<i64>,
+    ) -> i64 {
+        let id = self.next_temp_run_id.fetch_sub(1, Ordering::Relaxed);
+        let command_id = params.command_id;
+        if let Some(previous) = previous {
+            self.successors.lock().unwrap().insert(previous, id);
+        }
+        let position = {
+            let mut queue = self.queue.lock().unwrap();
+            queue.push(QueueEntry {
+                id,
+                command_id,
+                group: params.concurrency_group.clone(),
+                queued_at: chrono::Local::now().to_rfc3339(),
+                payload: (params, trigger),
+            })
+        };
+        log::info!("命令 {} 超出并发限制，排队等待（第 {} 位）", command_id, position);
+        self.emit_queue_changed(&[command_id]);
+        id
+    }
+
+    /// 按队列顺序启动有名额的排队运行（调用方需持有 launch_lock）
+    fn start_queued_runs(&self, limits: &ConcurrencyLimits) {
+        let mut started = Vec::new();
+        loop {
+            let running = self.running_counts();
+            let entry = {
+                let mut queue = self.queue.lock().unwrap();
+                queue.take_next(limits, &running)
+            };
+            let Some(entry) = entry else {
+                break;
+            };
+
+            let (params, trigger) = entry.payload;
+            log::info!("排队中的命令 {} 获得并发名额，开始执行", entry.command_id);
+            if let Err(e) = self.launch(params.clone(), trigger, &[entry.id]) {
+                log::error!("启动排队中的命令 {} 失败: {}", entry.command_id, e);
+                self.fail_queued_run(entry.id, &params, trigger, &e);
+            }
+            started.push(entry.command_id);
+        }
+
+        if !started.is_empty() {
+            self.emit_queue_changed(&started);
+        }
+    }
+
+    /// 排队的运行启动失败
+    ///
+    /// 记录一次失败的运行（写入运行历史和日志），并作为排队 ID 的后续运行登记，
+    /// 跟随排队 ID 的一方（流水线步骤等）会看到失败状态。
+    fn fail_queued_run(
+        &self,
+        queue_id: i64,
+        params: &ExecuteCommandParams,
+        trigger: RunTrigger,
+        error: &str,
+    ) {
+        let run = NewCommandRun {
+            command_id: params.command_id,
+            command_name: params.command_name.clone(),
+            command_text: params.command.clone(),
+            trigger,
+            pid: None,
+            started_at: chrono::Local::now().to_rfc3339(),
+        };
+        let run_id = self
+            .app_handle
+            .try_state::<Database>()
+            .and_then(|database| match database.insert_command_run(&run) {
+                Ok(run_id) => Some(run_id),
+                Err(e) => {
+                    log::error!("记录命令 {} 的运行历史失败: {}", params.command_id, e);
+                    None
+                }
+            });
+        let run_id = match run_id {
+            Some(run_id) => {
+                if let Some(store) = self.app_handle.try_state::<RunLogStore>() {
+                    if let Err(e) = store.create(run_id) {
+                        log::error!("{}", e);
+                    }
                 }
+                run_id
+            }
+            None => self.next_temp_run_id.fetch_sub(1, Ordering::Relaxed),
+        };
+
+        self.add_instance(params, run_id, CommandStatus::Failed, None, &[queue_id]);
+        self.append_log(run_id, StyledLine::plain(error), LogStream::Stderr);
+        self.record_run_finish(run_id, &CommandStatus::Failed, None, Duration::ZERO);
+    }
+
+    /// 启动有名额的排队运行（运行结束或并发限制配置变化后调用）
+    pub fn process_queue(&self) {
+        if self.queue.lock().unwrap().is_empty() {
+            return;
+        }
+        let limits = self.concurrency_limits();
+        let _launch = self.launch_lock.lock().unwrap();
+        self.start_queued_runs(&limits);
+    }
+
+    /// 获取运行队列
+    pub fn get_queue(&self) -> Vec<QueuedRun> {
+        let queue = self.queue.lock().unwrap();
+        queue
+            .iter()
+            .map(|(position, entry)| QueuedRun {
+                queue_id: entry.id,
+                command_id: entry.command_id,
+                command_name: entry.payload.0.command_name.clone(),
+                concurrency_group: entry.group.clone(),
+                position,
+                queued_at: entry.queued_at.clone(),
+                trigger: entry.payload.1,
+            })
+            .collect()
+    }
+
+    /// 取消排队中的运行
+    pub fn cancel_queued(&self, queue_id: i64) -> Result<(), String> {
+        let entry = {
+            let mut queue = self.queue.lock().unwrap();
+            queue.remove(queue_id).ok_or("排队中的运行不存在")?
+        };
+        log::info!("已取消命令 {} 的排队运行", entry.command_id);
+        self.emit_queue_changed(&[entry.command_id]);
+        Ok(())
+    }
+
+    /// 调整排队中的运行的位置（从 1 开始）
+    pub fn move_queued(&self, queue_id: i64, position: usize) -> Result<(), String> {
+        {
+            let mut queue = self.queue.lock().unwrap();
+            queue.move_to(queue_id, position)?;
+        }
+        self.emit_queue_changed(&[]);
+        Ok(())
+    }
+
+    /// 发送运行队列变化事件，并更新排队中（排队位置可能变化）和 `command_ids` 中命令的状态
+    fn emit_queue_changed(&self, command_ids: &[i64]) {
+        let queue = self.get_queue();
+        let mut affected: Vec<i64> = queue
+            .iter()
+            .map(|run| run.command_id)
+            .chain(command_ids.iter().copied())
+            .collect();
+        affected.sort_unstable();
+        affected.dedup();
+
+        let _ = self
+            .app_handle
+            .emit_to(EventTarget::Any, "run-queue-changed", queue);
+        for command_id in affected {
+            let state = self
+                .get_state(command_id)
+                .unwrap_or_else(|| CommandState::new(command_id, 0, CommandStatus::Idle));
+            let _ = self
+                .app_handle
+                .emit_to(EventTarget::Any, "command-status-changed", state);
+        }
+    }
+
+    /// 启动命令进程，返回运行 ID
+    fn launch(
+        &self,
+        params: ExecuteCommandParams,
+        trigger: RunTrigger,
+        previous: &[i64],
+    ) -> Result<i64, String> {
+        // 合并环境变量：.env 文件在前，命令中直接配置的环境变量优先
+        let mut env = resolve_command_env(&params)?;
+        if params.force_color {
+            // 输出不是终端时，多数工具需要这些变量才会输出颜色（用户显式配置的值优先）
+            for key in ["FORCE_COLOR", "CLICOLOR_FORCE"] {
+                env.entry(key.to_string()).or_insert_with(|| "1".to_string());
             }
         }
 
@@ -234,12 +1103,65 @@ impl CommandRunner {
         // 但如果当前进程已有管理员权限，则不需要临时文件
         #[cfg(target_os = "windows")]
         let elevated = if params.sudo { is_elevated() } else { false };
+
+        // 命令未指定 shell 时使用全局默认 shell
+        let shell = params.shell.or_default(
+            self.app_handle
+                .try_state::<Database>()
+                .map(|database| ConfigManager::get_default_shell(&database))
+                .unwrap_or_default(),
+        );
+
+        // 渲染命令参数，参数值的引用方式取决于最终执行命令的 shell
+        #[cfg(target_os = "windows")]
+        let quote_style = if params.sudo && !elevated {
+            if !matches!(shell, Shell::SystemDefault | Shell::Cmd) {
+                log::warn!("命令 {} 需要 UAC 提升执行，忽略 shell 设置", params.command_id);
+            }
+            QuoteStyle::Batch
+        } else {
+            shell.quote_style()
+        };
+        #[cfg(not(target_os = "windows"))]
+        let quote_style = shell.quote_style();
+
+        let command_line = command_params::render_command(
+            &params.command,
+            &params.parameters,
+            &params.parameter_values,
+            quote_style,
+        )?;
+        let (program, args) = shell.program_and_args(&command_line)?;
+
+        // 自定义停止命令始终以当前用户通过 shell 执行
+        let stop_command_line = match params.stop_command.as_deref().map(str::trim) {
+            Some(stop_command) if params.stop_mode == StopMode::Command && !stop_command.is_empty() => {
+                #[cfg(target_os = "windows")]
+                let stop_quote_style = QuoteStyle::Cmd;
+                #[cfg(not(target_os = "windows"))]
+                let stop_quote_style = QuoteStyle::Posix;
+
+                Some(command_params::render_command(
+                    stop_command,
+                    &params.parameters,
+                    &params.parameter_values,
+                    stop_quote_style,
+                )?)
+            }
+            _ => None,
+        };
+
         #[cfg(target_os = "windows")]
         let (temp_batch_file, temp_output_file, temp_error_file, temp_exit_file) =
             if params.sudo && !elevated {
                 // 创建临时文件用于 UAC 提升执行
                 let temp_dir = std::env::temp_dir();
-                let file_prefix = format!("sigil_cmd_{}", params.command_id);
+                // 同一命令可能同时运行多个实例，文件名加上时间戳
+                let file_prefix = format!(
+                    "sigil_cmd_{}_{}",
+                    params.command_id,
+                    chrono::Local::now().format("%Y%m%d%H%M%S%f")
+                );
 
                 let batch_file = temp_dir.join(format!("{}.bat", file_prefix));
                 let output_file = temp_dir.join(format!("{}_stdout.txt", file_prefix));
@@ -264,13 +1186,28 @@ impl CommandRunner {
                     }
                 };
 
+                // 提升后的进程不会继承当前进程的环境，环境变量需要写入批处理文件
+                let mut env_lines = String::new();
+                for (key, value) in &env {
+                    if value.contains(['\r', '\n']) {
+                        return Err(format!("环境变量值不能包含换行: {}", key));
+                    }
+                    env_lines.push_str(&format!(
+                        "set \"{}={}\"\n",
+                        key,
+                        value.replace('%', "%%")
+                    ));
+                }
+
                 let batch_content = format!(
                     "@echo off\n\
+                {}\
                 cd /d \"{}\"\n\
                 ({}) > \"{}\" 2> \"{}\"\n\
                 echo %ERRORLEVEL% > \"{}\"",
+                    env_lines,
                     working_dir,
-                    params.command.replace('"', "\""),
+                    command_line.replace('"', "\""),
                     output_file.to_string_lossy().replace('"', "\""),
                     error_file.to_string_lossy().replace('"', "\""),
                     exit_file.to_string_lossy().replace('"', "\"")
@@ -291,17 +1228,12 @@ impl CommandRunner {
                 (None, None, None, None)
             };
 
-        let mut cmd = if cfg!(target_os = "windows") {
+        #[cfg(target_os = "windows")]
+        let mut cmd = {
             if params.sudo {
                 if elevated {
                     // 如果已有管理员权限，直接执行命令（不需要 UAC 提升和临时文件）
-                    let mut c = Command::new("cmd");
-                    c.args(["/C", &params.command]);
-                    // Windows 进程创建标志：
-                    // CREATE_NEW_PROCESS_GROUP = 0x00000200
-                    // CREATE_NO_WINDOW = 0x08000000
-                    c.creation_flags(0x00000200 | 0x08000000);
-                    c
+                    program_command(&program, &args)
                 } else {
                     // 没有管理员权限，需要使用 UAC 提升
                     // 使用 PowerShell 的 -WindowStyle Hidden 来隐藏窗口
@@ -340,30 +1272,85 @@ impl CommandRunner {
                     c
                 }
             } else {
-                // Windows: 普通执行，使用 cmd /C
-                let mut c = Command::new("cmd");
-                c.args(["/C", &params.command]);
-                // Windows 进程创建标志：
-                // CREATE_NEW_PROCESS_GROUP = 0x00000200
-                // CREATE_NO_WINDOW = 0x08000000
-                c.creation_flags(0x00000200 | 0x08000000);
-                c
+                // Windows: 普通执行
+                program_command(&program, &args)
             }
-        } else {
+        };
+
+        #[cfg(not(target_os = "windows"))]
+        let mut cmd = {
             // Linux/macOS: 根据 sudo 标志决定是否使用 sudo
             if params.sudo {
                 let mut c = Command::new("sudo");
-                c.args(["-S", "sh", "-c", &params.command]);
+                c.arg("-S");
+                // sudo 默认会重置环境变量，通过 env 显式传递
+                if !env.is_empty() {
+                    c.arg("env");
+                    c.args(env.iter().map(|(key, value)| format!("{}={}", key, value)));
+                }
+                c.arg(&program).args(&args);
                 // sudo -S 表示从标准输入读取密码
                 // 但这里我们不提供密码输入，让系统提示用户输入
                 c
             } else {
-                let mut c = Command::new("sh");
-                c.args(["-c", &params.command]);
-                c
+                program_command(&program, &args)
             }
         };
 
+        // Windows UAC 提升时（且没有管理员权限），输出被重定向到临时文件，不需要管道
+        #[cfg(target_os = "windows")]
+        let is_using_temp_files = params.sudo && !elevated;
+        #[cfg(not(target_os = "windows"))]
+        let is_using_temp_files = false;
+
+        // 伪终端模式（UAC 提升执行时输出来自临时文件，不使用伪终端）
+        let use_pty = params.pty.enabled && !is_using_temp_files;
+        if params.pty.enabled && is_using_temp_files {
+            log::warn!("命令 {} 需要 UAC 提升执行，忽略伪终端模式", params.command_id);
+        }
+
+        // Unix: 在独立进程组中运行，便于向整个进程树发送信号
+        // （伪终端模式下子进程会创建新会话，同样成为进程组首进程）
+        if !use_pty {
+            process_tree::configure_process_group(&mut cmd);
+        }
+
+        // 资源限制：Linux 优先使用 cgroup v2，不可用时只能通过 setrlimit 限制内存
+        // （Windows 在进程启动后通过 Job Object 设置）
+        #[cfg(target_os = "linux")]
+        let cgroup = if params.resource_limits.is_empty() {
+            None
+        } else {
+            let name = format!(
+                "sigil_cmd_{}_{}",
+                params.command_id,
+                chrono::Local::now().timestamp_nanos_opt().unwrap_or_default()
+            );
+            // 此后到登记 cgroup 之前的任何提前返回（启动失败等）都依赖 RunCgroup 的 Drop
+            // 终止进程并删除 cgroup，因此在登记前不要通过 mem::forget 等方式转移所有权
+            RunCgroup::create(&name, &params.resource_limits)
+        };
+        #[cfg(target_os = "linux")]
+        let cgroup_procs = cgroup.as_ref().map(RunCgroup::procs_path);
+        #[cfg(all(unix, not(target_os = "linux")))]
+        let cgroup_procs: Option<PathBuf> = None;
+        #[cfg(unix)]
+        {
+            let limits = &params.resource_limits;
+            if cgroup_procs.is_none()
+                && (limits.cpu_percent.is_some() || limits.max_processes.is_some())
+            {
+                log::warn!(
+                    "cgroup v2 不可用，命令 {} 只限制内存，忽略 CPU 和进程数限制",
+                    params.command_id
+                );
+            }
+            resource_limits::configure_command(&mut cmd, limits, cgroup_procs.as_deref());
+        }
+
+        // 设置环境变量（在继承当前进程环境的基础上覆盖）
+        cmd.envs(&env);
+
         // 设置工作目录
         // Windows UAC 提升时（且没有管理员权限），工作目录已在批处理文件中设置，不需要在这里设置
         // 但如果已有管理员权限，直接执行命令时，需要设置工作目录
@@ -372,6 +1359,7 @@ impl CommandRunner {
         #[cfg(not(target_os = "windows"))]
         let should_set_working_dir = true;
 
+        let mut resolved_working_dir: Option<PathBuf> = None;
         if should_set_working_dir {
             // 获取工作目录：如果指定了工作目录则使用指定的，否则使用当前工作目录
             let working_dir;
@@ -411,6 +1399,7 @@ impl CommandRunner {
             // 设置工作目录
             if let Some(path) = working_dir {
                 cmd.current_dir(&path);
+                resolved_working_dir = Some(path);
             }
         } else {
             // Windows UAC 提升时，仍然需要验证工作目录存在（批处理文件中会使用）
@@ -422,20 +1411,47 @@ impl CommandRunner {
                 if !path.is_dir() {
                     return Err(format!("工作目录路径不是目录: {}", wd));
                 }
+                resolved_working_dir = Some(path);
             }
         }
 
-        // 配置输入输出
-        // Windows UAC 提升时（且没有管理员权限），输出被重定向到临时文件，不需要管道
+        // 检查可执行文件（相对路径基于工作目录，按命令的 PATH 查找）
         #[cfg(target_os = "windows")]
-        let is_using_temp_files = params.sudo && !elevated;
+        let check_executable = !is_using_temp_files;
         #[cfg(not(target_os = "windows"))]
-        let is_using_temp_files = false;
+        let check_executable = true;
+        if check_executable
+            && shell::find_executable(
+                &program,
+                env.get("PATH").map(OsStr::new),
+                resolved_working_dir.as_deref(),
+            )
+            .is_none()
+        {
+            return Err(format!("找不到可执行文件: {}", program));
+        }
+
+        // 就绪检查（文件路径相对于工作目录）
+        let readiness_checker = ReadinessChecker::new(
+            &params.readiness.probe,
+            params.url.as_deref(),
+            resolved_working_dir.as_deref(),
+        )?;
+
+        let health_checker =
+            HealthChecker::new(&params.health_check.probe, params.url.as_deref())?;
+        let output_triggers = OutputTriggerSet::compile(&params.output_triggers)?;
+        let success_checker = SuccessChecker::new(&params.success_rule)?;
 
+        // 配置输入输出
         if !is_using_temp_files {
-            cmd.stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .stdin(Stdio::null());
+            // 开启交互输入时保留标准输入管道
+            let stdin = if params.interactive {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            };
+            cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).stdin(stdin);
         } else {
             // UAC 提升时，输出重定向到临时文件，但 PowerShell 的输出仍需要捕获
             cmd.stdout(Stdio::piped())
@@ -444,8 +1460,39 @@ impl CommandRunner {
         }
 
         // 启动进程
-        let mut child = match cmd.spawn() {
-            Ok(child) => child,
+        let spawn_result = if use_pty {
+            #[cfg(unix)]
+            let result = pty::spawn(cmd, &params.pty);
+            #[cfg(target_os = "windows")]
+            let result = pty::spawn(
+                &shell.windows_command_line(&command_line)?,
+                &env,
+                resolved_working_dir.as_deref(),
+                &params.pty,
+            );
+            result.map(|(child, output, input)| {
+                let input = params.interactive.then(|| CommandInput {
+                    writer: Box::new(input),
+                    newline: "\r",
+                    echo: false,
+                });
+                (child, None, None, Some(output), input)
+            })
+        } else {
+            cmd.spawn().map(|mut child| {
+                // 获取 stdout 和 stderr 句柄用于日志收集
+                let stdout = child.stdout.take();
+                let stderr = child.stderr.take();
+                let input = child.stdin.take().map(|stdin| CommandInput {
+                    writer: Box::new(stdin),
+                    newline: "\n",
+                    echo: true,
+                });
+                (ChildProcess::Std(child), stdout, stderr, None, input)
+            })
+        };
+        let (child, stdout, stderr, pty_output, input) = match spawn_result {
+            Ok(spawned) => spawned,
             Err(e) => {
                 log::error!("启动命令失败: {}", e);
                 return Err(format!("启动命令失败: {}", e));
@@ -454,13 +1501,11 @@ impl CommandRunner {
 
         let pid = child.id();
 
-        // 获取 stdout 和 stderr 句柄用于日志收集
-        let stdout = child.stdout.take();
-        let stderr = child.stderr.take();
-
-        // Windows: 创建 Job Object 并将进程添加到其中
+        // Windows: 创建 Job Object 并将进程添加到其中（失败时终止刚启动的进程）
         #[cfg(target_os = "windows")]
-        {
+        let mut child = child;
+        #[cfg(target_os = "windows")]
+        let job = {
             unsafe {
                 // 创建 Job Object
                 let job = match CreateJobObjectW(None, PCWSTR::null()) {
@@ -472,9 +1517,18 @@ impl CommandRunner {
                     }
                 };
 
-                // 设置 Job 属性：当 Job 句柄关闭时，终止所有关联的进程
+                // 设置 Job 属性：当 Job 句柄关闭时，终止所有关联的进程，并按配置限制内存和进程数
+                let limits = &params.resource_limits;
                 let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
                 info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+                if let Some(bytes) = limits.max_memory_bytes() {
+                    info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_JOB_MEMORY;
+                    info.JobMemoryLimit = bytes as usize;
+                }
+                if let Some(max) = limits.max_processes {
+                    info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_ACTIVE_PROCESS;
+                    info.BasicLimitInformation.ActiveProcessLimit = max;
+                }
 
                 if let Err(e) = SetInformationJobObject(
                     job,
@@ -487,35 +1541,89 @@ impl CommandRunner {
                     return Err(format!("设置 Job Object 信息失败: {:?}", e));
                 }
 
+                // CPU 使用率上限（CpuRate 以万分之一为单位）
+                if let Some(percent) = limits.cpu_percent {
+                    let mut cpu: JOBOBJECT_CPU_RATE_CONTROL_INFORMATION = std::mem::zeroed();
+                    cpu.ControlFlags =
+                        JOB_OBJECT_CPU_RATE_CONTROL_ENABLE | JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP;
+                    cpu.Anonymous.CpuRate = percent * 100;
+                    if let Err(e) = SetInformationJobObject(
+                        job,
+                        JobObjectCpuRateControlInformation,
+                        &cpu as *const _ as *const std::ffi::c_void,
+                        std::mem::size_of::<JOBOBJECT_CPU_RATE_CONTROL_INFORMATION>() as u32,
+                    ) {
+                        let _ = CloseHandle(job);
+                        let _ = child.kill();
+                        return Err(format!("设置 CPU 使用率上限失败: {:?}", e));
+                    }
+                }
+
+                // 接收超出内存或进程数限制的通知
+                let port = if limits.max_memory_mb.is_some() || limits.max_processes.is_some() {
+                    JobHandle::associate_completion_port(job)
+                } else {
+                    None
+                };
+                let job = JobHandle { job, port };
+
                 // 打开进程句柄
                 let process_handle =
                     match OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, false, pid) {
                         Ok(handle) => handle,
                         Err(e) => {
-                            let _ = CloseHandle(job);
+                            drop(job);
                             let _ = child.kill();
                             return Err(format!("打开进程句柄失败: {:?}", e));
                         }
                     };
 
                 // 将进程添加到 Job
-                let assign_result = AssignProcessToJobObject(job, process_handle);
+                let assign_result = AssignProcessToJobObject(job.job, process_handle);
                 let _ = CloseHandle(process_handle);
 
                 if let Err(e) = assign_result {
-                    let _ = CloseHandle(job);
+                    drop(job);
                     let _ = child.kill();
                     return Err(format!("将进程添加到 Job Object 失败: {:?}", e));
                 }
 
-                // 保存 Job Object 句柄
-                let mut jobs = self.job_objects.lock().unwrap();
-                jobs.insert(params.command_id, JobHandle(job));
+                job
+            }
+        };
+
+        // 记录运行历史（数据库不可用时使用临时运行 ID）
+        let run_id = match self.record_run_start(&params, &command_line, pid, trigger) {
+            Some(run_id) => {
+                if let Some(store) = self.app_handle.try_state::<RunLogStore>() {
+                    if let Err(e) = store.create(run_id) {
+                        log::error!("{}", e);
+                    }
+                }
+                run_id
             }
+            None => self.next_temp_run_id.fetch_sub(1, Ordering::Relaxed),
+        };
+
+        // 保存 Job Object 句柄
+        #[cfg(target_os = "windows")]
+        {
+            let mut jobs = self.job_objects.lock().unwrap();
+            jobs.insert(run_id, job);
+        }
+        #[cfg(target_os = "linux")]
+        if let Some(cgroup) = cgroup {
+            let mut cgroups = self.cgroups.lock().unwrap();
+            cgroups.insert(run_id, cgroup);
         }
 
-        // 更新状态为运行中
-        self.update_state(params.command_id, CommandStatus::Running, Some(pid), None);
+        // 登记运行实例，状态为运行中（配置了就绪检查时为等待就绪）
+        let status = if readiness_checker.is_some() {
+            CommandStatus::Starting
+        } else {
+            CommandStatus::Running
+        };
+        self.add_instance(&params, run_id, status, Some(pid), previous);
 
         // 保存命令信息
         {
@@ -536,9 +1644,12 @@ impl CommandRunner {
                     None
                 };
                 infos.insert(
-                    params.command_id,
+                    run_id,
                     CommandInfo {
                         params: params.clone(),
+                        stop_command_line,
+                        env,
+                        working_dir: resolved_working_dir,
                         temp_files,
                     },
                 );
@@ -546,54 +1657,69 @@ impl CommandRunner {
             #[cfg(not(target_os = "windows"))]
             {
                 infos.insert(
-                    params.command_id,
+                    run_id,
                     CommandInfo {
                         params: params.clone(),
+                        stop_command_line,
+                        env,
+                        working_dir: resolved_working_dir,
                     },
                 );
             }
         }
 
-        // 初始化日志缓冲
+        // 输出触发器
+        if let Some(set) = output_triggers {
+            let mut triggers = self.triggers.lock().unwrap();
+            triggers.insert(
+                run_id,
+                RunTriggers {
+                    set,
+                    command_name: params.command_name.clone(),
+                    url: params.url.clone(),
+                    marked_failed: false,
+                },
+            );
+        }
         {
-            let mut logs = self.logs.lock().unwrap();
-            logs.insert(params.command_id, Vec::new());
+            let mut checkers = self.success_checkers.lock().unwrap();
+            checkers.insert(run_id, success_checker);
+        }
+
+        // 伪终端模式：stdout/stderr 合并为终端输出
+        if let Some(output) = pty_output {
+            let runner = self.clone_for_thread();
+            std::thread::spawn(move || {
+                runner.read_stream_to_logs(run_id, output, LogStream::Stdout, None);
+            });
         }
 
         // 启动日志读取线程（stdout）
         // Windows UAC 提升时，输出被重定向到临时文件，不需要读取 stdout/stderr
-        #[cfg(target_os = "windows")]
-        let is_using_temp_files = params.sudo && !elevated;
-        #[cfg(not(target_os = "windows"))]
-        let is_using_temp_files = false;
-
         if !is_using_temp_files {
             if let Some(stdout) = stdout {
-                let command_id = params.command_id;
                 let runner = self.clone_for_thread();
                 std::thread::spawn(move || {
-                    runner.read_stream_to_logs(command_id, stdout, "stdout", None);
+                    runner.read_stream_to_logs(run_id, stdout, LogStream::Stdout, None);
                 });
             }
 
             // 启动日志读取线程（stderr）
             if let Some(stderr) = stderr {
-                let command_id = params.command_id;
                 let runner = self.clone_for_thread();
                 std::thread::spawn(move || {
-                    runner.read_stream_to_logs(command_id, stderr, "stderr", None);
+                    runner.read_stream_to_logs(run_id, stderr, LogStream::Stderr, None);
                 });
             }
         } else {
             // Windows UAC 提升时，仍然需要读取 PowerShell 的输出
             if let Some(stderr) = stderr {
-                let command_id = params.command_id;
                 let runner = self.clone_for_thread();
                 std::thread::spawn(move || {
                     runner.read_stream_to_logs(
-                        command_id,
+                        run_id,
                         stderr,
-                        "stderr",
+                        LogStream::Stderr,
                         Some("[PowerShell] "),
                     );
                 });
@@ -603,133 +1729,692 @@ impl CommandRunner {
         // 保存进程句柄
         {
             let mut processes = self.processes.lock().unwrap();
-            processes.insert(params.command_id, child);
+            processes.insert(run_id, child);
+        }
+        if let Some(input) = input {
+            let mut inputs = self.inputs.lock().unwrap();
+            inputs.insert(run_id, input);
         }
 
         // 启动后台线程监控进程
-        let command_id = params.command_id;
         let runner = self.clone_for_thread();
 
         std::thread::spawn(move || {
-            runner.monitor_process(command_id);
+            runner.monitor_process(run_id);
         });
 
-        Ok(())
+        // 等待就绪（未配置就绪检查时视为立即就绪）
+        match readiness_checker {
+            Some(checker) => {
+                let runner = self.clone_for_thread();
+                let readiness = params.readiness.clone();
+                let url = params.url.clone();
+                std::thread::spawn(move || {
+                    runner.wait_until_ready(run_id, checker, &readiness, url);
+                });
+            }
+            None if params.readiness.open_url => self.open_url(params.url.as_deref()),
+            None => {}
+        }
+
+        // 健康检查
+        if let Some(checker) = health_checker {
+            let runner = self.clone_for_thread();
+            let health_check = params.health_check.clone();
+            let command_name = params.command_name.clone();
+            std::thread::spawn(move || {
+                runner.monitor_health(run_id, checker, &health_check, &command_name);
+            });
+        }
+
+        Ok(run_id)
+    }
+
+    /// 定期执行健康检查，直到本次运行结束或开始停止
+    fn monitor_health(
+        &self,
+        run_id: i64,
+        checker: HealthChecker,
+        health_check: &HealthCheckConfig,
+        command_name: &str,
+    ) {
+        let interval = Duration::from_secs(health_check.interval_secs.max(1));
+        let mut tracker = HealthTracker::new(health_check.failure_threshold);
+
+        loop {
+            std::thread::sleep(interval);
+
+            let Some(state) = self.get_instance_state(run_id) else {
+                return;
+            };
+            let command_id = state.command_id;
+            match state.status {
+                // 就绪前不检查
+                CommandStatus::Starting => continue,
+                CommandStatus::Running | CommandStatus::Ready => {}
+                _ => return,
+            }
+
+            let success = match &checker {
+                HealthChecker::Probe(probe) => probe.check(&[]),
+                HealthChecker::Command(command_line) => self.run_health_command(run_id, command_line),
+            };
+            let Some(health) = tracker.record(success) else {
+                continue;
+            };
+            if !self.set_health(run_id, health, tracker.consecutive_failures()) {
+                return;
+            }
+            if health == Health::Healthy {
+                log::info!("命令 {} 健康检查恢复正常", command_id);
+                continue;
+            }
+
+            log::warn!(
+                "命令 {} 健康检查连续失败 {} 次",
+                command_id,
+                tracker.consecutive_failures()
+            );
+            if health_check.notify {
+                self.show_notification(
+                    "命令运行不健康",
+                    format!("命令 \"{}\" 健康检查连续失败", command_name),
+                );
+            }
+            if health_check.restart_on_unhealthy {
+                if let Ok(true) = self.register_stop(run_id, StopReason::Unhealthy) {
+                    log::info!("命令 {} 不健康，停止后重新执行", command_id);
+                    self.begin_stop(run_id, StopReason::Unhealthy);
+                }
+                return;
+            }
+        }
+    }
+
+    /// 执行健康检查命令，在超时时间内以退出码 0 结束视为健康
+    fn run_health_command(&self, run_id: i64, command_line: &str) -> bool {
+        let mut cmd = shell_command(command_line);
+        cmd.stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        {
+            // 沿用命令的环境变量和工作目录
+            let infos = self.command_infos.lock().unwrap();
+            if let Some(info) = infos.get(&run_id) {
+                cmd.envs(&info.env);
+                if let Some(dir) = &info.working_dir {
+                    cmd.current_dir(dir);
+                }
+            }
+        }
+
+        let mut child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                log::warn!("启动运行 {} 的健康检查命令失败: {}", run_id, e);
+                return false;
+            }
+        };
+
+        let timeout = Duration::from_secs(constants::health::CHECK_COMMAND_TIMEOUT_SECS);
+        let started_at = Instant::now();
+        loop {
+            match child.try_wait() {
+                Ok(Some(status)) => return status.success(),
+                Ok(None) if started_at.elapsed() < timeout => {
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+                Ok(None) => {
+                    log::warn!("运行 {} 的健康检查命令超时", run_id);
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return false;
+                }
+                Err(e) => {
+                    log::warn!("等待运行 {} 的健康检查命令失败: {}", run_id, e);
+                    return false;
+                }
+            }
+        }
+    }
+
+    /// 更新健康状态并发送事件（本次运行已结束或正在停止时返回 false）
+    fn set_health(&self, run_id: i64, health: Health, consecutive_failures: u32) -> bool {
+        let state = {
+            let mut states = self.states.lock().unwrap();
+            match states.get_mut(&run_id) {
+                Some(state)
+                    if matches!(state.status, CommandStatus::Running | CommandStatus::Ready) =>
+                {
+                    state.health = Some(health);
+                    state.clone()
+                }
+                _ => return false,
+            }
+        };
+
+        let command_id = state.command_id;
+        self.emit_state(state);
+        let event = CommandHealthEvent {
+            command_id,
+            run_id,
+            health,
+            consecutive_failures,
+        };
+        let _ = self
+            .app_handle
+            .emit_to(EventTarget::Any, "command-health-changed", event);
+        true
+    }
+
+    /// 轮询就绪检查，通过后将状态从等待就绪切换为已就绪，超时则停止命令
+    fn wait_until_ready(
+        &self,
+        run_id: i64,
+        checker: ReadinessChecker,
+        readiness: &ReadinessConfig,
+        url: Option<String>,
+    ) {
+        let started_at = Instant::now();
+        let timeout = Duration::from_secs(readiness.timeout_secs);
+        let interval = Duration::from_millis(
+            readiness
+                .interval_ms
+                .max(constants::readiness::MIN_PROBE_INTERVAL_MS),
+        );
+        let mut cursor = 0;
+
+        loop {
+            // 进程已退出或正在停止时结束检查
+            let Some(state) = self
+                .get_instance_state(run_id)
+                .filter(|state| state.status == CommandStatus::Starting)
+            else {
+                return;
+            };
+            let command_id = state.command_id;
+
+            let new_lines: Vec<String> = {
+                let logs = self.logs.lock().unwrap();
+                match logs.get(&run_id) {
+                    Some(buffer) => {
+                        let page = buffer.page(Some(cursor), usize::MAX);
+                        cursor = page.next_cursor;
+                        page.lines.into_iter().map(|line| line.text).collect()
+                    }
+                    None => Vec::new(),
+                }
+            };
+
+            if checker.check(&new_lines) {
+                if self.mark_ready(run_id) {
+                    log::info!("命令 {} 已就绪（{:?}）", command_id, started_at.elapsed());
+                    if readiness.open_url {
+                        self.open_url(url.as_deref());
+                    }
+                }
+                return;
+            }
+
+            if readiness.timeout_secs > 0 && started_at.elapsed() >= timeout {
+                if let Ok(true) = self.register_stop(run_id, StopReason::NotReady) {
+                    log::warn!("命令 {} 在 {:?} 内未就绪，开始停止", command_id, timeout);
+                    self.begin_stop(run_id, StopReason::NotReady);
+                    let event = CommandNotReadyEvent {
+                        command_id,
+                        run_id,
+                        timeout_secs: readiness.timeout_secs,
+                    };
+                    let _ = self
+                        .app_handle
+                        .emit_to(EventTarget::Any, "command-not-ready", event);
+                }
+                return;
+            }
+
+            std::thread::sleep(interval);
+        }
+    }
+
+    /// 将等待就绪的命令标记为已就绪（状态已变化时返回 false）
+    fn mark_ready(&self, run_id: i64) -> bool {
+        let state = {
+            let mut states = self.states.lock().unwrap();
+            match states.get_mut(&run_id) {
+                Some(state) if state.status == CommandStatus::Starting => {
+                    state.status = CommandStatus::Ready;
+                    state.clone()
+                }
+                _ => return false,
+            }
+        };
+
+        self.emit_state(state);
+        true
+    }
+
+    /// 在浏览器中打开命令的 URL
+    fn open_url(&self, url: Option<&str>) {
+        let Some(url) = url.map(str::trim).filter(|url| !url.is_empty()) else {
+            return;
+        };
+        if let Err(e) = self.app_handle.opener().open_url(url, None::<&str>) {
+            log::warn!("打开 URL {} 失败: {}", url, e);
+        }
+    }
+
+    /// 停止命令的所有运行实例（同时取消排队中的运行）
+    ///
+    /// 按命令配置的停止方式发起停止后立即返回，宽限期后的强制终止和最终状态由监控线程处理，
+    /// 日志保留到下次执行（用于查看停止前后的输出和记录运行历史）。
+    /// 对正在停止中的命令再次调用会直接强制终止。
+    pub fn stop(&self, command_id: i64) -> Result<(), String> {
+        // 取消排队中的运行和等待中的自动重启
+        let dequeued = {
+            let mut queue = self.queue.lock().unwrap();
+            queue.remove_command(command_id)
+        };
+        if dequeued > 0 {
+            log::info!("已取消命令 {} 的 {} 个排队运行", command_id, dequeued);
+            self.emit_queue_changed(&[command_id]);
+        }
+        let restart_cancelled = self.cancel_pending_restart(command_id);
+
+        let run_ids: Vec<i64> = self
+            .get_instances(command_id)
+            .iter()
+            .filter(|state| state.status.is_active())
+            .map(|state| state.run_id)
+            .collect();
+        if run_ids.is_empty() {
+            if dequeued > 0 || restart_cancelled {
+                return Ok(());
+            }
+            return Err("命令未在运行".to_string());
+        }
+
+        let mut registered = false;
+        for run_id in run_ids {
+            // 实例可能已在检查后自行退出
+            registered |= matches!(self.request_user_stop(run_id), Ok(true));
+        }
+
+        // 关闭日志窗口（如果存在）
+        if registered {
+            let window_label = format!("log-{}", command_id);
+            if let Some(window) = self.app_handle.get_webview_window(&window_label) {
+                let _ = window.close();
+            }
+        }
+        Ok(())
+    }
+
+    /// 停止单个运行实例（对正在停止中的实例再次调用会直接强制终止）
+    ///
+    /// 排队中的运行直接取消，等待自动重启的运行取消重启。
+    pub fn stop_instance(&self, run_id: i64) -> Result<(), String> {
+        if let Some(state) = self.get_instance_state(run_id) {
+            if state.status == CommandStatus::Queued {
+                return self.cancel_queued(run_id);
+            }
+            if state.next_retry_at.is_some() && !state.status.is_active() {
+                // 重启已开始时取消不到，按未在运行处理
+                if self.cancel_pending_restart(state.command_id) {
+                    return Ok(());
+                }
+            }
+        }
+        self.request_user_stop(run_id).map(|_| ())
+    }
+
+    /// 停止运行实例，返回是否新发起了停止
+    fn request_user_stop(&self, run_id: i64) -> Result<bool, String> {
+        let registered = self.register_stop(run_id, StopReason::User)?;
+        if registered {
+            self.begin_stop(run_id, StopReason::User);
+        } else {
+            self.force_kill(run_id);
+        }
+        Ok(registered)
+    }
+
+    /// 取消等待中的自动重启，返回是否存在等待中的重启
+    fn cancel_pending_restart(&self, command_id: i64) -> bool {
+        let cancelled = {
+            let mut restarts = self.restarts.lock().unwrap();
+            restarts
+                .get_mut(&command_id)
+                .and_then(|restart| restart.next_retry_at.take())
+                .is_some()
+        };
+
+        // 等待重启的是最近一次已结束的运行
+        if let Some(state) = self.latest_finished(command_id).filter(|_| cancelled) {
+            log::info!("已取消命令 {} 的自动重启", command_id);
+            self.update_state(
+                state.run_id,
+                CommandStatus::Stopped,
+                None,
+                state.exit_code,
+                None,
+                None,
+            );
+        }
+
+        cancelled
+    }
+
+    /// 登记停止请求并将状态更新为正在停止
+    ///
+    /// 返回 `Ok(false)` 表示命令已经在停止中。
+    fn register_stop(&self, run_id: i64, reason: StopReason) -> Result<bool, String> {
+        let processes = self.processes.lock().unwrap();
+        let Some(child) = processes.get(&run_id) else {
+            return Err("命令未在运行".to_string());
+        };
+        let pid = child.id();
+
+        {
+            let mut stopping = self.stopping.lock().unwrap();
+            if stopping.contains_key(&run_id) {
+                return Ok(false);
+            }
+            stopping.insert(
+                run_id,
+                StopRequest {
+                    reason,
+                    requested_at: Instant::now(),
+                    force_killed: false,
+                    #[cfg(unix)]
+                    descendants: process_tree::collect_descendants(pid),
+                },
+            );
+        }
+
+        self.update_state(run_id, CommandStatus::Stopping, Some(pid), None, None, None);
+        Ok(true)
+    }
+
+    /// 按停止原因和命令配置的停止方式发起停止
+    fn begin_stop(&self, run_id: i64, reason: StopReason) {
+        let info = {
+            let infos = self.command_infos.lock().unwrap();
+            infos.get(&run_id).cloned()
+        };
+
+        let Some(info) = info else {
+            self.force_kill(run_id);
+            return;
+        };
+
+        match reason.stop_mode(info.params.stop_mode) {
+            StopMode::Kill => self.force_kill(run_id),
+            StopMode::Graceful => self.request_graceful_stop(run_id),
+            StopMode::Command => match info.stop_command_line.clone() {
+                Some(command_line) => {
+                    // 停止命令可能耗时较长（如 docker compose down），在独立线程中执行
+                    let runner = self.clone_for_thread();
+                    std::thread::spawn(move || {
+                        runner.run_stop_command(run_id, &info, &command_line);
+                    });
+                }
+                None => {
+                    log::warn!(
+                        "命令 {} 未配置停止命令，改为请求优雅退出",
+                        info.params.command_id
+                    );
+                    self.request_graceful_stop(run_id);
+                }
+            },
+        }
+    }
+
+    /// 执行自定义停止命令，失败时直接强制终止
+    fn run_stop_command(&self, run_id: i64, info: &CommandInfo, command_line: &str) {
+        let command_id = info.params.command_id;
+        log::info!("命令 {} 执行停止命令: {}", command_id, command_line);
+
+        let mut cmd = shell_command(command_line);
+        cmd.envs(&info.env).stdin(Stdio::null());
+        if let Some(dir) = &info.working_dir {
+            cmd.current_dir(dir);
+        }
+
+        match cmd.output() {
+            Ok(output) if output.status.success() => {
+                log::info!("命令 {} 的停止命令执行完成", command_id);
+            }
+            Ok(output) => {
+                log::warn!(
+                    "命令 {} 的停止命令执行失败（{}）: {}，直接强制终止",
+                    command_id,
+                    output.status,
+                    decode_output_bytes(&output.stderr).trim()
+                );
+                self.force_kill(run_id);
+            }
+            Err(e) => {
+                log::warn!("启动命令 {} 的停止命令失败: {}，直接强制终止", command_id, e);
+                self.force_kill(run_id);
+            }
+        }
     }
 
-    /// 停止命令
-    pub fn stop(&self, command_id: i64) -> Result<(), String> {
-        // 先更新状态为 Stopped，防止监控线程覆盖状态
-        self.update_state(command_id, CommandStatus::Stopped, None, None);
-
-        // 获取命令信息（用于清理临时文件）
-        #[cfg(target_os = "windows")]
-        let temp_files = {
-            let infos = self.command_infos.lock().unwrap();
-            infos
-                .get(&command_id)
-                .and_then(|info| info.temp_files.clone())
+    /// 请求进程优雅退出，失败时直接强制终止
+    fn request_graceful_stop(&self, run_id: i64) {
+        let result = {
+            let mut processes = self.processes.lock().unwrap();
+            match processes.get_mut(&run_id) {
+                Some(child) => child.request_graceful_stop(),
+                None => return,
+            }
         };
 
-        // 清理命令信息
+        if let Err(e) = result {
+            log::warn!("请求运行 {} 优雅退出失败: {}，直接强制终止", run_id, e);
+            self.force_kill(run_id);
+        }
+    }
+
+    /// 运行期间触发的资源限制（内存超限或进程数达到上限）
+    fn limit_exceeded(
+        &self,
+        run_id: i64,
+        status: Option<&std::process::ExitStatus>,
+    ) -> Option<LimitedResource> {
+        #[cfg(target_os = "linux")]
         {
-            let mut infos = self.command_infos.lock().unwrap();
-            infos.remove(&command_id);
+            let cgroups = self.cgroups.lock().unwrap();
+            if let Some(cgroup) = cgroups.get(&run_id) {
+                return cgroup.exceeded();
+            }
         }
 
-        // Windows UAC 提升：清理临时文件（如果存在）
-        #[cfg(target_os = "windows")]
-        if let Some((batch_file, output_file, error_file, exit_file)) = temp_files {
-            let _ = std::fs::remove_file(batch_file);
-            let _ = std::fs::remove_file(output_file);
-            let _ = std::fs::remove_file(error_file);
-            let _ = std::fs::remove_file(exit_file);
+        // Unix 未使用 cgroup 时通过 setrlimit 限制内存，按退出方式判断
+        #[cfg(unix)]
+        {
+            let memory_limited = {
+                let infos = self.command_infos.lock().unwrap();
+                infos
+                    .get(&run_id)
+                    .is_some_and(|info| info.params.resource_limits.max_memory_mb.is_some())
+            };
+            (memory_limited && status.is_some_and(resource_limits::rlimit_memory_exceeded))
+                .then_some(LimitedResource::Memory)
         }
 
-        // 清理日志数据
+        #[cfg(target_os = "windows")]
         {
-            let mut logs = self.logs.lock().unwrap();
-            logs.remove(&command_id);
+            let _ = status;
+            let jobs = self.job_objects.lock().unwrap();
+            jobs.get(&run_id).and_then(JobHandle::exceeded)
         }
+    }
 
-        // 关闭日志窗口（如果存在）
-        let window_label = format!("log-{}", command_id);
-        if let Some(window) = self.app_handle.get_webview_window(&window_label) {
-            let _ = window.close();
+    /// 强制终止命令的整个进程树
+    fn force_kill(&self, run_id: i64) {
+        {
+            let mut stopping = self.stopping.lock().unwrap();
+            if let Some(request) = stopping.get_mut(&run_id) {
+                request.force_killed = true;
+            }
         }
 
-        // Windows: 移除 Job Object，这会自动终止所有关联的进程
+        // Windows: 关闭 Job Object 会终止其中的所有进程
         #[cfg(target_os = "windows")]
-        let job_existed = {
+        {
             let mut jobs = self.job_objects.lock().unwrap();
-            if let Some(job) = jobs.remove(&command_id) {
-                drop(job); // 触发 Drop trait，关闭 Job Object 句柄
-                true
-            } else {
-                false
-            }
-        };
+            jobs.remove(&run_id);
+        }
 
-        // 移除进程句柄
         let mut processes = self.processes.lock().unwrap();
-        if let Some(mut child) = processes.remove(&command_id) {
-            drop(processes); // 释放锁
-
-            // 尝试 kill 原始进程（通常 Job Object 已经终止了）
-            #[cfg(target_os = "windows")]
+        if let Some(child) = processes.get_mut(&run_id) {
+            #[cfg(unix)]
             {
-                let _ = child.kill();
+                // 发起停止时记录的后代进程
+                let known_descendants = {
+                    let stopping = self.stopping.lock().unwrap();
+                    stopping
+                        .get(&run_id)
+                        .map(|request| request.descendants.clone())
+                        .unwrap_or_default()
+                };
+                if let Err(e) = process_tree::kill_process_tree(child.id(), &known_descendants) {
+                    log::warn!("终止运行 {} 的进程树失败: {}", run_id, e);
+                }
             }
+            let _ = child.kill();
+        }
+    }
 
-            #[cfg(not(target_os = "windows"))]
-            {
-                let _ = child.kill();
+    /// 监控进程状态
+    fn monitor_process(&self, run_id: i64) {
+        let (command_id, timeout, grace_period) = {
+            let infos = self.command_infos.lock().unwrap();
+            let Some(params) = infos.get(&run_id).map(|info| &info.params) else {
+                return;
+            };
+            (
+                params.command_id,
+                params
+                    .timeout_secs
+                    .filter(|secs| *secs > 0)
+                    .map(Duration::from_secs),
+                Duration::from_secs(
+                    params
+                        .graceful_timeout_secs
+                        .unwrap_or(constants::command::DEFAULT_GRACEFUL_TIMEOUT_SECS),
+                ),
+            )
+        };
+
+        let pid = {
+            let processes = self.processes.lock().unwrap();
+            match processes.get(&run_id) {
+                Some(child) => child.id(),
+                None => return,
             }
+        };
 
-            // 等待进程完全结束
-            let _ = child.wait();
+        let started_at = Instant::now();
+        let mut timeout_requested = false;
 
-            Ok(())
-        } else {
-            // 如果找不到进程句柄，但 Job Object 存在过，说明进程已经被终止了
-            #[cfg(target_os = "windows")]
-            if job_existed {
-                return Ok(());
+        // 轮询等待进程结束（不在持锁期间阻塞等待，避免 stop 被阻塞）
+        let exit_status = loop {
+            {
+                let mut processes = self.processes.lock().unwrap();
+                let result = match processes.get_mut(&run_id) {
+                    Some(child) => child.try_wait(),
+                    None => return,
+                };
+                match result {
+                    Ok(Some(status)) => {
+                        processes.remove(&run_id);
+                        break Ok(status);
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        processes.remove(&run_id);
+                        break Err(e);
+                    }
+                }
             }
 
-            Err("命令未在运行".to_string())
-        }
-    }
+            // 超时处理：先请求优雅退出，宽限期后强制终止
+            if let Some(timeout) = timeout {
+                if !timeout_requested && started_at.elapsed() >= timeout {
+                    timeout_requested = true;
+                    if let Ok(true) = self.register_stop(run_id, StopReason::Timeout) {
+                        log::warn!("命令 {} 运行超时（{:?}），开始停止", command_id, timeout);
+                        self.begin_stop(run_id, StopReason::Timeout);
+                    }
+                }
+            }
 
-    /// 监控进程状态
-    fn monitor_process(&self, command_id: i64) {
-        // 等待进程结束
-        let exit_status = {
-            let mut processes = self.processes.lock().unwrap();
-            if let Some(child) = processes.get_mut(&command_id) {
-                child.wait()
-            } else {
-                return;
+            // 停止请求超过宽限期后强制终止
+            let overdue = {
+                let stopping = self.stopping.lock().unwrap();
+                stopping
+                    .get(&run_id)
+                    .is_some_and(|r| !r.force_killed && r.requested_at.elapsed() >= grace_period)
+            };
+            if overdue {
+                log::warn!("命令 {} 在宽限期内未退出，强制终止", command_id);
+                self.force_kill(run_id);
             }
+
+            std::thread::sleep(Duration::from_millis(
+                constants::command::PROCESS_POLL_INTERVAL_MS,
+            ));
         };
 
-        // 检查当前状态是否已经是 Stopped（被手动停止）
+        // 在释放 Job Object 和 cgroup 之前检查是否超出资源限制
+        let limit_exceeded = self.limit_exceeded(run_id, exit_status.as_ref().ok());
+
+        // 关闭标准输入
         {
-            let states = self.states.lock().unwrap();
-            if let Some(state) = states.get(&command_id) {
-                if state.status == CommandStatus::Stopped {
-                    // 如果已经被手动停止，不要覆盖状态
-                    return;
-                }
-            }
+            let mut inputs = self.inputs.lock().unwrap();
+            inputs.remove(&run_id);
         }
 
-        // 获取命令信息（用于通知和临时文件清理）
+        // 取出停止请求和命令信息（用于通知和临时文件清理）
+        let stop_request = {
+            let mut stopping = self.stopping.lock().unwrap();
+            stopping.remove(&run_id)
+        };
         let command_info = {
-            let infos = self.command_infos.lock().unwrap();
-            infos.get(&command_id).cloned()
+            let mut infos = self.command_infos.lock().unwrap();
+            infos.remove(&run_id)
         };
 
+        // 被停止的命令：清理残留的后代进程（如脱离进程组或忽略了停止信号的子进程）
+        if let Some(ref request) = stop_request {
+            #[cfg(unix)]
+            if let Err(e) = process_tree::kill_process_tree(pid, &request.descendants) {
+                log::warn!("清理命令 {} 的残留进程失败: {}", command_id, e);
+            }
+
+            #[cfg(target_os = "windows")]
+            let _ = (pid, request);
+        }
+
+        // 释放 Job Object / cgroup（会终止其中残留的进程）；
+        // 正常退出但留有后代进程时继续限制这些进程，后代进程全部退出后再释放
+        #[cfg(target_os = "windows")]
+        {
+            let job = self.job_objects.lock().unwrap().remove(&run_id);
+            if let Some(job) = job.filter(|_| stop_request.is_none()) {
+                release_when_empty(job, JobHandle::is_empty);
+            }
+        }
+        #[cfg(target_os = "linux")]
+        {
+            let cgroup = self.cgroups.lock().unwrap().remove(&run_id);
+            if let Some(cgroup) = cgroup.filter(|_| stop_request.is_none()) {
+                release_when_empty(cgroup, RunCgroup::is_empty);
+            }
+        }
+
         // Windows UAC 提升：从临时文件读取输出
         #[cfg(target_os = "windows")]
         if let Some(ref info) = command_info {
@@ -741,30 +2426,26 @@ impl CommandRunner {
 
                 // 读取 stdout
                 if let Ok(bytes) = std::fs::read(output_file) {
+                    let mut parser = AnsiParser::new();
                     for line_bytes in split_output_lines(&bytes) {
-                        let line = decode_output_bytes(&line_bytes);
-                        self.append_log(command_id, line, "stdout");
+                        let line = parser.parse(&decode_output_bytes(&line_bytes));
+                        self.append_log(run_id, line, LogStream::Stdout);
                     }
                 }
 
                 // 读取 stderr
                 if let Ok(bytes) = std::fs::read(error_file) {
+                    let mut parser = AnsiParser::new();
                     for line_bytes in split_output_lines(&bytes) {
-                        let line = decode_output_bytes(&line_bytes);
-                        self.append_log(command_id, line, "stderr");
+                        let line = parser.parse(&decode_output_bytes(&line_bytes));
+                        self.append_log(run_id, line, LogStream::Stderr);
                     }
                 }
             }
         }
 
-        // 移除进程句柄
-        {
-            let mut processes = self.processes.lock().unwrap();
-            processes.remove(&command_id);
-        }
-
         // 更新状态并发送通知
-        let (final_status, exit_code) = match exit_status {
+        let exit_code = match exit_status {
             Ok(status) => {
                 let exit_code = status.code();
 
@@ -785,25 +2466,113 @@ impl CommandRunner {
                     exit_code
                 };
 
-                let final_status = if status.success() || exit_code == Some(0) {
-                    CommandStatus::Success
-                } else {
-                    CommandStatus::Failed
-                };
-
-                (final_status, exit_code)
+                exit_code
             }
             Err(e) => {
                 log::error!("等待进程失败: {:?}", e);
-                (CommandStatus::Failed, None)
+                None
+            }
+        };
+
+        // 按成功判定规则判定结果，输出触发器也可以将运行标记为失败
+        let checker = {
+            let mut checkers = self.success_checkers.lock().unwrap();
+            checkers.remove(&run_id)
+        };
+        let failure = match checker {
+            Some(checker) => checker.evaluate(exit_code),
+            None if exit_code == Some(0) => None,
+            None => Some(FailureReason::ExitCode { code: exit_code }),
+        };
+        let marked_failed = {
+            let mut triggers = self.triggers.lock().unwrap();
+            triggers
+                .remove(&run_id)
+                .is_some_and(|triggers| triggers.marked_failed)
+        };
+        let failure = failure.or(marked_failed.then_some(FailureReason::Trigger));
+        // 超出资源限制导致的失败单独报告
+        let failure = match limit_exceeded {
+            Some(resource) if failure.is_some() => {
+                log::warn!("命令 {} 超出资源限制（{:?}）被终止", command_id, resource);
+                Some(FailureReason::ResourceLimit { resource })
+            }
+            _ => failure,
+        };
+        let succeeded = failure.is_none();
+
+        // 被停止的命令以停止原因作为最终状态，并记录是否被强制终止
+        let final_status = match stop_request.as_ref().map(|r| r.reason) {
+            Some(reason) => reason.final_status(),
+            None if succeeded => CommandStatus::Success,
+            None => CommandStatus::Failed,
+        };
+        let stop_result = stop_request.as_ref().map(|r| {
+            if r.force_killed {
+                StopResult::ForceKilled
+            } else {
+                StopResult::Exited
+            }
+        });
+
+        // 计划自动重启（手动停止的命令不重启）
+        let restart = match (&command_info, stop_request.as_ref().map(|r| r.reason)) {
+            (Some(info), reason) if reason != Some(StopReason::User) => {
+                // 因不健康被停止时总是尝试重启
+                let policy = match reason {
+                    Some(StopReason::Unhealthy) => info.params.restart_policy.for_unhealthy(),
+                    _ => info.params.restart_policy.clone(),
+                };
+                let mut restarts = self.restarts.lock().unwrap();
+                let restart = restarts.entry(command_id).or_default();
+                let plan = policy.plan(
+                    final_status == CommandStatus::Success,
+                    restart.restart_count,
+                    started_at.elapsed(),
+                );
+                plan.map(|plan| {
+                    let next_retry_at = (chrono::Local::now()
+                        + chrono::Duration::from_std(plan.delay).unwrap_or_default())
+                    .to_rfc3339();
+                    restart.restart_count = plan.restart_count;
+                    restart.next_retry_at = Some(next_retry_at.clone());
+                    (info.params.clone(), plan, next_retry_at)
+                })
             }
+            _ => None,
         };
 
-        self.update_state(command_id, final_status.clone(), None, exit_code);
+        self.record_run_finish(run_id, &final_status, exit_code, started_at.elapsed());
+        // 被停止的命令不记录失败原因
+        let failure_reason = failure.filter(|_| stop_request.is_none());
+        self.update_state(
+            run_id,
+            final_status.clone(),
+            None,
+            exit_code,
+            stop_result,
+            failure_reason,
+        );
+
+        // 发送超时事件
+        if let (Some(timeout), Some(request)) = (
+            timeout,
+            stop_request.filter(|r| r.reason == StopReason::Timeout),
+        ) {
+            let event = CommandTimeoutEvent {
+                command_id,
+                run_id,
+                timeout_secs: timeout.as_secs(),
+                force_killed: request.force_killed,
+            };
+            let _ = self
+                .app_handle
+                .emit_to(EventTarget::Any, "command-timed-out", event);
+        }
 
-        // 如果配置了通知，发送通知
+        // 如果配置了通知，发送通知（即将自动重启时不通知）
         if let Some(ref info) = command_info {
-            if info.params.notification_when_finished {
+            if info.params.notification_when_finished && restart.is_none() {
                 self.send_notification(&info.params.command_name, &final_status, exit_code);
             }
         }
@@ -820,13 +2589,158 @@ impl CommandRunner {
             }
         }
 
-        // 清理命令信息
-        {
-            let mut infos = self.command_infos.lock().unwrap();
-            infos.remove(&command_id);
+        // 释放的名额交给排队中的运行
+        self.process_queue();
+
+        if let Some((params, plan, next_retry_at)) = restart {
+            self.schedule_restart(run_id, params, plan, next_retry_at);
+        }
+    }
+
+    /// 新增运行历史记录（数据库不可用时返回 None，不影响命令执行）
+    fn record_run_start(
+        &self,
+        params: &ExecuteCommandParams,
+        command_line: &str,
+        pid: u32,
+        trigger: RunTrigger,
+    ) -> Option<i64> {
+        let database = self.app_handle.try_state::<Database>()?;
+        let run = NewCommandRun {
+            command_id: params.command_id,
+            command_name: params.command_name.clone(),
+            command_text: command_line.to_string(),
+            trigger,
+            pid: Some(pid),
+            started_at: chrono::Local::now().to_rfc3339(),
+        };
+
+        match database.insert_command_run(&run) {
+            Ok(run_id) => Some(run_id),
+            Err(e) => {
+                log::error!("记录命令 {} 的运行历史失败: {}", params.command_id, e);
+                None
+            }
+        }
+    }
+
+    /// 更新运行历史记录的结束信息
+    fn record_run_finish(
+        &self,
+        run_id: i64,
+        status: &CommandStatus,
+        exit_code: Option<i32>,
+        duration: Duration,
+    ) {
+        // 临时运行 ID 没有运行历史记录
+        let Some(database) = self
+            .app_handle
+            .try_state::<Database>()
+            .filter(|_| run_id > 0)
+        else {
+            return;
+        };
+
+        // 日志摘录：最后若干行
+        let log_excerpt = {
+            let logs = self.logs.lock().unwrap();
+            logs.get(&run_id).filter(|buffer| !buffer.is_empty()).map(|buffer| {
+                buffer
+                    .tail(constants::history::LOG_EXCERPT_LINES)
+                    .map(|line| format!("[{}] {}", line.stream.as_str(), line.text))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+        };
+
+        let finish = FinishCommandRun {
+            status: status.clone(),
+            exit_code,
+            ended_at: chrono::Local::now().to_rfc3339(),
+            duration_ms: duration.as_millis() as i64,
+            log_excerpt,
+        };
+        let keep = ConfigManager::get_run_history_limit(&database);
+
+        let pruned = match database.finish_command_run(run_id, &finish, keep) {
+            Ok(pruned) => pruned,
+            Err(e) => {
+                log::error!("更新运行历史 {} 失败: {}", run_id, e);
+                Vec::new()
+            }
+        };
+
+        // 关闭本次运行的日志文件，删除被清理的运行记录的日志，并按保留策略清理旧日志
+        if let Some(store) = self.app_handle.try_state::<RunLogStore>() {
+            store.finish(run_id);
+            store.delete(&pruned);
+            store.cleanup(ConfigManager::get_run_log_retention(&database));
         }
     }
 
+    /// 等待退避时间后自动重启命令
+    fn schedule_restart(
+        &self,
+        run_id: i64,
+        params: ExecuteCommandParams,
+        plan: RestartPlan,
+        next_retry_at: String,
+    ) {
+        let command_id = params.command_id;
+        log::info!(
+            "命令 {} 将在 {:?} 后自动重启（第 {} 次）",
+            command_id,
+            plan.delay,
+            plan.restart_count
+        );
+
+        let event = CommandRestartingEvent {
+            command_id,
+            restart_count: plan.restart_count,
+            max_retries: params.restart_policy.max_retries,
+            delay_secs: plan.delay.as_secs(),
+            next_retry_at: next_retry_at.clone(),
+        };
+        let _ = self
+            .app_handle
+            .emit_to(EventTarget::Any, "command-restarting", event);
+
+        let runner = self.clone_for_thread();
+        std::thread::spawn(move || {
+            std::thread::sleep(plan.delay);
+
+            // 等待期间被手动停止或重新执行时放弃本次重启
+            let still_pending = {
+                let mut restarts = runner.restarts.lock().unwrap();
+                match restarts.get_mut(&command_id) {
+                    Some(restart) if restart.next_retry_at.as_deref() == Some(next_retry_at.as_str()) => {
+                        restart.next_retry_at = None;
+                        true
+                    }
+                    _ => false,
+                }
+            };
+            if !still_pending {
+                return;
+            }
+
+            if let Err(e) = runner.execute_after(params, RunTrigger::Restart, Some(run_id)) {
+                log::error!("自动重启命令 {} 失败: {}", command_id, e);
+                // 刷新最近一次运行的状态（已不再等待重启）
+                if let Some(state) = runner.latest_finished(command_id) {
+                    runner.update_state(
+                        state.run_id,
+                        state.status,
+                        state.pid,
+                        state.exit_code,
+                        state.stop_result,
+                        state.failure_reason,
+                    );
+                }
+            }
+        });
+    }
+
     /// 发送通知
     fn send_notification(
         &self,
@@ -834,8 +2748,6 @@ impl CommandRunner {
         status: &CommandStatus,
         exit_code: Option<i32>,
     ) {
-        use tauri_plugin_notification::NotificationExt;
-
         let (title, body) = match status {
             CommandStatus::Success => (
                 "命令执行成功",
@@ -849,10 +2761,20 @@ impl CommandRunner {
                 };
                 ("命令执行失败", msg)
             }
+            CommandStatus::TimedOut => (
+                "命令执行超时",
+                format!("命令 \"{}\" 运行超时，已被终止", command_name),
+            ),
             _ => return, // 其他状态不发送通知
         };
 
-        // 发送通知（忽略错误，避免通知失败影响主流程）
+        self.show_notification(title, body);
+    }
+
+    /// 显示桌面通知（忽略错误，避免通知失败影响主流程）
+    fn show_notification(&self, title: &str, body: String) {
+        use tauri_plugin_notification::NotificationExt;
+
         let _ = self
             .app_handle
             .notification()
@@ -867,50 +2789,203 @@ impl CommandRunner {
         Self {
             states: Arc::clone(&self.states),
             processes: Arc::clone(&self.processes),
+            inputs: Arc::clone(&self.inputs),
             command_infos: Arc::clone(&self.command_infos),
             logs: Arc::clone(&self.logs),
-            starting: Arc::clone(&self.starting),
+            stopping: Arc::clone(&self.stopping),
+            triggers: Arc::clone(&self.triggers),
+            success_checkers: Arc::clone(&self.success_checkers),
             #[cfg(target_os = "windows")]
             job_objects: Arc::clone(&self.job_objects),
+            #[cfg(target_os = "linux")]
+            cgroups: Arc::clone(&self.cgroups),
+            instances: Arc::clone(&self.instances),
+            successors: Arc::clone(&self.successors),
+            starting: Arc::clone(&self.starting),
+            restarts: Arc::clone(&self.restarts),
+            queue: Arc::clone(&self.queue),
+            launch_lock: Arc::clone(&self.launch_lock),
+            next_temp_run_id: Arc::clone(&self.next_temp_run_id),
             app_handle: self.app_handle.clone(),
         }
     }
 
+    /// 向运行实例发送输入，并以 stdin 流回显到日志
+    pub fn send_input(&self, run_id: i64, text: &str, append_newline: bool) -> Result<(), String> {
+        let result = {
+            let mut inputs = self.inputs.lock().unwrap();
+            inputs.get_mut(&run_id).map(|input| {
+                let mut data = text.to_string();
+                if append_newline {
+                    data.push_str(input.newline);
+                }
+                input
+                    .writer
+                    .write_all(data.as_bytes())
+                    .and_then(|_| input.writer.flush())
+                    .map(|_| input.echo)
+            })
+        };
+
+        match result {
+            Some(Ok(echo)) => {
+                if echo {
+                    for line in text.lines() {
+                        self.append_log(run_id, StyledLine::plain(line), LogStream::Stdin);
+                    }
+                }
+                Ok(())
+            }
+            Some(Err(e)) => Err(format!("写入命令输入失败: {}", e)),
+            None if self.processes.lock().unwrap().contains_key(&run_id) => {
+                Err("命令未开启交互输入".to_string())
+            }
+            None => Err("命令未在运行".to_string()),
+        }
+    }
+
     /// 追加日志行
-    fn append_log(&self, command_id: i64, line: String, stream: &str) {
-        let line = line.trim_end_matches(['\r', '\n']).to_string();
-        if line.trim().is_empty() {
+    fn append_log(&self, run_id: i64, line: StyledLine, stream: LogStream) {
+        if line.text.trim().is_empty() {
             return;
         }
-        let formatted_line = format!("[{}] {}", stream, line);
-        // 追加到日志缓冲
-        {
+        // 追加到日志缓冲（分配序号和时间戳）
+        let log_line = {
             let mut logs = self.logs.lock().unwrap();
-            if let Some(log_vec) = logs.get_mut(&command_id) {
-                log_vec.push(formatted_line.clone());
+            match logs.get_mut(&run_id) {
+                Some(buffer) => buffer.push(stream, line),
+                None => return,
             }
+        };
+
+        // 写入本次运行的日志文件（临时运行 ID 没有日志文件）
+        if let Some(store) = self
+            .app_handle
+            .try_state::<RunLogStore>()
+            .filter(|_| run_id > 0)
+        {
+            store.append(run_id, &log_line);
         }
 
+        // 检查输出触发器和成功判定规则（不检查用户输入的回显）
+        let fired = if stream == LogStream::Stdin {
+            Vec::new()
+        } else {
+            {
+                let mut checkers = self.success_checkers.lock().unwrap();
+                if let Some(checker) = checkers.get_mut(&run_id) {
+                    checker.observe(stream, &log_line.text);
+                }
+            }
+            self.fire_triggers(run_id, &log_line.text)
+        };
+
         // 发送日志更新事件（广播到所有窗口）
-        let log_line = LogLine {
+        let _ = self
+            .app_handle
+            .emit_to(EventTarget::Any, "command-log-update", &log_line);
+
+        for trigger in fired {
+            self.run_trigger_actions(run_id, &log_line, trigger);
+        }
+    }
+
+    /// 匹配输出触发器，返回触发的触发器（同时记录标记失败的动作）
+    fn fire_triggers(&self, run_id: i64, line: &str) -> Vec<FiredTrigger> {
+        let mut triggers = self.triggers.lock().unwrap();
+        let Some(run) = triggers.get_mut(&run_id) else {
+            return Vec::new();
+        };
+
+        let fired: Vec<FiredTrigger> = run
+            .set
+            .fire(line, Instant::now())
+            .into_iter()
+            .map(|index| FiredTrigger {
+                index,
+                pattern: run.set.get(index).pattern.clone(),
+                actions: run.set.get(index).actions.clone(),
+                command_name: run.command_name.clone(),
+                url: run.url.clone(),
+            })
+            .collect();
+        if fired.iter().any(|trigger| trigger.actions.contains(&TriggerAction::MarkFailed)) {
+            run.marked_failed = true;
+        }
+        fired
+    }
+
+    /// 执行触发器的动作
+    fn run_trigger_actions(&self, run_id: i64, log_line: &LogLine, trigger: FiredTrigger) {
+        let (command_id, line) = (log_line.command_id, log_line.text.as_str());
+        log::info!(
+            "命令 {} 的输出匹配触发器 {}（{}）",
+            command_id,
+            trigger.index + 1,
+            trigger.pattern
+        );
+        let event = CommandTriggerEvent {
             command_id,
-            line: formatted_line,
-            stream: stream.to_string(),
+            run_id,
+            trigger: trigger.index,
+            pattern: trigger.pattern.clone(),
+            line: line.to_string(),
         };
         let _ = self
             .app_handle
-            .emit_to(EventTarget::Any, "command-log-update", log_line);
+            .emit_to(EventTarget::Any, "command-trigger-fired", event);
+
+        for action in &trigger.actions {
+            match action {
+                TriggerAction::Notify { message } => {
+                    let body = match message.as_deref().map(str::trim) {
+                        Some(message) if !message.is_empty() => message.to_string(),
+                        _ => line.to_string(),
+                    };
+                    self.show_notification(&trigger.command_name, body);
+                }
+                // 已在匹配时记录，运行结束时生效
+                TriggerAction::MarkFailed => {}
+                // 与手动停止本次运行相同（不自动重启），但保留日志窗口
+                TriggerAction::Stop => {
+                    if let Ok(true) = self.register_stop(run_id, StopReason::User) {
+                        self.begin_stop(run_id, StopReason::User);
+                    }
+                }
+                TriggerAction::RunCommand {
+                    command_id: target_id,
+                } => {
+                    let runner = self.clone_for_thread();
+                    let target_id = *target_id;
+                    std::thread::spawn(move || {
+                        let Some(database) = runner.app_handle.try_state::<Database>() else {
+                            return;
+                        };
+                        let result = database.get_command_by_id(target_id).and_then(|command| {
+                            let params = ExecuteCommandParams::from_command(command, HashMap::new());
+                            runner.execute(params, RunTrigger::Trigger)
+                        });
+                        if let Err(e) = result {
+                            log::warn!("触发器执行命令 {} 失败: {}", target_id, e);
+                        }
+                    });
+                }
+                TriggerAction::OpenUrl => self.open_url(trigger.url.as_deref()),
+            }
+        }
     }
 
     fn read_stream_to_logs<R: Read>(
         &self,
-        command_id: i64,
+        run_id: i64,
         mut reader: R,
-        stream: &str,
+        stream: LogStream,
         line_prefix: Option<&'static str>,
     ) {
         let mut buffer = [0u8; 4096];
         let mut pending: Vec<u8> = Vec::new();
+        // ANSI 样式会跨行保留，每个输出流使用独立的解析器
+        let mut parser = AnsiParser::new();
 
         loop {
             match reader.read(&mut buffer) {
@@ -918,13 +2993,13 @@ impl CommandRunner {
                 Ok(n) => {
                     pending.extend_from_slice(&buffer[..n]);
                     for line_bytes in drain_pending_lines(&mut pending) {
-                        let mut line = decode_output_bytes(&line_bytes);
+                        let mut line = parser.parse(&decode_output_bytes(&line_bytes));
                         if let Some(prefix) = line_prefix {
-                            if !line.trim().is_empty() {
-                                line = format!("{}{}", prefix, line);
+                            if !line.text.trim().is_empty() {
+                                line.prepend(prefix);
                             }
                         }
-                        self.append_log(command_id, line, stream);
+                        self.append_log(run_id, line, stream);
                     }
                 }
                 Err(_) => break,
@@ -932,38 +3007,128 @@ impl CommandRunner {
         }
 
         if !pending.is_empty() {
-            let mut line = decode_output_bytes(&pending);
+            let mut line = parser.parse(&decode_output_bytes(&pending));
             if let Some(prefix) = line_prefix {
-                if !line.trim().is_empty() {
-                    line = format!("{}{}", prefix, line);
+                if !line.text.trim().is_empty() {
+                    line.prepend(prefix);
                 }
             }
-            self.append_log(command_id, line, stream);
+            self.append_log(run_id, line, stream);
         }
     }
 
-    /// 获取命令日志
-    pub fn get_logs(&self, command_id: i64) -> Vec<String> {
+    /// 分页获取命令所有运行实例的日志（按序号合并，未指定游标时返回最新的 `limit` 行）
+    pub fn get_logs(&self, command_id: i64, cursor: Option<u64>, limit: usize) -> LogPage {
+        let run_ids = self.instance_ids(command_id);
+        let logs = self.logs.lock().unwrap();
+        merged_page(run_ids.iter().filter_map(|run_id| logs.get(run_id)), cursor, limit)
+    }
+
+    /// 分页获取运行实例的日志
+    pub fn get_instance_logs(
+        &self,
+        run_id: i64,
+        cursor: Option<u64>,
+        limit: usize,
+    ) -> Result<LogPage, String> {
         let logs = self.logs.lock().unwrap();
-        logs.get(&command_id).cloned().unwrap_or_default()
+        logs.get(&run_id)
+            .map(|buffer| buffer.page(cursor, limit))
+            .ok_or_else(|| "运行实例不存在".to_string())
     }
 
-    /// 清空命令日志
+    /// 清空命令所有运行实例的日志
     pub fn clear_logs(&self, command_id: i64) {
+        let run_ids = self.instance_ids(command_id);
         let mut logs = self.logs.lock().unwrap();
-        if let Some(log_vec) = logs.get_mut(&command_id) {
-            log_vec.clear();
+        for run_id in run_ids {
+            if let Some(buffer) = logs.get_mut(&run_id) {
+                buffer.clear();
+            }
         }
     }
 
     /// 检查命令是否有日志
     #[allow(dead_code)]
     pub fn has_logs(&self, command_id: i64) -> bool {
+        let run_ids = self.instance_ids(command_id);
         let logs = self.logs.lock().unwrap();
-        logs.get(&command_id)
-            .map(|v| !v.is_empty())
-            .unwrap_or(false)
+        run_ids
+            .iter()
+            .any(|run_id| logs.get(run_id).is_some_and(|buffer| !buffer.is_empty()))
+    }
+}
+
+/// 构建直接执行程序的进程
+fn program_command(program: &str, args: &[String]) -> Command {
+    let mut c = Command::new(program);
+    c.args(args);
+    // Windows 进程创建标志：
+    // CREATE_NEW_PROCESS_GROUP = 0x00000200
+    // CREATE_NO_WINDOW = 0x08000000
+    #[cfg(target_os = "windows")]
+    c.creation_flags(0x00000200 | 0x08000000);
+    c
+}
+
+/// 构建通过系统 shell 执行命令行的进程
+fn shell_command(command_line: &str) -> Command {
+    #[cfg(target_os = "windows")]
+    {
+        let mut c = Command::new("cmd");
+        c.args(["/C", command_line]);
+        // Windows 进程创建标志：
+        // CREATE_NEW_PROCESS_GROUP = 0x00000200
+        // CREATE_NO_WINDOW = 0x08000000
+        c.creation_flags(0x00000200 | 0x08000000);
+        c
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let mut c = Command::new("sh");
+        c.args(["-c", command_line]);
+        c
+    }
+}
+
+/// 解析命令最终使用的环境变量（.env 文件相对于工作目录解析）
+fn resolve_command_env(params: &ExecuteCommandParams) -> Result<BTreeMap<String, String>, String> {
+    if params.env_files.is_empty() {
+        return Ok(params.env.clone());
+    }
+
+    let base_dir = match params.working_directory.as_deref().filter(|wd| !wd.is_empty()) {
+        Some(wd) => {
+            let path = PathBuf::from(wd);
+            if !path.is_dir() {
+                return Err(format!("工作目录不存在: {:?}", wd));
+            }
+            path
+        }
+        None => std::env::current_dir().map_err(|e| format!("无法获取当前工作目录: {}", e))?,
+    };
+
+    let mut env = dotenv::load_env_files(&params.env_files, &base_dir)?;
+    env.extend(params.env.clone());
+    Ok(env)
+}
+
+/// 进程组的资源限制（Job Object 或 cgroup）在其中的进程全部退出后再释放
+///
+/// 已没有进程时直接释放，否则在后台定期检查。
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+fn release_when_empty<T: Send + 'static>(resource: T, is_empty: fn(&T) -> bool) {
+    if is_empty(&resource) {
+        return;
     }
+    std::thread::spawn(move || {
+        let interval = Duration::from_secs(constants::command::LEFTOVER_PROCESS_CHECK_INTERVAL_SECS);
+        while !is_empty(&resource) {
+            std::thread::sleep(interval);
+        }
+        drop(resource);
+    });
 }
 
 fn find_line_ending(bytes: &[u8]) -> Option<(usize, usize)> {
@@ -1063,3 +3228,23 @@ fn decode_output_bytes(bytes: &[u8]) -> String {
         String::from_utf8_lossy(bytes).to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeout_stops_gracefully_and_times_out() {
+        // 超时不使用命令配置的停止方式，先请求优雅退出
+        for mode in [StopMode::Graceful, StopMode::Kill, StopMode::Command] {
+            assert_eq!(StopReason::Timeout.stop_mode(mode), StopMode::Graceful);
+        }
+        assert_eq!(StopReason::Timeout.final_status(), CommandStatus::TimedOut);
+
+        // 其他原因按配置的停止方式停止
+        assert_eq!(StopReason::User.stop_mode(StopMode::Kill), StopMode::Kill);
+        assert_eq!(StopReason::User.stop_mode(StopMode::Command), StopMode::Command);
+        assert_eq!(StopReason::User.final_status(), CommandStatus::Stopped);
+        assert_eq!(StopReason::Unhealthy.final_status(), CommandStatus::Failed);
+    }
+}
@@ -1,11 +1,36 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, EventTarget, Manager, State};
+
 use crate::autostart;
-use crate::command_runner::{CommandRunner, CommandState, ExecuteCommandParams};
+use crate::command_params::{self, CommandParameter};
+use crate::command_runner::{
+    CommandRunner, CommandState, ExecuteCommandParams, QueuedRun, RunTrigger,
+};
 use crate::constants;
-use crate::db::{self, CreateCommandInput, Database, UpdateCommandInput};
+use crate::db::{
+    self, CommandRunFilter, CommandRunPage, CreateCommandInput, CreatePipelineInput, Database,
+    Pipeline, UpdateCommandInput, UpdatePipelineInput,
+};
+use crate::health::HealthCheckConfig;
 use crate::i18n::{get_language_from_db, Translations};
-use crate::monitor::{DiskInfo, DiskMonitorState, MonitorState, SystemInfo};
-use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Emitter, EventTarget, Manager, State};
+use crate::log_buffer::LogPage;
+use crate::monitor::{CommandMetrics, DiskInfo, DiskMonitorState, MonitorState, SystemInfo};
+use crate::pipeline::{self, PipelineStep};
+use crate::pipeline_runner::{PipelineRunState, PipelineRunner};
+use crate::process_tree::StopMode;
+use crate::pty::PtyConfig;
+use crate::readiness::ReadinessConfig;
+use crate::resource_limits::ResourceLimits;
+use crate::restart::RestartPolicy;
+use crate::run_log::{RunLogPage, RunLogStore};
+use crate::run_queue::ConcurrencyLimits;
+use crate::schedule::{CommandSchedule, Schedule};
+use crate::scheduler::{CommandScheduler, ScheduleStatus};
+use crate::shell::Shell;
+use crate::success::SuccessRule;
+use crate::trigger::{self, OutputTrigger};
 
 // ==================== 系统监控命令 ====================
 
@@ -33,18 +58,52 @@ pub fn get_disk_info(app: AppHandle) -> Result<DiskInfo, String> {
     Ok(disk_info.clone())
 }
 
+/// 获取运行中命令的资源占用（每秒更新，同时通过 command-metrics 事件推送）
+#[tauri::command]
+pub fn get_command_metrics(app: AppHandle) -> Result<Vec<CommandMetrics>, String> {
+    let state = app.state::<MonitorState>();
+    let command_metrics = state
+        .command_metrics
+        .lock()
+        .map_err(|e| format!("获取命令资源占用失败: {}", e))?;
+
+    Ok(command_metrics.clone())
+}
+
 // ==================== 数据库命令 ====================
 
 /// 创建命令
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn create_command(
     database: State<Database>,
+    scheduler: State<CommandScheduler>,
     name: String,
     command: String,
     sudo: bool,
     working_directory: Option<String>,
     url: Option<String>,
     notification_when_finished: bool,
+    env: Option<BTreeMap<String, String>>,
+    env_files: Option<Vec<String>>,
+    parameters: Option<Vec<CommandParameter>>,
+    timeout_secs: Option<u64>,
+    graceful_timeout_secs: Option<u64>,
+    stop_mode: Option<StopMode>,
+    stop_command: Option<String>,
+    restart_policy: Option<RestartPolicy>,
+    force_color: Option<bool>,
+    pty: Option<PtyConfig>,
+    interactive: Option<bool>,
+    schedule: Option<CommandSchedule>,
+    readiness: Option<ReadinessConfig>,
+    health_check: Option<HealthCheckConfig>,
+    output_triggers: Option<Vec<OutputTrigger>>,
+    success_rule: Option<SuccessRule>,
+    shell: Option<Shell>,
+    max_instances: Option<u32>,
+    concurrency_group: Option<String>,
+    resource_limits: Option<ResourceLimits>,
 ) -> Result<db::Command, String> {
     let input = CreateCommandInput {
         name,
@@ -53,8 +112,36 @@ pub fn create_command(
         working_directory,
         url,
         notification_when_finished,
+        env: env.unwrap_or_default(),
+        env_files: env_files.unwrap_or_default(),
+        parameters: parameters.unwrap_or_default(),
+        timeout_secs,
+        graceful_timeout_secs,
+        stop_mode: stop_mode.unwrap_or_default(),
+        stop_command,
+        restart_policy: restart_policy.unwrap_or_default(),
+        force_color: force_color.unwrap_or_default(),
+        pty: pty.unwrap_or_default(),
+        interactive: interactive.unwrap_or_default(),
+        schedule: schedule.unwrap_or_default(),
+        readiness: readiness.unwrap_or_default(),
+        health_check: health_check.unwrap_or_default(),
+        output_triggers: output_triggers.unwrap_or_default(),
+        success_rule: success_rule.unwrap_or_default(),
+        shell: shell.unwrap_or_default(),
+        max_instances,
+        concurrency_group,
+        resource_limits: resource_limits.unwrap_or_default(),
     };
-    database.create_command(input)
+    input.schedule.validate(&input.command, &input.parameters)?;
+    input.readiness.validate(input.url.as_deref())?;
+    input.health_check.validate(input.url.as_deref())?;
+    trigger::validate_triggers(&input.output_triggers, None)?;
+    input.success_rule.validate()?;
+    input.resource_limits.validate()?;
+    let command = database.create_command(input)?;
+    scheduler.reload(&database);
+    Ok(command)
 }
 
 /// 获取所有命令
@@ -71,8 +158,10 @@ pub fn get_command_by_id(database: State<Database>, id: i64) -> Result<db::Comma
 
 /// 更新命令
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn update_command(
     database: State<Database>,
+    scheduler: State<CommandScheduler>,
     id: i64,
     name: Option<String>,
     command: Option<String>,
@@ -80,6 +169,26 @@ pub fn update_command(
     working_directory: Option<String>,
     url: Option<String>,
     notification_when_finished: Option<bool>,
+    env: Option<BTreeMap<String, String>>,
+    env_files: Option<Vec<String>>,
+    parameters: Option<Vec<CommandParameter>>,
+    timeout_secs: Option<u64>,
+    graceful_timeout_secs: Option<u64>,
+    stop_mode: Option<StopMode>,
+    stop_command: Option<String>,
+    restart_policy: Option<RestartPolicy>,
+    force_color: Option<bool>,
+    pty: Option<PtyConfig>,
+    interactive: Option<bool>,
+    schedule: Option<CommandSchedule>,
+    readiness: Option<ReadinessConfig>,
+    health_check: Option<HealthCheckConfig>,
+    output_triggers: Option<Vec<OutputTrigger>>,
+    success_rule: Option<SuccessRule>,
+    shell: Option<Shell>,
+    max_instances: Option<u32>,
+    concurrency_group: Option<String>,
+    resource_limits: Option<ResourceLimits>,
 ) -> Result<(), String> {
     let input = UpdateCommandInput {
         name,
@@ -88,14 +197,99 @@ pub fn update_command(
         working_directory,
         url,
         notification_when_finished,
+        env,
+        env_files,
+        parameters,
+        timeout_secs,
+        graceful_timeout_secs,
+        stop_mode,
+        stop_command,
+        restart_policy,
+        force_color,
+        pty,
+        interactive,
+        schedule,
+        readiness,
+        health_check,
+        output_triggers,
+        success_rule,
+        shell,
+        max_instances,
+        concurrency_group,
+        resource_limits,
     };
-    database.update_command(id, input)
+    // 修改命令内容或参数也可能使已启用的定时执行缺少参数值
+    if input.schedule.is_some() || input.command.is_some() || input.parameters.is_some() {
+        let saved = database.get_command_by_id(id)?;
+        input.schedule.as_ref().unwrap_or(&saved.schedule).validate(
+            input.command.as_deref().unwrap_or(&saved.command),
+            input.parameters.as_deref().unwrap_or(&saved.parameters),
+        )?;
+    }
+    // 修改 URL 也可能使已保存的 HTTP 就绪检查或健康检查失去地址
+    if input.readiness.is_some() || input.health_check.is_some() || input.url.is_some() {
+        let saved = database.get_command_by_id(id)?;
+        let url = input.url.as_deref().or(saved.url.as_deref());
+        input.readiness.as_ref().unwrap_or(&saved.readiness).validate(url)?;
+        input.health_check.as_ref().unwrap_or(&saved.health_check).validate(url)?;
+    }
+    if let Some(output_triggers) = &input.output_triggers {
+        trigger::validate_triggers(output_triggers, Some(id))?;
+    }
+    if let Some(success_rule) = &input.success_rule {
+        success_rule.validate()?;
+    }
+    if let Some(resource_limits) = &input.resource_limits {
+        resource_limits.validate()?;
+    }
+    database.update_command(id, input)?;
+    scheduler.reload(&database);
+    Ok(())
+}
+
+/// 获取命令的参数定义（用于执行前提示用户填写）
+#[tauri::command]
+pub fn get_command_parameters(
+    database: State<Database>,
+    id: i64,
+) -> Result<Vec<CommandParameter>, String> {
+    let command = database.get_command_by_id(id)?;
+    command_params::resolve_parameters(&command.command, &command.parameters)
 }
 
-/// 删除命令
+/// 删除命令（先停止其运行实例，同时删除运行日志文件）
+///
+/// 命令被流水线步骤或其他命令的输出触发器引用时拒绝删除。
 #[tauri::command]
-pub fn delete_command(database: State<Database>, id: i64) -> Result<(), String> {
-    database.delete_command(id)
+pub fn delete_command(
+    database: State<Database>,
+    scheduler: State<CommandScheduler>,
+    runner: State<CommandRunner>,
+    run_log_store: State<RunLogStore>,
+    id: i64,
+) -> Result<(), String> {
+    let pipelines = database.get_all_pipelines()?;
+    if let Some(pipeline) = pipelines
+        .iter()
+        .find(|pipeline| pipeline.steps.iter().any(|step| step.command_id == id))
+    {
+        return Err(format!("命令被流水线 {} 使用，无法删除", pipeline.name));
+    }
+    let commands = database.get_all_commands()?;
+    if let Some(command) = commands.iter().find(|command| {
+        command.id != id
+            && trigger::run_command_targets(&command.output_triggers).any(|target| target == id)
+    }) {
+        return Err(format!("命令被命令 {} 的输出触发器执行，无法删除", command.name));
+    }
+
+    // 停止运行实例，取消排队中的运行和等待中的自动重启（未在运行时忽略错误）
+    let _ = runner.stop(id);
+
+    let run_ids = database.delete_command(id)?;
+    run_log_store.delete(&run_ids);
+    scheduler.reload(&database);
+    Ok(())
 }
 
 /// 更新命令排序
@@ -118,7 +312,28 @@ pub fn set_system_config(
     key: String,
     value: String,
 ) -> Result<(), String> {
+    if key == constants::config_keys::DEFAULT_SHELL {
+        Shell::parse(&value)?;
+    }
+    if key == constants::config_keys::MAX_CONCURRENT_RUNS && !value.trim().is_empty() {
+        value
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| "无效的最大并发运行数".to_string())?;
+    }
+    if key == constants::config_keys::CONCURRENCY_GROUPS {
+        ConcurrencyLimits::parse_groups(&value)?;
+    }
     database.set_config(&key, &value)?;
+
+    // 并发限制变化后启动有名额的排队运行
+    if key == constants::config_keys::MAX_CONCURRENT_RUNS
+        || key == constants::config_keys::CONCURRENCY_GROUPS
+    {
+        if let Some(runner) = app.try_state::<CommandRunner>() {
+            runner.process_queue();
+        }
+    }
     
     // 如果设置的是语言配置，广播语言变化事件到所有窗口
     if key == constants::config_keys::LANGUAGE {
@@ -138,31 +353,28 @@ pub fn get_all_system_configs(
 
 // ==================== 命令执行相关命令 ====================
 
-/// 执行命令
+/// 执行命令，返回本次运行的运行 ID（超出并发限制进入队列时返回排队 ID）
 #[tauri::command]
 pub async fn execute_command(
     command_id: i64,
+    parameter_values: Option<HashMap<String, serde_json::Value>>,
     db: State<'_, Database>,
     runner: State<'_, CommandRunner>,
-) -> Result<(), String> {
+) -> Result<i64, String> {
     // 从数据库获取命令详情
     let command = db.get_command_by_id(command_id)?;
 
     // 构建执行参数
-    let params = ExecuteCommandParams {
-        command_id,
-        command_name: command.name,
-        command: command.command,
-        sudo: command.sudo,
-        working_directory: command.working_directory,
-        notification_when_finished: command.notification_when_finished,
-    };
+    let params = ExecuteCommandParams::from_command(
+        command,
+        command_params::normalize_values(parameter_values.unwrap_or_default()),
+    );
 
     // 执行命令
-    runner.execute(params)
+    runner.execute(params, RunTrigger::Manual)
 }
 
-/// 停止命令
+/// 停止命令（所有运行实例）
 #[tauri::command]
 pub async fn stop_command(
     command_id: i64,
@@ -171,6 +383,60 @@ pub async fn stop_command(
     runner.stop(command_id)
 }
 
+/// 停止命令的单个运行实例（排队中的运行直接取消）
+#[tauri::command]
+pub async fn stop_command_instance(
+    run_id: i64,
+    runner: State<'_, CommandRunner>,
+) -> Result<(), String> {
+    runner.stop_instance(run_id)
+}
+
+/// 向运行中的命令发送输入（需开启交互输入，默认追加换行）
+///
+/// 未指定运行 ID 时发送给最近启动的运行实例。
+#[tauri::command]
+pub fn send_command_input(
+    command_id: i64,
+    text: String,
+    append_newline: Option<bool>,
+    run_id: Option<i64>,
+    runner: State<CommandRunner>,
+) -> Result<(), String> {
+    let run_id = match run_id {
+        Some(run_id) => run_id,
+        None => runner
+            .get_state(command_id)
+            .map(|state| state.run_id)
+            .ok_or_else(|| "命令未在运行".to_string())?,
+    };
+    runner.send_input(run_id, &text, append_newline.unwrap_or(true))
+}
+
+// ==================== 定时执行相关命令 ====================
+
+/// 获取所有定时执行命令的状态（包括下一次执行时间）
+#[tauri::command]
+pub fn get_command_schedules(
+    scheduler: State<CommandScheduler>,
+) -> Result<Vec<ScheduleStatus>, String> {
+    Ok(scheduler.get_statuses())
+}
+
+/// 预览执行计划表达式接下来的若干次执行时间（RFC 3339）
+#[tauri::command]
+pub fn preview_schedule(expression: String, count: Option<usize>) -> Result<Vec<String>, String> {
+    let schedule = Schedule::parse(&expression)?;
+    Ok(schedule
+        .upcoming(
+            chrono::Local::now(),
+            count.unwrap_or(constants::schedule::DEFAULT_PREVIEW_COUNT),
+        )
+        .iter()
+        .map(|time| time.to_rfc3339())
+        .collect())
+}
+
 /// 获取单个命令的状态
 #[tauri::command]
 pub async fn get_command_state(
@@ -188,17 +454,215 @@ pub async fn get_all_command_states(
     Ok(runner.get_all_states())
 }
 
+/// 获取命令的所有运行实例（按启动顺序）
+#[tauri::command]
+pub async fn get_command_instances(
+    command_id: i64,
+    runner: State<'_, CommandRunner>,
+) -> Result<Vec<CommandState>, String> {
+    Ok(runner.get_instances(command_id))
+}
+
+/// 获取运行队列（按排队顺序）
+#[tauri::command]
+pub async fn get_run_queue(runner: State<'_, CommandRunner>) -> Result<Vec<QueuedRun>, String> {
+    Ok(runner.get_queue())
+}
+
+/// 取消排队中的运行
+#[tauri::command]
+pub async fn cancel_queued_run(
+    queue_id: i64,
+    runner: State<'_, CommandRunner>,
+) -> Result<(), String> {
+    runner.cancel_queued(queue_id)
+}
+
+/// 调整排队中的运行的位置（从 1 开始）
+#[tauri::command]
+pub async fn move_queued_run(
+    queue_id: i64,
+    position: usize,
+    runner: State<'_, CommandRunner>,
+) -> Result<(), String> {
+    runner.move_queued(queue_id, position)
+}
+
+// ==================== 流水线相关命令 ====================
+
+/// 校验流水线步骤（结构合法且引用的命令存在）
+fn validate_pipeline_steps(database: &Database, steps: &[PipelineStep]) -> Result<(), String> {
+    pipeline::validate_steps(steps)?;
+    for step in steps {
+        database
+            .get_command_by_id(step.command_id)
+            .map_err(|_| format!("流水线引用的命令 {} 不存在", step.command_id))?;
+    }
+    Ok(())
+}
+
+/// 创建流水线
+#[tauri::command]
+pub fn create_pipeline(
+    database: State<Database>,
+    name: String,
+    steps: Vec<PipelineStep>,
+) -> Result<Pipeline, String> {
+    validate_pipeline_steps(&database, &steps)?;
+    database.create_pipeline(CreatePipelineInput { name, steps })
+}
+
+/// 获取所有流水线
+#[tauri::command]
+pub fn get_all_pipelines(database: State<Database>) -> Result<Vec<Pipeline>, String> {
+    database.get_all_pipelines()
+}
+
+/// 更新流水线
+#[tauri::command]
+pub fn update_pipeline(
+    database: State<Database>,
+    id: i64,
+    name: Option<String>,
+    steps: Option<Vec<PipelineStep>>,
+) -> Result<(), String> {
+    if let Some(steps) = &steps {
+        validate_pipeline_steps(&database, steps)?;
+    }
+    database.update_pipeline(id, UpdatePipelineInput { name, steps })
+}
+
+/// 删除流水线（运行中的流水线需要先停止）
+#[tauri::command]
+pub fn delete_pipeline(
+    database: State<Database>,
+    pipelines: State<PipelineRunner>,
+    id: i64,
+) -> Result<(), String> {
+    if pipelines.is_running(id) {
+        return Err("流水线正在运行，请先停止".to_string());
+    }
+    database.delete_pipeline(id)
+}
+
+/// 运行流水线
+#[tauri::command]
+pub fn run_pipeline(
+    pipeline_id: i64,
+    database: State<Database>,
+    pipelines: State<PipelineRunner>,
+) -> Result<(), String> {
+    let pipeline = database.get_pipeline_by_id(pipeline_id)?;
+    pipelines.run(pipeline)
+}
+
+/// 停止流水线（停止所有步骤）
+#[tauri::command]
+pub fn stop_pipeline(pipeline_id: i64, pipelines: State<PipelineRunner>) -> Result<(), String> {
+    pipelines.stop(pipeline_id)
+}
+
+/// 获取所有流水线最近一次的运行状态
+#[tauri::command]
+pub fn get_pipeline_states(pipelines: State<PipelineRunner>) -> Result<Vec<PipelineRunState>, String> {
+    Ok(pipelines.get_states())
+}
+
+// ==================== 运行历史相关命令 ====================
+
+/// 分页查询运行历史
+#[tauri::command]
+pub fn get_command_runs(
+    database: State<Database>,
+    filter: Option<CommandRunFilter>,
+    offset: Option<i64>,
+    limit: Option<i64>,
+) -> Result<CommandRunPage, String> {
+    database.get_command_runs(
+        &filter.unwrap_or_default(),
+        offset.unwrap_or(0),
+        limit.unwrap_or(constants::history::DEFAULT_PAGE_SIZE),
+    )
+}
+
+/// 删除指定的运行记录（同时删除运行日志文件，仍在运行的记录不删除）
+#[tauri::command]
+pub fn delete_command_runs(
+    database: State<Database>,
+    run_log_store: State<RunLogStore>,
+    run_ids: Vec<i64>,
+) -> Result<usize, String> {
+    let deleted = database.delete_command_runs(&run_ids)?;
+    run_log_store.delete(&deleted);
+    Ok(deleted.len())
+}
+
+/// 清空运行记录（不指定命令时清空全部，同时删除运行日志文件，仍在运行的记录不删除）
+#[tauri::command]
+pub fn clear_command_runs(
+    database: State<Database>,
+    run_log_store: State<RunLogStore>,
+    command_id: Option<i64>,
+) -> Result<usize, String> {
+    let run_ids = database.clear_command_runs(command_id)?;
+    run_log_store.delete(&run_ids);
+    Ok(run_ids.len())
+}
+
 // ==================== 导入导出相关命令 ====================
 
-/// 导出用的命令数据结构（不包含 id, sort_order, created_at, updated_at）
+/// 导出用的命令数据结构（不包含 sort_order, created_at, updated_at）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExportCommand {
+    /// 导出时的命令 ID（导入时用于重新映射输出触发器执行的命令）
+    #[serde(default)]
+    pub id: Option<i64>,
     pub name: String,
     pub command: String,
     pub sudo: bool,
     pub working_directory: Option<String>,
     pub url: Option<String>,
     pub notification_when_finished: bool,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub env_files: Vec<String>,
+    #[serde(default)]
+    pub parameters: Vec<CommandParameter>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub graceful_timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub stop_mode: StopMode,
+    #[serde(default)]
+    pub stop_command: Option<String>,
+    #[serde(default)]
+    pub restart_policy: RestartPolicy,
+    #[serde(default)]
+    pub force_color: bool,
+    #[serde(default)]
+    pub pty: PtyConfig,
+    #[serde(default)]
+    pub interactive: bool,
+    #[serde(default)]
+    pub schedule: CommandSchedule,
+    #[serde(default)]
+    pub readiness: ReadinessConfig,
+    #[serde(default)]
+    pub health_check: HealthCheckConfig,
+    #[serde(default)]
+    pub output_triggers: Vec<OutputTrigger>,
+    #[serde(default)]
+    pub success_rule: SuccessRule,
+    #[serde(default)]
+    pub shell: Shell,
+    #[serde(default)]
+    pub max_instances: Option<u32>,
+    #[serde(default)]
+    pub concurrency_group: Option<String>,
+    #[serde(default)]
+    pub resource_limits: ResourceLimits,
 }
 
 /// 导入结果
@@ -226,12 +690,33 @@ pub fn export_commands(database: State<Database>) -> Result<String, String> {
     let export_commands: Vec<ExportCommand> = commands
         .into_iter()
         .map(|cmd| ExportCommand {
+            id: Some(cmd.id),
             name: cmd.name,
             command: cmd.command,
             sudo: cmd.sudo,
             working_directory: cmd.working_directory,
             url: cmd.url,
             notification_when_finished: cmd.notification_when_finished,
+            env: cmd.env,
+            env_files: cmd.env_files,
+            parameters: cmd.parameters,
+            timeout_secs: cmd.timeout_secs,
+            graceful_timeout_secs: cmd.graceful_timeout_secs,
+            stop_mode: cmd.stop_mode,
+            stop_command: cmd.stop_command,
+            restart_policy: cmd.restart_policy,
+            force_color: cmd.force_color,
+            pty: cmd.pty,
+            interactive: cmd.interactive,
+            schedule: cmd.schedule,
+            readiness: cmd.readiness,
+            health_check: cmd.health_check,
+            output_triggers: cmd.output_triggers,
+            success_rule: cmd.success_rule,
+            shell: cmd.shell,
+            max_instances: cmd.max_instances,
+            concurrency_group: cmd.concurrency_group,
+            resource_limits: cmd.resource_limits,
         })
         .collect();
 
@@ -240,61 +725,99 @@ pub fn export_commands(database: State<Database>) -> Result<String, String> {
         .map_err(|e| format!("序列化命令失败: {}", e))
 }
 
+/// 校验导入的命令（与创建命令时的校验相同）
+fn validate_import(cmd: &ExportCommand) -> Result<(), String> {
+    if cmd.name.trim().is_empty() {
+        return Err("命令名称不能为空".to_string());
+    }
+    if cmd.command.trim().is_empty() {
+        return Err("命令内容不能为空".to_string());
+    }
+    cmd.schedule.validate(&cmd.command, &cmd.parameters)?;
+    cmd.readiness.validate(cmd.url.as_deref())?;
+    cmd.health_check.validate(cmd.url.as_deref())?;
+    trigger::validate_triggers(&cmd.output_triggers, cmd.id)?;
+    cmd.success_rule.validate()?;
+    cmd.resource_limits.validate()
+}
+
 /// 导入命令
+///
+/// 输出触发器执行的命令按导出时的命令 ID 重新映射为导入后（或已存在的相同命令）的 ID，
+/// 引用的命令不在导入数据中时不导入该命令。
 #[tauri::command]
-pub fn import_commands(database: State<Database>, json_data: String) -> Result<ImportResult, String> {
+pub fn import_commands(
+    database: State<Database>,
+    scheduler: State<CommandScheduler>,
+    json_data: String,
+) -> Result<ImportResult, String> {
     // 解析 JSON
     let import_commands: Vec<ExportCommand> = serde_json::from_str(&json_data)
         .map_err(|e| format!("解析 JSON 失败: {}", e))?;
 
     let mut success_count = 0;
-    let mut skip_count = 0;
     let mut failed_items = Vec::new();
+    // 导出时的命令 ID -> 当前数据库中的命令 ID
+    let mut ids: HashMap<i64, i64> = HashMap::new();
 
-    // 逐条导入
+    // 校验并检查是否重复（已存在的命令映射到现有的命令）
+    let mut pending = Vec::new();
     for (index, cmd) in import_commands.into_iter().enumerate() {
-        // 验证必填字段
-        if cmd.name.trim().is_empty() {
-            failed_items.push(FailedItem {
-                index,
-                reason: "命令名称不能为空".to_string(),
-            });
-            skip_count += 1;
-            continue;
+        let existing = validate_import(&cmd).and_then(|_| {
+            database
+                .find_command_id(&cmd.name, &cmd.command)
+                .map_err(|e| format!("检查重复失败: {}", e))
+        });
+        match existing {
+            Ok(Some(existing_id)) => {
+                if let Some(id) = cmd.id {
+                    ids.insert(id, existing_id);
+                }
+                failed_items.push(FailedItem {
+                    index,
+                    reason: "命令已存在（名称和内容重复）".to_string(),
+                });
+            }
+            Ok(None) => pending.push((index, cmd)),
+            Err(reason) => failed_items.push(FailedItem { index, reason }),
         }
+    }
 
-        if cmd.command.trim().is_empty() {
+    // 输出触发器执行的命令必须在导入数据中（不导入的命令可能又被其他命令引用，重复检查）
+    loop {
+        let available: HashSet<i64> = ids
+            .keys()
+            .copied()
+            .chain(pending.iter().filter_map(|(_, cmd)| cmd.id))
+            .collect();
+        let (rejected, kept): (Vec<_>, Vec<_>) = pending.into_iter().partition(|(_, cmd)| {
+            trigger::run_command_targets(&cmd.output_triggers).any(|id| !available.contains(&id))
+        });
+        pending = kept;
+        if rejected.is_empty() {
+            break;
+        }
+        for (index, _) in rejected {
             failed_items.push(FailedItem {
                 index,
-                reason: "命令内容不能为空".to_string(),
+                reason: "输出触发器执行的命令不在导入数据中".to_string(),
             });
-            skip_count += 1;
-            continue;
         }
+    }
 
-        // 检查是否重复
-        match database.check_command_exists(&cmd.name, &cmd.command) {
-            Ok(exists) => {
-                if exists {
-                    failed_items.push(FailedItem {
-                        index,
-                        reason: "命令已存在（名称和内容重复）".to_string(),
-                    });
-                    skip_count += 1;
-                    continue;
-                }
-            }
-            Err(e) => {
-                failed_items.push(FailedItem {
-                    index,
-                    reason: format!("检查重复失败: {}", e),
-                });
-                skip_count += 1;
-                continue;
-            }
-        }
+    // 创建命令（执行其他命令的触发器在所有命令创建后再写入）
+    let mut deferred = Vec::new();
+    for (index, mut cmd) in pending {
+        let source_id = cmd.id;
+        let output_triggers = if trigger::run_command_targets(&cmd.output_triggers)
+            .next()
+            .is_some()
+        {
+            std::mem::take(&mut cmd.output_triggers)
+        } else {
+            Vec::new()
+        };
 
-        // 创建命令
         let input = CreateCommandInput {
             name: cmd.name,
             command: cmd.command,
@@ -302,25 +825,76 @@ pub fn import_commands(database: State<Database>, json_data: String) -> Result<I
             working_directory: cmd.working_directory,
             url: cmd.url,
             notification_when_finished: cmd.notification_when_finished,
+            env: cmd.env,
+            env_files: cmd.env_files,
+            parameters: cmd.parameters,
+            timeout_secs: cmd.timeout_secs,
+            graceful_timeout_secs: cmd.graceful_timeout_secs,
+            stop_mode: cmd.stop_mode,
+            stop_command: cmd.stop_command,
+            restart_policy: cmd.restart_policy,
+            force_color: cmd.force_color,
+            pty: cmd.pty,
+            interactive: cmd.interactive,
+            schedule: cmd.schedule,
+            readiness: cmd.readiness,
+            health_check: cmd.health_check,
+            output_triggers: cmd.output_triggers,
+            success_rule: cmd.success_rule,
+            shell: cmd.shell,
+            max_instances: cmd.max_instances,
+            concurrency_group: cmd.concurrency_group,
+            resource_limits: cmd.resource_limits,
         };
 
         match database.create_command(input) {
-            Ok(_) => {
+            Ok(command) => {
                 success_count += 1;
+                if let Some(id) = source_id {
+                    ids.insert(id, command.id);
+                }
+                if !output_triggers.is_empty() {
+                    deferred.push((index, command.id, output_triggers));
+                }
             }
             Err(e) => {
                 failed_items.push(FailedItem {
                     index,
                     reason: format!("创建命令失败: {}", e),
                 });
-                skip_count += 1;
             }
         }
     }
 
+    // 写入重新映射后的触发器，引用的命令创建失败时撤销导入该命令
+    for (index, id, mut output_triggers) in deferred {
+        let missing = trigger::remap_run_command_targets(&mut output_triggers, &ids);
+        let result = if missing.is_empty() {
+            let input = UpdateCommandInput {
+                output_triggers: Some(output_triggers),
+                ..Default::default()
+            };
+            database.update_command(id, input)
+        } else {
+            Err("输出触发器执行的命令导入失败".to_string())
+        };
+        if let Err(e) = result {
+            if let Err(e) = database.delete_command(id) {
+                log::warn!("撤销导入命令 {} 失败: {}", id, e);
+            }
+            success_count -= 1;
+            failed_items.push(FailedItem { index, reason: e });
+        }
+    }
+
+    if success_count > 0 {
+        scheduler.reload(&database);
+    }
+
+    failed_items.sort_by_key(|item| item.index);
     Ok(ImportResult {
         success_count,
-        skip_count,
+        skip_count: failed_items.len(),
         failed_items,
     })
 }
@@ -345,13 +919,49 @@ pub fn read_import_file(file_path: String) -> Result<String, String> {
 
 // ==================== 日志相关命令 ====================
 
-/// 获取命令日志
+/// 分页获取命令日志（cursor 为上次返回的 next_cursor，未指定时返回最新的日志）
 #[tauri::command]
 pub fn get_command_logs(
     command_id: i64,
+    cursor: Option<u64>,
+    limit: Option<usize>,
+    runner: State<CommandRunner>,
+) -> Result<LogPage, String> {
+    Ok(runner.get_logs(
+        command_id,
+        cursor,
+        limit.unwrap_or(constants::command::DEFAULT_LOG_PAGE_SIZE),
+    ))
+}
+
+/// 分页获取单个运行实例的日志
+#[tauri::command]
+pub fn get_instance_logs(
+    run_id: i64,
+    cursor: Option<u64>,
+    limit: Option<usize>,
     runner: State<CommandRunner>,
-) -> Result<Vec<String>, String> {
-    Ok(runner.get_logs(command_id))
+) -> Result<LogPage, String> {
+    runner.get_instance_logs(
+        run_id,
+        cursor,
+        limit.unwrap_or(constants::command::DEFAULT_LOG_PAGE_SIZE),
+    )
+}
+
+/// 分页读取某次运行的日志文件
+#[tauri::command]
+pub fn get_run_logs(
+    run_log_store: State<RunLogStore>,
+    run_id: i64,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<RunLogPage, String> {
+    run_log_store.read(
+        run_id,
+        offset.unwrap_or(0),
+        limit.unwrap_or(constants::command::DEFAULT_LOG_PAGE_SIZE),
+    )
 }
 
 /// 清空命令日志
@@ -374,6 +984,17 @@ pub async fn open_log_window(
     crate::window::create_log_window(&app, command_id, &command_name)
 }
 
+/// 打开某次运行的历史日志窗口
+#[tauri::command]
+pub async fn open_run_log_window(
+    app: AppHandle,
+    run_id: i64,
+    command_id: i64,
+    command_name: String,
+) -> Result<(), String> {
+    crate::window::create_run_log_window(&app, run_id, command_id, &command_name)
+}
+
 /// 更新日志窗口标题
 #[tauri::command]
 pub async fn update_log_window_title(
@@ -3,6 +3,10 @@
 use crate::constants;
 use crate::db::Database;
 use crate::error::{AppError, AppResult};
+use crate::run_log::RunLogRetention;
+use crate::run_queue::ConcurrencyLimits;
+use crate::shell::Shell;
+use std::time::Duration;
 
 /// 配置管理器
 #[allow(dead_code)]
@@ -46,6 +50,73 @@ impl ConfigManager {
             .unwrap_or(false)
     }
 
+    /// 获取每个命令保留的运行历史条数（无效或为 0 时使用默认值）
+    pub fn get_run_history_limit(database: &Database) -> usize {
+        Self::get(database, constants::config_keys::RUN_HISTORY_LIMIT)
+            .ok()
+            .flatten()
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|&limit: &usize| limit > 0)
+            .unwrap_or(constants::history::DEFAULT_RUN_HISTORY_LIMIT)
+    }
+
+    /// 获取每个命令在内存中保留的日志行数（无效或为 0 时使用默认值）
+    pub fn get_log_buffer_capacity(database: &Database) -> usize {
+        Self::get(database, constants::config_keys::LOG_BUFFER_MAX_LINES)
+            .ok()
+            .flatten()
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|&lines: &usize| lines > 0)
+            .unwrap_or(constants::command::MAX_LOG_LINES)
+    }
+
+    /// 获取命令未指定 shell 时使用的默认 shell（未配置或无效时为系统 shell）
+    pub fn get_default_shell(database: &Database) -> Shell {
+        Self::get(database, constants::config_keys::DEFAULT_SHELL)
+            .ok()
+            .flatten()
+            .and_then(|v| Shell::parse(&v).ok())
+            .unwrap_or_default()
+    }
+
+    /// 获取并发限制（配置无效时视为不限制）
+    pub fn get_concurrency_limits(database: &Database) -> ConcurrencyLimits {
+        let get = |key: &str| Self::get(database, key).ok().flatten();
+        ConcurrencyLimits {
+            max_concurrent_runs: get(constants::config_keys::MAX_CONCURRENT_RUNS)
+                .and_then(|v| v.trim().parse().ok())
+                .filter(|&max: &usize| max > 0),
+            groups: get(constants::config_keys::CONCURRENCY_GROUPS)
+                .and_then(|v| ConcurrencyLimits::parse_groups(&v).ok())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// 获取运行日志文件的保留策略
+    pub fn get_run_log_retention(database: &Database) -> RunLogRetention {
+        let get_u64 = |key: &str, default: u64| {
+            Self::get(database, key)
+                .ok()
+                .flatten()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .unwrap_or(default)
+        };
+
+        let max_age_days = get_u64(
+            constants::config_keys::RUN_LOG_MAX_AGE_DAYS,
+            constants::history::DEFAULT_RUN_LOG_MAX_AGE_DAYS,
+        );
+        let max_total_mb = get_u64(
+            constants::config_keys::RUN_LOG_MAX_TOTAL_MB,
+            constants::history::DEFAULT_RUN_LOG_MAX_TOTAL_MB,
+        );
+
+        RunLogRetention {
+            max_age: (max_age_days > 0).then(|| Duration::from_secs(max_age_days * 24 * 60 * 60)),
+            max_total_bytes: (max_total_mb > 0).then(|| max_total_mb * 1024 * 1024),
+        }
+    }
+
     /// 设置开机自启动配置
     pub fn set_autostart(database: &Database, enabled: bool) -> AppResult<()> {
         Self::set(
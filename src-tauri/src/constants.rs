@@ -16,16 +16,81 @@ pub mod config_keys {
     pub const AUTO_START: &str = "auto_start";
     /// 语言配置键
     pub const LANGUAGE: &str = "language";
+    /// 每个命令保留的运行历史条数（0 或无效值使用默认值）
+    pub const RUN_HISTORY_LIMIT: &str = "run_history_limit";
+    /// 运行日志文件保留天数
+    pub const RUN_LOG_MAX_AGE_DAYS: &str = "run_log_max_age_days";
+    /// 运行日志文件总大小上限（MB）
+    pub const RUN_LOG_MAX_TOTAL_MB: &str = "run_log_max_total_mb";
+    /// 每个命令在内存中保留的日志行数
+    pub const LOG_BUFFER_MAX_LINES: &str = "log_buffer_max_lines";
+    /// 命令未指定 shell 时使用的默认 shell
+    pub const DEFAULT_SHELL: &str = "default_shell";
+    /// 同时运行的最大命令数（0 或为空表示不限制）
+    pub const MAX_CONCURRENT_RUNS: &str = "max_concurrent_runs";
+    /// 并发组的最大运行数（JSON 对象，如 `{"heavy builds": 1}`）
+    pub const CONCURRENCY_GROUPS: &str = "concurrency_groups";
 }
 
 /// 命令执行相关常量
 pub mod command {
-    /// 日志最大行数
-    #[allow(dead_code)]
+    /// 日志最大行数（内存日志缓冲的默认容量）
     pub const MAX_LOG_LINES: usize = 10000;
+    /// 日志分页读取的默认行数
+    pub const DEFAULT_LOG_PAGE_SIZE: usize = 1000;
     /// 状态更新间隔（毫秒）
     #[allow(dead_code)]
     pub const STATUS_UPDATE_INTERVAL_MS: u64 = 1000;
+    /// 进程状态轮询间隔（毫秒）
+    pub const PROCESS_POLL_INTERVAL_MS: u64 = 100;
+    /// 默认优雅停止等待时间（秒）
+    pub const DEFAULT_GRACEFUL_TIMEOUT_SECS: u64 = 10;
+    /// 运行结束后留有后代进程时，检查其是否全部退出的间隔（秒）
+    pub const LEFTOVER_PROCESS_CHECK_INTERVAL_SECS: u64 = 5;
+}
+
+/// 运行历史相关常量
+pub mod history {
+    /// 每个命令默认保留的运行历史条数
+    pub const DEFAULT_RUN_HISTORY_LIMIT: usize = 100;
+    /// 运行历史中保存的日志摘录行数（取最后若干行）
+    pub const LOG_EXCERPT_LINES: usize = 50;
+    /// 分页查询的默认条数
+    pub const DEFAULT_PAGE_SIZE: i64 = 50;
+    /// 运行日志文件默认保留天数（0 表示不限制）
+    pub const DEFAULT_RUN_LOG_MAX_AGE_DAYS: u64 = 30;
+    /// 运行日志文件默认总大小上限（MB，0 表示不限制）
+    pub const DEFAULT_RUN_LOG_MAX_TOTAL_MB: u64 = 500;
+}
+
+/// 定时执行相关常量
+pub mod schedule {
+    /// 调度线程检查间隔（毫秒）
+    pub const TICK_INTERVAL_MS: u64 = 1000;
+    /// 执行时间延迟超过该值（秒）视为错过执行（如系统休眠）
+    pub const MISSED_RUN_GRACE_SECS: i64 = 60;
+    /// 预览执行计划时默认返回的次数
+    pub const DEFAULT_PREVIEW_COUNT: usize = 5;
+}
+
+/// 流水线相关常量
+pub mod pipeline {
+    /// 步骤状态检查间隔（毫秒）
+    pub const POLL_INTERVAL_MS: u64 = 200;
+}
+
+/// 就绪检查相关常量
+pub mod readiness {
+    /// 单次检查的连接/读写超时（毫秒）
+    pub const PROBE_IO_TIMEOUT_MS: u64 = 2000;
+    /// 最小检查间隔（毫秒）
+    pub const MIN_PROBE_INTERVAL_MS: u64 = 100;
+}
+
+/// 健康检查相关常量
+pub mod health {
+    /// 健康检查命令的超时时间（秒）
+    pub const CHECK_COMMAND_TIMEOUT_SECS: u64 = 10;
 }
 
 /// 系统监控相关常量
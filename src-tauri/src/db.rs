@@ -1,8 +1,25 @@
-use rusqlite::{params, Connection, OptionalExtension, Result as SqliteResult};
-use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Mutex;
+
+use rusqlite::{params, Connection, OptionalExtension, Result as SqliteResult, Row};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use tauri::Manager;
+
+use crate::command_params::CommandParameter;
+use crate::command_runner::{CommandStatus, RunTrigger};
 use crate::constants;
+use crate::health::HealthCheckConfig;
+use crate::pipeline::PipelineStep;
+use crate::process_tree::StopMode;
+use crate::pty::PtyConfig;
+use crate::readiness::ReadinessConfig;
+use crate::resource_limits::ResourceLimits;
+use crate::restart::RestartPolicy;
+use crate::schedule::CommandSchedule;
+use crate::shell::Shell;
+use crate::success::SuccessRule;
+use crate::trigger::OutputTrigger;
 
 // ==================== 数据结构定义 ====================
 
@@ -16,13 +33,45 @@ pub struct Command {
     pub working_directory: Option<String>,
     pub url: Option<String>,
     pub notification_when_finished: bool,
+    pub env: BTreeMap<String, String>,
+    pub env_files: Vec<String>,
+    pub parameters: Vec<CommandParameter>,
+    pub timeout_secs: Option<u64>,
+    pub graceful_timeout_secs: Option<u64>,
+    pub stop_mode: StopMode,
+    pub stop_command: Option<String>,
+    pub restart_policy: RestartPolicy,
+    /// 自动设置 FORCE_COLOR / CLICOLOR_FORCE，让工具输出彩色日志
+    pub force_color: bool,
+    /// 伪终端模式（需要 TTY 的命令）
+    pub pty: PtyConfig,
+    /// 保持标准输入可写，允许向运行中的命令发送输入
+    pub interactive: bool,
+    /// 定时执行配置
+    pub schedule: CommandSchedule,
+    /// 就绪检查配置
+    pub readiness: ReadinessConfig,
+    /// 健康检查配置
+    pub health_check: HealthCheckConfig,
+    /// 输出触发器
+    pub output_triggers: Vec<OutputTrigger>,
+    /// 成功判定规则
+    pub success_rule: SuccessRule,
+    /// 执行命令使用的 shell（system-default 表示使用全局默认 shell）
+    pub shell: Shell,
+    /// 允许同时运行的实例数（为空时只允许一个）
+    pub max_instances: Option<u32>,
+    /// 并发组名称（同一组的运行数受组的并发限制）
+    pub concurrency_group: Option<String>,
+    /// 资源限制（内存、CPU 和进程数）
+    pub resource_limits: ResourceLimits,
     pub sort_order: i64,
     pub created_at: String,
     pub updated_at: String,
 }
 
 /// 创建命令的输入参数
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CreateCommandInput {
     pub name: String,
     pub command: String,
@@ -30,10 +79,65 @@ pub struct CreateCommandInput {
     pub working_directory: Option<String>,
     pub url: Option<String>,
     pub notification_when_finished: bool,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub env_files: Vec<String>,
+    #[serde(default)]
+    pub parameters: Vec<CommandParameter>,
+    /// 运行超时（秒），为空或 0 表示不限制
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// 优雅停止的等待时间（秒），超过后强制终止
+    #[serde(default)]
+    pub graceful_timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub stop_mode: StopMode,
+    /// 自定义停止命令（仅 stop_mode 为 command 时使用）
+    #[serde(default)]
+    pub stop_command: Option<String>,
+    #[serde(default)]
+    pub restart_policy: RestartPolicy,
+    /// 自动设置 FORCE_COLOR / CLICOLOR_FORCE，让工具输出彩色日志
+    #[serde(default)]
+    pub force_color: bool,
+    /// 伪终端模式（需要 TTY 的命令）
+    #[serde(default)]
+    pub pty: PtyConfig,
+    /// 保持标准输入可写，允许向运行中的命令发送输入
+    #[serde(default)]
+    pub interactive: bool,
+    /// 定时执行配置
+    #[serde(default)]
+    pub schedule: CommandSchedule,
+    /// 就绪检查配置
+    #[serde(default)]
+    pub readiness: ReadinessConfig,
+    /// 健康检查配置
+    #[serde(default)]
+    pub health_check: HealthCheckConfig,
+    /// 输出触发器
+    #[serde(default)]
+    pub output_triggers: Vec<OutputTrigger>,
+    /// 成功判定规则
+    #[serde(default)]
+    pub success_rule: SuccessRule,
+    /// 执行命令使用的 shell（system-default 表示使用全局默认 shell）
+    #[serde(default)]
+    pub shell: Shell,
+    /// 允许同时运行的实例数（为空时只允许一个）
+    #[serde(default)]
+    pub max_instances: Option<u32>,
+    /// 并发组名称（同一组的运行数受组的并发限制）
+    #[serde(default)]
+    pub concurrency_group: Option<String>,
+    /// 资源限制（内存、CPU 和进程数）
+    #[serde(default)]
+    pub resource_limits: ResourceLimits,
 }
 
 /// 更新命令的输入参数
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct UpdateCommandInput {
     pub name: Option<String>,
     pub command: Option<String>,
@@ -41,6 +145,219 @@ pub struct UpdateCommandInput {
     pub working_directory: Option<String>,
     pub url: Option<String>,
     pub notification_when_finished: Option<bool>,
+    /// 环境变量（整体替换）
+    pub env: Option<BTreeMap<String, String>>,
+    pub env_files: Option<Vec<String>>,
+    pub parameters: Option<Vec<CommandParameter>>,
+    /// 为 0 时清除超时设置
+    pub timeout_secs: Option<u64>,
+    /// 为 0 时恢复默认值
+    pub graceful_timeout_secs: Option<u64>,
+    pub stop_mode: Option<StopMode>,
+    /// 为空字符串时清除
+    pub stop_command: Option<String>,
+    pub restart_policy: Option<RestartPolicy>,
+    pub force_color: Option<bool>,
+    pub pty: Option<PtyConfig>,
+    pub interactive: Option<bool>,
+    pub schedule: Option<CommandSchedule>,
+    pub readiness: Option<ReadinessConfig>,
+    pub health_check: Option<HealthCheckConfig>,
+    pub output_triggers: Option<Vec<OutputTrigger>>,
+    pub success_rule: Option<SuccessRule>,
+    pub shell: Option<Shell>,
+    pub max_instances: Option<u32>,
+    pub concurrency_group: Option<String>,
+    pub resource_limits: Option<ResourceLimits>,
+}
+
+/// 命令表查询列（顺序与 `command_from_row` 对应）
+const COMMAND_COLUMNS: &str = "id, name, command, sudo, working_directory, url, notification_when_finished, sort_order, created_at, updated_at, env_files, parameters, timeout_secs, graceful_timeout_secs, stop_mode, stop_command, restart_policy, force_color, pty, interactive, schedule, readiness, health_check, output_triggers, success_rule, shell, max_instances, concurrency_group, resource_limits";
+
+/// 将查询行转换为命令（环境变量需要单独填充）
+fn command_from_row(row: &Row) -> SqliteResult<Command> {
+    Ok(Command {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        command: row.get(2)?,
+        sudo: row.get(3)?,
+        working_directory: row.get(4)?,
+        url: row.get(5)?,
+        notification_when_finished: row.get(6)?,
+        env: BTreeMap::new(),
+        env_files: json_column(row, 10)?,
+        parameters: json_column(row, 11)?,
+        timeout_secs: row.get(12)?,
+        graceful_timeout_secs: row.get(13)?,
+        stop_mode: enum_column(row, 14)?,
+        stop_command: row.get(15)?,
+        restart_policy: json_column(row, 16)?,
+        force_color: row.get(17)?,
+        pty: json_column(row, 18)?,
+        interactive: row.get(19)?,
+        schedule: json_column(row, 20)?,
+        readiness: json_column(row, 21)?,
+        health_check: json_column(row, 22)?,
+        output_triggers: json_column(row, 23)?,
+        success_rule: json_column(row, 24)?,
+        shell: enum_column(row, 25)?,
+        max_instances: row.get(26)?,
+        concurrency_group: row.get(27)?,
+        resource_limits: json_column(row, 28)?,
+        sort_order: row.get(7)?,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+/// 流水线数据结构
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pipeline {
+    pub id: i64,
+    pub name: String,
+    /// 按顺序排列的步骤，依赖关系构成有向无环图
+    pub steps: Vec<PipelineStep>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// 创建流水线的输入参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePipelineInput {
+    pub name: String,
+    pub steps: Vec<PipelineStep>,
+}
+
+/// 更新流水线的输入参数
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdatePipelineInput {
+    pub name: Option<String>,
+    pub steps: Option<Vec<PipelineStep>>,
+}
+
+/// 流水线表查询列（顺序与 `pipeline_from_row` 对应）
+const PIPELINE_COLUMNS: &str = "id, name, steps, created_at, updated_at";
+
+/// 将查询行转换为流水线
+fn pipeline_from_row(row: &Row) -> SqliteResult<Pipeline> {
+    Ok(Pipeline {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        steps: json_column(row, 2)?,
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
+}
+
+/// 命令运行记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandRun {
+    pub id: i64,
+    pub command_id: i64,
+    pub command_name: String,
+    /// 实际执行的命令文本（参数已替换）
+    pub command_text: String,
+    pub trigger: RunTrigger,
+    pub status: CommandStatus,
+    pub exit_code: Option<i32>,
+    pub pid: Option<u32>,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub duration_ms: Option<i64>,
+    /// 结束时最后若干行日志
+    pub log_excerpt: Option<String>,
+}
+
+/// 新建运行记录的输入参数
+#[derive(Debug, Clone)]
+pub struct NewCommandRun {
+    pub command_id: i64,
+    pub command_name: String,
+    pub command_text: String,
+    pub trigger: RunTrigger,
+    pub pid: Option<u32>,
+    pub started_at: String,
+}
+
+/// 运行结束时更新的字段
+#[derive(Debug, Clone)]
+pub struct FinishCommandRun {
+    pub status: CommandStatus,
+    pub exit_code: Option<i32>,
+    pub ended_at: String,
+    pub duration_ms: i64,
+    pub log_excerpt: Option<String>,
+}
+
+/// 运行历史查询条件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommandRunFilter {
+    pub command_id: Option<i64>,
+    pub status: Option<CommandStatus>,
+    pub trigger: Option<RunTrigger>,
+    /// 开始时间下限（RFC 3339，包含）
+    pub started_after: Option<String>,
+    /// 开始时间上限（RFC 3339，不包含）
+    pub started_before: Option<String>,
+}
+
+/// 运行历史分页结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandRunPage {
+    pub runs: Vec<CommandRun>,
+    pub total: i64,
+}
+
+/// 运行记录查询列（顺序与 `command_run_from_row` 对应）
+const COMMAND_RUN_COLUMNS: &str = "id, command_id, command_name, command_text, trigger, status, exit_code, pid, started_at, ended_at, duration_ms, log_excerpt";
+
+/// 将查询行转换为运行记录
+fn command_run_from_row(row: &Row) -> SqliteResult<CommandRun> {
+    Ok(CommandRun {
+        id: row.get(0)?,
+        command_id: row.get(1)?,
+        command_name: row.get(2)?,
+        command_text: row.get(3)?,
+        trigger: enum_column(row, 4)?,
+        status: enum_column(row, 5)?,
+        exit_code: row.get(6)?,
+        pid: row.get(7)?,
+        started_at: row.get(8)?,
+        ended_at: row.get(9)?,
+        duration_ms: row.get(10)?,
+        log_excerpt: row.get(11)?,
+    })
+}
+
+/// 读取以 JSON 文本存储的列
+fn json_column<T: DeserializeOwned>(row: &Row, index: usize) -> SqliteResult<T> {
+    let text: String = row.get(index)?;
+    serde_json::from_str(&text).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+/// 读取以文本存储的枚举列
+fn enum_column<T: DeserializeOwned>(row: &Row, index: usize) -> SqliteResult<T> {
+    let text: String = row.get(index)?;
+    serde_json::from_value(serde_json::Value::String(text)).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+/// 将枚举序列化为文本用于存储
+fn to_enum_text<T: Serialize>(value: &T) -> Result<String, String> {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(text)) => Ok(text),
+        Ok(other) => Err(format!("序列化字段失败: 不是枚举值 {}", other)),
+        Err(e) => Err(format!("序列化字段失败: {}", e)),
+    }
+}
+
+/// 将值序列化为 JSON 文本用于存储
+fn to_json_text<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string(value).map_err(|e| format!("序列化字段失败: {}", e))
 }
 
 // ==================== 数据库管理 ====================
@@ -119,6 +436,8 @@ impl Database {
             )
         })?;
 
+        Self::close_interrupted_runs(&conn)?;
+
         Ok(Database {
             conn: Mutex::new(conn),
         })
@@ -138,12 +457,103 @@ impl Database {
                 notification_when_finished BOOLEAN NOT NULL DEFAULT 0,
                 sort_order INTEGER NOT NULL DEFAULT 0,
                 created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-                updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+                updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                env_files TEXT NOT NULL DEFAULT '[]',
+                parameters TEXT NOT NULL DEFAULT '[]',
+                timeout_secs INTEGER,
+                graceful_timeout_secs INTEGER,
+                stop_mode TEXT NOT NULL DEFAULT 'graceful',
+                stop_command TEXT,
+                restart_policy TEXT NOT NULL DEFAULT '{}',
+                force_color BOOLEAN NOT NULL DEFAULT 0,
+                pty TEXT NOT NULL DEFAULT '{}',
+                interactive BOOLEAN NOT NULL DEFAULT 0,
+                schedule TEXT NOT NULL DEFAULT '{}',
+                readiness TEXT NOT NULL DEFAULT '{}',
+                health_check TEXT NOT NULL DEFAULT '{}',
+                output_triggers TEXT NOT NULL DEFAULT '[]',
+                success_rule TEXT NOT NULL DEFAULT '{}',
+                shell TEXT NOT NULL DEFAULT 'system-default',
+                max_instances INTEGER,
+                concurrency_group TEXT,
+                resource_limits TEXT NOT NULL DEFAULT '{}'
             )",
             [],
         )
         .map_err(|e| format!("创建命令表失败: {}", e))?;
 
+        // 旧版本数据库补充新增列
+        Self::ensure_column(conn, "commands", "env_files", "TEXT NOT NULL DEFAULT '[]'")?;
+        Self::ensure_column(conn, "commands", "parameters", "TEXT NOT NULL DEFAULT '[]'")?;
+        Self::ensure_column(conn, "commands", "timeout_secs", "INTEGER")?;
+        Self::ensure_column(conn, "commands", "graceful_timeout_secs", "INTEGER")?;
+        Self::ensure_column(conn, "commands", "stop_mode", "TEXT NOT NULL DEFAULT 'graceful'")?;
+        Self::ensure_column(conn, "commands", "stop_command", "TEXT")?;
+        Self::ensure_column(conn, "commands", "restart_policy", "TEXT NOT NULL DEFAULT '{}'")?;
+        Self::ensure_column(conn, "commands", "force_color", "BOOLEAN NOT NULL DEFAULT 0")?;
+        Self::ensure_column(conn, "commands", "pty", "TEXT NOT NULL DEFAULT '{}'")?;
+        Self::ensure_column(conn, "commands", "interactive", "BOOLEAN NOT NULL DEFAULT 0")?;
+        Self::ensure_column(conn, "commands", "schedule", "TEXT NOT NULL DEFAULT '{}'")?;
+        Self::ensure_column(conn, "commands", "readiness", "TEXT NOT NULL DEFAULT '{}'")?;
+        Self::ensure_column(conn, "commands", "health_check", "TEXT NOT NULL DEFAULT '{}'")?;
+        Self::ensure_column(conn, "commands", "output_triggers", "TEXT NOT NULL DEFAULT '[]'")?;
+        Self::ensure_column(conn, "commands", "success_rule", "TEXT NOT NULL DEFAULT '{}'")?;
+        Self::ensure_column(conn, "commands", "shell", "TEXT NOT NULL DEFAULT 'system-default'")?;
+        Self::ensure_column(conn, "commands", "max_instances", "INTEGER")?;
+        Self::ensure_column(conn, "commands", "concurrency_group", "TEXT")?;
+        Self::ensure_column(conn, "commands", "resource_limits", "TEXT NOT NULL DEFAULT '{}'")?;
+
+        // 创建命令环境变量表
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS command_env (
+                command_id INTEGER NOT NULL,
+                key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                PRIMARY KEY (command_id, key)
+            )",
+            [],
+        )
+        .map_err(|e| format!("创建环境变量表失败: {}", e))?;
+
+        // 创建运行历史表
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS command_runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                command_id INTEGER NOT NULL,
+                command_name TEXT NOT NULL,
+                command_text TEXT NOT NULL,
+                trigger TEXT NOT NULL,
+                status TEXT NOT NULL,
+                exit_code INTEGER,
+                pid INTEGER,
+                started_at TEXT NOT NULL,
+                ended_at TEXT,
+                duration_ms INTEGER,
+                log_excerpt TEXT
+            )",
+            [],
+        )
+        .map_err(|e| format!("创建运行历史表失败: {}", e))?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_command_runs_command ON command_runs (command_id, started_at)",
+            [],
+        )
+        .map_err(|e| format!("创建运行历史索引失败: {}", e))?;
+
+        // 创建流水线表
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS pipelines (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                steps TEXT NOT NULL DEFAULT '[]',
+                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )
+        .map_err(|e| format!("创建流水线表失败: {}", e))?;
+
         // 创建系统配置表
         conn.execute(
             "CREATE TABLE IF NOT EXISTS system_config (
@@ -158,14 +568,41 @@ impl Database {
         Ok(())
     }
 
+    /// 如果表中缺少指定列则添加（用于兼容旧版本数据库）
+    fn ensure_column(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<(), String> {
+        let mut stmt = conn
+            .prepare(&format!("PRAGMA table_info({})", table))
+            .map_err(|e| format!("准备查询失败: {}", e))?;
+
+        let exists = stmt
+            .query_map([], |row| row.get::<_, String>(1))
+            .map_err(|e| format!("查询表结构失败: {}", e))?
+            .filter_map(Result::ok)
+            .any(|name| name == column);
+
+        if !exists {
+            conn.execute(
+                &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+                [],
+            )
+            .map_err(|e| format!("添加字段 {}.{} 失败: {}", table, column, e))?;
+        }
+
+        Ok(())
+    }
+
     // ==================== 命令操作 ====================
 
     /// 创建新命令
     pub fn create_command(&self, input: CreateCommandInput) -> Result<Command, String> {
-        let conn = self.conn.lock().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+        let mut conn = self.conn.lock().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("开始事务失败: {}", e))?;
 
         // 获取当前最大的 sort_order
-        let max_sort_order: i64 = conn
+        let max_sort_order: i64 = tx
             .query_row("SELECT COALESCE(MAX(sort_order), -1) FROM commands", [], |row| {
                 row.get(0)
             })
@@ -174,9 +611,9 @@ impl Database {
         let new_sort_order = max_sort_order + 1;
 
         // 插入命令
-        conn.execute(
-            "INSERT INTO commands (name, command, sudo, working_directory, url, notification_when_finished, sort_order)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        tx.execute(
+            "INSERT INTO commands (name, command, sudo, working_directory, url, notification_when_finished, sort_order, env_files, parameters, timeout_secs, graceful_timeout_secs, stop_mode, stop_command, restart_policy, force_color, pty, interactive, schedule, readiness, health_check, output_triggers, success_rule, shell, max_instances, concurrency_group, resource_limits)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26)",
             params![
                 input.name,
                 input.command,
@@ -185,14 +622,40 @@ impl Database {
                 input.url,
                 input.notification_when_finished,
                 new_sort_order,
+                to_json_text(&input.env_files)?,
+                to_json_text(&input.parameters)?,
+                input.timeout_secs.filter(|v| *v > 0),
+                input.graceful_timeout_secs.filter(|v| *v > 0),
+                to_enum_text(&input.stop_mode)?,
+                input.stop_command.filter(|v| !v.trim().is_empty()),
+                to_json_text(&input.restart_policy)?,
+                input.force_color,
+                to_json_text(&input.pty)?,
+                input.interactive,
+                to_json_text(&input.schedule)?,
+                to_json_text(&input.readiness)?,
+                to_json_text(&input.health_check)?,
+                to_json_text(&input.output_triggers)?,
+                to_json_text(&input.success_rule)?,
+                to_enum_text(&input.shell)?,
+                input.max_instances.filter(|v| *v > 0),
+                input.concurrency_group.as_deref().map(str::trim).filter(|g| !g.is_empty()),
+                to_json_text(&input.resource_limits)?,
             ],
         )
         .map_err(|e| format!("插入命令失败: {}", e))?;
 
-        let id = conn.last_insert_rowid();
+        let id = tx.last_insert_rowid();
+
+        // 保存环境变量
+        Self::replace_command_env(&tx, id, &input.env)?;
 
         // 获取刚创建的命令
-        self.get_command_by_id_internal(&conn, id)
+        let command = self.get_command_by_id_internal(&tx, id)?;
+
+        tx.commit().map_err(|e| format!("提交事务失败: {}", e))?;
+
+        Ok(command)
     }
 
     /// 获取所有命令（按 sort_order 排序）
@@ -200,54 +663,40 @@ impl Database {
         let conn = self.conn.lock().map_err(|e| format!("获取数据库连接失败: {}", e))?;
 
         let mut stmt = conn
-            .prepare("SELECT id, name, command, sudo, working_directory, url, notification_when_finished, sort_order, created_at, updated_at FROM commands ORDER BY sort_order ASC")
+            .prepare(&format!("SELECT {} FROM commands ORDER BY sort_order ASC", COMMAND_COLUMNS))
             .map_err(|e| format!("准备查询失败: {}", e))?;
 
         let commands = stmt
-            .query_map([], |row| {
-                Ok(Command {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    command: row.get(2)?,
-                    sudo: row.get(3)?,
-                    working_directory: row.get(4)?,
-                    url: row.get(5)?,
-                    notification_when_finished: row.get(6)?,
-                    sort_order: row.get(7)?,
-                    created_at: row.get(8)?,
-                    updated_at: row.get(9)?,
-                })
-            })
+            .query_map([], command_from_row)
             .map_err(|e| format!("查询命令失败: {}", e))?
             .collect::<SqliteResult<Vec<Command>>>()
             .map_err(|e| format!("收集查询结果失败: {}", e))?;
 
+        // 填充环境变量
+        let mut env_map = Self::load_all_command_env(&conn)?;
+        let commands = commands
+            .into_iter()
+            .map(|mut command| {
+                command.env = env_map.remove(&command.id).unwrap_or_default();
+                command
+            })
+            .collect();
+
         Ok(commands)
     }
 
     /// 根据 ID 获取命令（内部使用，不需要锁）
     fn get_command_by_id_internal(&self, conn: &Connection, id: i64) -> Result<Command, String> {
         let mut stmt = conn
-            .prepare("SELECT id, name, command, sudo, working_directory, url, notification_when_finished, sort_order, created_at, updated_at FROM commands WHERE id = ?1")
+            .prepare(&format!("SELECT {} FROM commands WHERE id = ?1", COMMAND_COLUMNS))
             .map_err(|e| format!("准备查询失败: {}", e))?;
 
-        let command = stmt
-            .query_row([id], |row| {
-                Ok(Command {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    command: row.get(2)?,
-                    sudo: row.get(3)?,
-                    working_directory: row.get(4)?,
-                    url: row.get(5)?,
-                    notification_when_finished: row.get(6)?,
-                    sort_order: row.get(7)?,
-                    created_at: row.get(8)?,
-                    updated_at: row.get(9)?,
-                })
-            })
+        let mut command = stmt
+            .query_row([id], command_from_row)
             .map_err(|e| format!("查询命令失败: {}", e))?;
 
+        command.env = Self::load_command_env(conn, id)?;
+
         Ok(command)
     }
 
@@ -259,7 +708,7 @@ impl Database {
 
     /// 更新命令
     pub fn update_command(&self, id: i64, input: UpdateCommandInput) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+        let mut conn = self.conn.lock().map_err(|e| format!("获取数据库连接失败: {}", e))?;
 
         // 构建动态更新语句
         let mut updates = Vec::new();
@@ -289,8 +738,86 @@ impl Database {
             updates.push("notification_when_finished = ?");
             params.push(Box::new(notification_when_finished));
         }
+        if let Some(env_files) = &input.env_files {
+            updates.push("env_files = ?");
+            params.push(Box::new(to_json_text(env_files)?));
+        }
+        if let Some(parameters) = &input.parameters {
+            updates.push("parameters = ?");
+            params.push(Box::new(to_json_text(parameters)?));
+        }
+        if let Some(timeout_secs) = input.timeout_secs {
+            updates.push("timeout_secs = ?");
+            params.push(Box::new(Some(timeout_secs).filter(|v| *v > 0)));
+        }
+        if let Some(graceful_timeout_secs) = input.graceful_timeout_secs {
+            updates.push("graceful_timeout_secs = ?");
+            params.push(Box::new(Some(graceful_timeout_secs).filter(|v| *v > 0)));
+        }
+        if let Some(stop_mode) = &input.stop_mode {
+            updates.push("stop_mode = ?");
+            params.push(Box::new(to_enum_text(stop_mode)?));
+        }
+        if let Some(stop_command) = &input.stop_command {
+            updates.push("stop_command = ?");
+            params.push(Box::new(Some(stop_command.clone()).filter(|v| !v.trim().is_empty())));
+        }
+        if let Some(restart_policy) = &input.restart_policy {
+            updates.push("restart_policy = ?");
+            params.push(Box::new(to_json_text(restart_policy)?));
+        }
+        if let Some(force_color) = &input.force_color {
+            updates.push("force_color = ?");
+            params.push(Box::new(*force_color));
+        }
+        if let Some(pty) = &input.pty {
+            updates.push("pty = ?");
+            params.push(Box::new(to_json_text(pty)?));
+        }
+        if let Some(interactive) = &input.interactive {
+            updates.push("interactive = ?");
+            params.push(Box::new(*interactive));
+        }
+        if let Some(schedule) = &input.schedule {
+            updates.push("schedule = ?");
+            params.push(Box::new(to_json_text(schedule)?));
+        }
+        if let Some(readiness) = &input.readiness {
+            updates.push("readiness = ?");
+            params.push(Box::new(to_json_text(readiness)?));
+        }
+        if let Some(health_check) = &input.health_check {
+            updates.push("health_check = ?");
+            params.push(Box::new(to_json_text(health_check)?));
+        }
+        if let Some(output_triggers) = &input.output_triggers {
+            updates.push("output_triggers = ?");
+            params.push(Box::new(to_json_text(output_triggers)?));
+        }
+        if let Some(success_rule) = &input.success_rule {
+            updates.push("success_rule = ?");
+            params.push(Box::new(to_json_text(success_rule)?));
+        }
+        if let Some(shell) = &input.shell {
+            updates.push("shell = ?");
+            params.push(Box::new(to_enum_text(shell)?));
+        }
+        if let Some(max_instances) = &input.max_instances {
+            updates.push("max_instances = ?");
+            params.push(Box::new(Some(*max_instances).filter(|v| *v > 0)));
+        }
+        if let Some(concurrency_group) = &input.concurrency_group {
+            updates.push("concurrency_group = ?");
+            params.push(Box::new(
+                Some(concurrency_group.trim().to_string()).filter(|g| !g.is_empty()),
+            ));
+        }
+        if let Some(resource_limits) = &input.resource_limits {
+            updates.push("resource_limits = ?");
+            params.push(Box::new(to_json_text(resource_limits)?));
+        }
 
-        if updates.is_empty() {
+        if updates.is_empty() && input.env.is_none() {
             return Ok(());
         }
 
@@ -301,20 +828,61 @@ impl Database {
 
         let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|b| b.as_ref()).collect();
 
-        conn.execute(&sql, params_refs.as_slice())
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("开始事务失败: {}", e))?;
+
+        let affected = tx
+            .execute(&sql, params_refs.as_slice())
             .map_err(|e| format!("更新命令失败: {}", e))?;
+        if affected == 0 {
+            return Err("命令不存在".to_string());
+        }
+
+        // 环境变量单独存表，整体替换
+        if let Some(env) = &input.env {
+            Self::replace_command_env(&tx, id, env)?;
+        }
+
+        tx.commit().map_err(|e| format!("提交事务失败: {}", e))?;
 
         Ok(())
     }
 
     /// 删除命令
-    pub fn delete_command(&self, id: i64) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+    ///
+    /// 返回被删除的运行记录 ID（调用方需删除对应的运行日志文件）
+    pub fn delete_command(&self, id: i64) -> Result<Vec<i64>, String> {
+        let mut conn = self.conn.lock().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("开始事务失败: {}", e))?;
 
-        conn.execute("DELETE FROM commands WHERE id = ?1", [id])
+        let run_ids = {
+            let mut stmt = tx
+                .prepare("SELECT id FROM command_runs WHERE command_id = ?1")
+                .map_err(|e| format!("准备查询失败: {}", e))?;
+            let run_ids = stmt
+                .query_map([id], |row| row.get::<_, i64>(0))
+                .map_err(|e| format!("查询运行记录失败: {}", e))?
+                .collect::<SqliteResult<Vec<i64>>>()
+                .map_err(|e| format!("收集查询结果失败: {}", e))?;
+            run_ids
+        };
+
+        tx.execute("DELETE FROM commands WHERE id = ?1", [id])
             .map_err(|e| format!("删除命令失败: {}", e))?;
 
-        Ok(())
+        tx.execute("DELETE FROM command_env WHERE command_id = ?1", [id])
+            .map_err(|e| format!("删除环境变量失败: {}", e))?;
+
+        tx.execute("DELETE FROM command_runs WHERE command_id = ?1", [id])
+            .map_err(|e| format!("删除运行记录失败: {}", e))?;
+
+        tx.commit().map_err(|e| format!("提交事务失败: {}", e))?;
+
+        Ok(run_ids)
     }
 
     /// 批量更新命令排序
@@ -338,19 +906,384 @@ impl Database {
         Ok(())
     }
 
-    /// 检查命令是否存在（根据 name 和 command）
-    pub fn check_command_exists(&self, name: &str, command: &str) -> Result<bool, String> {
+    // ==================== 环境变量操作 ====================
+
+    /// 替换命令的全部环境变量（内部使用，不需要锁）
+    fn replace_command_env(
+        conn: &Connection,
+        command_id: i64,
+        env: &BTreeMap<String, String>,
+    ) -> Result<(), String> {
+        for key in env.keys() {
+            if key.trim().is_empty() || key.contains('=') || key.contains('\0') {
+                return Err(format!("环境变量名无效: {:?}", key));
+            }
+        }
+
+        conn.execute("DELETE FROM command_env WHERE command_id = ?1", [command_id])
+            .map_err(|e| format!("删除环境变量失败: {}", e))?;
+
+        for (key, value) in env {
+            conn.execute(
+                "INSERT INTO command_env (command_id, key, value) VALUES (?1, ?2, ?3)",
+                params![command_id, key, value],
+            )
+            .map_err(|e| format!("插入环境变量失败: {}", e))?;
+        }
+
+        Ok(())
+    }
+
+    /// 获取单个命令的环境变量（内部使用，不需要锁）
+    fn load_command_env(conn: &Connection, command_id: i64) -> Result<BTreeMap<String, String>, String> {
+        let mut stmt = conn
+            .prepare("SELECT key, value FROM command_env WHERE command_id = ?1")
+            .map_err(|e| format!("准备查询失败: {}", e))?;
+
+        let env = stmt
+            .query_map([command_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(|e| format!("查询环境变量失败: {}", e))?
+            .collect::<SqliteResult<BTreeMap<String, String>>>()
+            .map_err(|e| format!("收集查询结果失败: {}", e))?;
+
+        Ok(env)
+    }
+
+    /// 获取所有命令的环境变量，按命令 ID 分组（内部使用，不需要锁）
+    fn load_all_command_env(
+        conn: &Connection,
+    ) -> Result<HashMap<i64, BTreeMap<String, String>>, String> {
+        let mut stmt = conn
+            .prepare("SELECT command_id, key, value FROM command_env")
+            .map_err(|e| format!("准备查询失败: {}", e))?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })
+            .map_err(|e| format!("查询环境变量失败: {}", e))?
+            .collect::<SqliteResult<Vec<(i64, String, String)>>>()
+            .map_err(|e| format!("收集查询结果失败: {}", e))?;
+
+        let mut env_map: HashMap<i64, BTreeMap<String, String>> = HashMap::new();
+        for (command_id, key, value) in rows {
+            env_map.entry(command_id).or_default().insert(key, value);
+        }
+
+        Ok(env_map)
+    }
+
+    /// 查找名称和内容都相同的命令，返回其 ID
+    pub fn find_command_id(&self, name: &str, command: &str) -> Result<Option<i64>, String> {
         let conn = self.conn.lock().map_err(|e| format!("获取数据库连接失败: {}", e))?;
 
-        let count: i64 = conn
+        conn.query_row(
+            "SELECT id FROM commands WHERE name = ?1 AND command = ?2 ORDER BY id LIMIT 1",
+            params![name, command],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("查询命令是否存在失败: {}", e))
+    }
+
+    // ==================== 运行历史操作 ====================
+
+    /// 新增运行记录，返回记录 ID
+    pub fn insert_command_run(&self, run: &NewCommandRun) -> Result<i64, String> {
+        let conn = self.conn.lock().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        conn.execute(
+            "INSERT INTO command_runs (command_id, command_name, command_text, trigger, status, pid, started_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                run.command_id,
+                run.command_name,
+                run.command_text,
+                to_enum_text(&run.trigger)?,
+                to_enum_text(&CommandStatus::Running)?,
+                run.pid,
+                run.started_at,
+            ],
+        )
+        .map_err(|e| format!("插入运行记录失败: {}", e))?;
+
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// 记录运行结束，并按保留条数清理该命令的旧记录
+    ///
+    /// 返回被清理的运行记录 ID（调用方需删除对应的运行日志文件）
+    pub fn finish_command_run(
+        &self,
+        run_id: i64,
+        finish: &FinishCommandRun,
+        keep_per_command: usize,
+    ) -> Result<Vec<i64>, String> {
+        let conn = self.conn.lock().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        conn.execute(
+            "UPDATE command_runs SET status = ?1, exit_code = ?2, ended_at = ?3, duration_ms = ?4, log_excerpt = ?5
+             WHERE id = ?6",
+            params![
+                to_enum_text(&finish.status)?,
+                finish.exit_code,
+                finish.ended_at,
+                finish.duration_ms,
+                finish.log_excerpt,
+                run_id,
+            ],
+        )
+        .map_err(|e| format!("更新运行记录失败: {}", e))?;
+
+        // 只保留最近的若干条记录（不清理仍在运行的实例）
+        const OUTDATED: &str = "command_id = (SELECT command_id FROM command_runs WHERE id = ?1)
+               AND ended_at IS NOT NULL
+               AND id NOT IN (
+                   SELECT id FROM command_runs
+                   WHERE command_id = (SELECT command_id FROM command_runs WHERE id = ?1)
+                     AND ended_at IS NOT NULL
+                   ORDER BY id DESC LIMIT ?2
+               )";
+        let keep = params![run_id, keep_per_command as i64];
+        let mut stmt = conn
+            .prepare(&format!("SELECT id FROM command_runs WHERE {}", OUTDATED))
+            .map_err(|e| format!("准备查询失败: {}", e))?;
+        let pruned = stmt
+            .query_map(keep, |row| row.get::<_, i64>(0))
+            .map_err(|e| format!("查询运行记录失败: {}", e))?
+            .collect::<SqliteResult<Vec<i64>>>()
+            .map_err(|e| format!("收集查询结果失败: {}", e))?;
+
+        conn.execute(&format!("DELETE FROM command_runs WHERE {}", OUTDATED), keep)
+            .map_err(|e| format!("清理运行记录失败: {}", e))?;
+
+        Ok(pruned)
+    }
+
+    /// 分页查询运行历史（按开始时间倒序）
+    pub fn get_command_runs(
+        &self,
+        filter: &CommandRunFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<CommandRunPage, String> {
+        let conn = self.conn.lock().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        // 构建动态查询条件
+        let mut conditions = Vec::new();
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        if let Some(command_id) = filter.command_id {
+            conditions.push("command_id = ?");
+            params.push(Box::new(command_id));
+        }
+        if let Some(status) = &filter.status {
+            conditions.push("status = ?");
+            params.push(Box::new(to_enum_text(status)?));
+        }
+        if let Some(trigger) = &filter.trigger {
+            conditions.push("trigger = ?");
+            params.push(Box::new(to_enum_text(trigger)?));
+        }
+        if let Some(started_after) = &filter.started_after {
+            conditions.push("started_at >= ?");
+            params.push(Box::new(started_after.clone()));
+        }
+        if let Some(started_before) = &filter.started_before {
+            conditions.push("started_at < ?");
+            params.push(Box::new(started_before.clone()));
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|b| b.as_ref()).collect();
+
+        let total: i64 = conn
             .query_row(
-                "SELECT COUNT(*) FROM commands WHERE name = ?1 AND command = ?2",
-                params![name, command],
+                &format!("SELECT COUNT(*) FROM command_runs {}", where_clause),
+                params_refs.as_slice(),
                 |row| row.get(0),
             )
-            .map_err(|e| format!("查询命令是否存在失败: {}", e))?;
+            .map_err(|e| format!("查询运行记录数量失败: {}", e))?;
 
-        Ok(count > 0)
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM command_runs {} ORDER BY started_at DESC, id DESC LIMIT {} OFFSET {}",
+                COMMAND_RUN_COLUMNS,
+                where_clause,
+                limit.max(0),
+                offset.max(0)
+            ))
+            .map_err(|e| format!("准备查询失败: {}", e))?;
+
+        let runs = stmt
+            .query_map(params_refs.as_slice(), command_run_from_row)
+            .map_err(|e| format!("查询运行记录失败: {}", e))?
+            .collect::<SqliteResult<Vec<CommandRun>>>()
+            .map_err(|e| format!("收集查询结果失败: {}", e))?;
+
+        Ok(CommandRunPage { runs, total })
+    }
+
+    /// 删除指定的运行记录（跳过仍在运行的记录），返回被删除记录的 ID
+    pub fn delete_command_runs(&self, run_ids: &[i64]) -> Result<Vec<i64>, String> {
+        let conn = self.conn.lock().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        let mut deleted = Vec::new();
+        for run_id in run_ids {
+            let count = conn
+                .execute(
+                    "DELETE FROM command_runs WHERE id = ?1 AND ended_at IS NOT NULL",
+                    [run_id],
+                )
+                .map_err(|e| format!("删除运行记录失败: {}", e))?;
+            if count > 0 {
+                deleted.push(*run_id);
+            }
+        }
+
+        Ok(deleted)
+    }
+
+    /// 清空运行记录（指定命令或全部，跳过仍在运行的记录），返回被删除记录的 ID
+    pub fn clear_command_runs(&self, command_id: Option<i64>) -> Result<Vec<i64>, String> {
+        let conn = self.conn.lock().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT id FROM command_runs
+                 WHERE (?1 IS NULL OR command_id = ?1) AND ended_at IS NOT NULL",
+            )
+            .map_err(|e| format!("准备查询失败: {}", e))?;
+        let run_ids = stmt
+            .query_map([command_id], |row| row.get::<_, i64>(0))
+            .map_err(|e| format!("查询运行记录失败: {}", e))?
+            .collect::<SqliteResult<Vec<i64>>>()
+            .map_err(|e| format!("收集查询结果失败: {}", e))?;
+
+        conn.execute(
+            "DELETE FROM command_runs WHERE (?1 IS NULL OR command_id = ?1) AND ended_at IS NOT NULL",
+            [command_id],
+        )
+        .map_err(|e| format!("清空运行记录失败: {}", e))?;
+
+        Ok(run_ids)
+    }
+
+    /// 将上次退出时仍未结束的运行记录标记为失败（应用异常退出等情况）
+    ///
+    /// 实际结束时间未知，结束时间记为开始时间，避免每次启动都再次匹配。
+    pub(crate) fn close_interrupted_runs(conn: &Connection) -> Result<(), String> {
+        conn.execute(
+            "UPDATE command_runs
+             SET status = ?1, ended_at = COALESCE(ended_at, started_at), duration_ms = COALESCE(duration_ms, 0)
+             WHERE ended_at IS NULL",
+            [to_enum_text(&CommandStatus::Failed)?],
+        )
+        .map_err(|e| format!("更新未结束的运行记录失败: {}", e))?;
+
+        Ok(())
+    }
+
+    // ==================== 流水线操作 ====================
+
+    /// 创建流水线
+    pub fn create_pipeline(&self, input: CreatePipelineInput) -> Result<Pipeline, String> {
+        let conn = self.conn.lock().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        conn.execute(
+            "INSERT INTO pipelines (name, steps) VALUES (?1, ?2)",
+            params![input.name, to_json_text(&input.steps)?],
+        )
+        .map_err(|e| format!("插入流水线失败: {}", e))?;
+
+        Self::get_pipeline_by_id_internal(&conn, conn.last_insert_rowid())
+    }
+
+    /// 获取所有流水线
+    pub fn get_all_pipelines(&self) -> Result<Vec<Pipeline>, String> {
+        let conn = self.conn.lock().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        let mut stmt = conn
+            .prepare(&format!("SELECT {} FROM pipelines ORDER BY id ASC", PIPELINE_COLUMNS))
+            .map_err(|e| format!("准备查询失败: {}", e))?;
+
+        let pipelines = stmt
+            .query_map([], pipeline_from_row)
+            .map_err(|e| format!("查询流水线失败: {}", e))?
+            .collect::<SqliteResult<Vec<Pipeline>>>()
+            .map_err(|e| format!("收集查询结果失败: {}", e))?;
+
+        Ok(pipelines)
+    }
+
+    /// 根据 ID 获取流水线（内部使用，不需要锁）
+    fn get_pipeline_by_id_internal(conn: &Connection, id: i64) -> Result<Pipeline, String> {
+        conn.query_row(
+            &format!("SELECT {} FROM pipelines WHERE id = ?1", PIPELINE_COLUMNS),
+            [id],
+            pipeline_from_row,
+        )
+        .map_err(|e| format!("查询流水线失败: {}", e))
+    }
+
+    /// 根据 ID 获取流水线
+    pub fn get_pipeline_by_id(&self, id: i64) -> Result<Pipeline, String> {
+        let conn = self.conn.lock().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+        Self::get_pipeline_by_id_internal(&conn, id)
+    }
+
+    /// 更新流水线
+    pub fn update_pipeline(&self, id: i64, input: UpdatePipelineInput) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        let mut updates = Vec::new();
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        if let Some(name) = &input.name {
+            updates.push("name = ?");
+            params.push(Box::new(name.clone()));
+        }
+        if let Some(steps) = &input.steps {
+            updates.push("steps = ?");
+            params.push(Box::new(to_json_text(steps)?));
+        }
+
+        if updates.is_empty() {
+            return Ok(());
+        }
+
+        updates.push("updated_at = CURRENT_TIMESTAMP");
+
+        let sql = format!("UPDATE pipelines SET {} WHERE id = ?", updates.join(", "));
+        params.push(Box::new(id));
+
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|b| b.as_ref()).collect();
+
+        conn.execute(&sql, params_refs.as_slice())
+            .map_err(|e| format!("更新流水线失败: {}", e))?;
+
+        Ok(())
+    }
+
+    /// 删除流水线
+    pub fn delete_pipeline(&self, id: i64) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| format!("获取数据库连接失败: {}", e))?;
+
+        conn.execute("DELETE FROM pipelines WHERE id = ?1", [id])
+            .map_err(|e| format!("删除流水线失败: {}", e))?;
+
+        Ok(())
     }
 
     // ==================== 系统配置操作 ====================
@@ -383,7 +1316,7 @@ impl Database {
     }
 
     /// 获取所有配置
-    pub fn get_all_configs(&self) -> Result<std::collections::HashMap<String, String>, String> {
+    pub fn get_all_configs(&self) -> Result<HashMap<String, String>, String> {
         let conn = self.conn.lock().map_err(|e| format!("获取数据库连接失败: {}", e))?;
 
         let mut stmt = conn
@@ -395,7 +1328,7 @@ impl Database {
                 Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
             })
             .map_err(|e| format!("查询配置失败: {}", e))?
-            .collect::<SqliteResult<std::collections::HashMap<String, String>>>()
+            .collect::<SqliteResult<HashMap<String, String>>>()
             .map_err(|e| format!("收集查询结果失败: {}", e))?;
 
         Ok(configs)
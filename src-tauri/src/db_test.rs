@@ -2,6 +2,7 @@
 mod tests {
     use crate::db::{Database, CreateCommandInput};
     use rusqlite::Connection;
+    use std::collections::BTreeMap;
     use std::time::{SystemTime, UNIX_EPOCH};
 
     fn create_test_database() -> Database {
@@ -34,6 +35,7 @@ mod tests {
             working_directory: None,
             url: None,
             notification_when_finished: false,
+            ..Default::default()
         };
         
         let result = db.create_command(input);
@@ -57,6 +59,7 @@ mod tests {
                 working_directory: None,
                 url: None,
                 notification_when_finished: false,
+                ..Default::default()
             };
             db.create_command(input).unwrap();
         }
@@ -76,6 +79,7 @@ mod tests {
             working_directory: None,
             url: None,
             notification_when_finished: false,
+            ..Default::default()
         };
         
         let created = db.create_command(input).unwrap();
@@ -96,6 +100,7 @@ mod tests {
             working_directory: None,
             url: None,
             notification_when_finished: false,
+            ..Default::default()
         };
         
         let created = db.create_command(input).unwrap();
@@ -107,6 +112,7 @@ mod tests {
             working_directory: None,
             url: None,
             notification_when_finished: None,
+            ..Default::default()
         };
         
         db.update_command(created.id, update_input).unwrap();
@@ -127,6 +133,7 @@ mod tests {
             working_directory: None,
             url: None,
             notification_when_finished: false,
+            ..Default::default()
         };
         
         let created = db.create_command(input).unwrap();
@@ -150,5 +157,254 @@ mod tests {
         let value = db.get_config("test_key").unwrap();
         assert_eq!(value, Some("new_value".to_string()));
     }
-}
 
+    #[test]
+    fn test_command_env_operations() {
+        let db = create_test_database();
+
+        let mut env = BTreeMap::new();
+        env.insert("NODE_ENV".to_string(), "development".to_string());
+        env.insert("PORT".to_string(), "3000".to_string());
+
+        let input = CreateCommandInput {
+            name: "开发服务器".to_string(),
+            command: "npm run dev".to_string(),
+            env,
+            ..Default::default()
+        };
+
+        let created = db.create_command(input).unwrap();
+        assert_eq!(created.env.get("PORT"), Some(&"3000".to_string()));
+
+        // 整体替换环境变量
+        let mut new_env = BTreeMap::new();
+        new_env.insert("RUST_LOG".to_string(), "debug".to_string());
+        let update_input = crate::db::UpdateCommandInput {
+            env: Some(new_env),
+            ..Default::default()
+        };
+        db.update_command(created.id, update_input).unwrap();
+
+        let commands = db.get_all_commands().unwrap();
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].env.len(), 1);
+        assert_eq!(commands[0].env.get("RUST_LOG"), Some(&"debug".to_string()));
+
+        // 非法的变量名应被拒绝
+        let mut invalid_env = BTreeMap::new();
+        invalid_env.insert("A=B".to_string(), "1".to_string());
+        let update_input = crate::db::UpdateCommandInput {
+            name: Some("不应保存".to_string()),
+            env: Some(invalid_env),
+            ..Default::default()
+        };
+        assert!(db.update_command(created.id, update_input).is_err());
+
+        // 失败时命令和环境变量都不应被修改
+        let command = db.get_command_by_id(created.id).unwrap();
+        assert_eq!(command.name, "开发服务器");
+        assert_eq!(command.env.get("RUST_LOG"), Some(&"debug".to_string()));
+
+        // 更新不存在的命令应返回错误
+        let update_input = crate::db::UpdateCommandInput {
+            env: Some(BTreeMap::new()),
+            ..Default::default()
+        };
+        assert!(db.update_command(created.id + 1, update_input).is_err());
+    }
+
+    #[test]
+    fn test_command_stop_mode() {
+        use crate::process_tree::StopMode;
+
+        let db = create_test_database();
+
+        let created = db
+            .create_command(CreateCommandInput {
+                name: "数据库".to_string(),
+                command: "docker compose up".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(created.stop_mode, StopMode::Graceful);
+        assert_eq!(created.stop_command, None);
+
+        let update_input = crate::db::UpdateCommandInput {
+            stop_mode: Some(StopMode::Command),
+            stop_command: Some("docker compose down".to_string()),
+            ..Default::default()
+        };
+        db.update_command(created.id, update_input).unwrap();
+
+        let command = db.get_command_by_id(created.id).unwrap();
+        assert_eq!(command.stop_mode, StopMode::Command);
+        assert_eq!(command.stop_command.as_deref(), Some("docker compose down"));
+
+        // 空字符串清除停止命令
+        let update_input = crate::db::UpdateCommandInput {
+            stop_command: Some(String::new()),
+            ..Default::default()
+        };
+        db.update_command(created.id, update_input).unwrap();
+        assert_eq!(db.get_command_by_id(created.id).unwrap().stop_command, None);
+    }
+
+    #[test]
+    fn test_command_run_history() {
+        use crate::command_runner::{CommandStatus, RunTrigger};
+        use crate::db::{CommandRunFilter, FinishCommandRun, NewCommandRun};
+
+        let db = create_test_database();
+
+        let new_run = |command_id: i64, trigger: RunTrigger, started_at: &str| NewCommandRun {
+            command_id,
+            command_name: format!("命令{}", command_id),
+            command_text: "echo test".to_string(),
+            trigger,
+            pid: Some(1234),
+            started_at: started_at.to_string(),
+        };
+        let finish = |status: CommandStatus, exit_code: i32| FinishCommandRun {
+            status,
+            exit_code: Some(exit_code),
+            ended_at: "2026-01-01T00:10:00+08:00".to_string(),
+            duration_ms: 1000,
+            log_excerpt: Some("[stdout] test".to_string()),
+        };
+
+        // 仍在运行的实例不参与清理
+        let active_run = db
+            .insert_command_run(&new_run(1, RunTrigger::Manual, "2026-01-01T00:00:00+08:00"))
+            .unwrap();
+
+        // 命令 1 运行三次，只保留最近两条
+        let mut run_ids = Vec::new();
+        let mut pruned = Vec::new();
+        for (minute, status, code) in [
+            (1, CommandStatus::Success, 0),
+            (2, CommandStatus::Failed, 1),
+            (3, CommandStatus::Success, 0),
+        ] {
+            let started_at = format!("2026-01-01T00:0{}:00+08:00", minute);
+            let run_id = db
+                .insert_command_run(&new_run(1, RunTrigger::Manual, &started_at))
+                .unwrap();
+            run_ids.push(run_id);
+            pruned.extend(db.finish_command_run(run_id, &finish(status, code), 2).unwrap());
+        }
+        // 返回被清理的记录，用于删除运行日志文件
+        assert_eq!(pruned, vec![run_ids[0]]);
+        let restart_run = db
+            .insert_command_run(&new_run(2, RunTrigger::Restart, "2026-01-01T00:04:00+08:00"))
+            .unwrap();
+
+        let page = db.get_command_runs(&CommandRunFilter::default(), 0, 10).unwrap();
+        assert_eq!(page.total, 4);
+        assert!(page.runs.iter().any(|run| run.id == active_run));
+        assert_eq!(page.runs[0].id, restart_run);
+        assert_eq!(page.runs[0].status, CommandStatus::Running);
+        assert_eq!(page.runs[0].ended_at, None);
+
+        let filter = CommandRunFilter {
+            command_id: Some(1),
+            status: Some(CommandStatus::Failed),
+            ..Default::default()
+        };
+        let page = db.get_command_runs(&filter, 0, 10).unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.runs[0].exit_code, Some(1));
+        assert_eq!(page.runs[0].log_excerpt.as_deref(), Some("[stdout] test"));
+
+        let filter = CommandRunFilter {
+            trigger: Some(RunTrigger::Restart),
+            ..Default::default()
+        };
+        assert_eq!(db.get_command_runs(&filter, 0, 10).unwrap().total, 1);
+
+        // 分页
+        let page = db.get_command_runs(&CommandRunFilter::default(), 1, 1).unwrap();
+        assert_eq!(page.total, 4);
+        assert_eq!(page.runs.len(), 1);
+
+        // 仍在运行的记录不删除
+        assert!(db.delete_command_runs(&[restart_run]).unwrap().is_empty());
+        assert_eq!(db.clear_command_runs(Some(1)).unwrap(), vec![run_ids[1], run_ids[2]]);
+        let page = db.get_command_runs(&CommandRunFilter::default(), 0, 10).unwrap();
+        assert_eq!(page.total, 2);
+
+        db.finish_command_run(restart_run, &finish(CommandStatus::Success, 0), 2).unwrap();
+        assert_eq!(db.delete_command_runs(&[restart_run]).unwrap(), vec![restart_run]);
+    }
+
+    #[test]
+    fn test_close_interrupted_runs() {
+        use crate::command_runner::CommandStatus;
+        use crate::db::CommandRunFilter;
+
+        let conn = Connection::open_in_memory().unwrap();
+        Database::create_tables(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO command_runs (command_id, command_name, command_text, trigger, status, started_at)
+             VALUES (1, '命令1', 'sleep 100', 'manual', 'running', '2026-01-01T00:00:00+08:00')",
+            [],
+        )
+        .unwrap();
+
+        Database::close_interrupted_runs(&conn).unwrap();
+        // 已补上结束时间，再次启动时不会重复处理
+        assert_eq!(
+            conn.query_row("SELECT COUNT(*) FROM command_runs WHERE ended_at IS NULL", [], |row| row.get::<_, i64>(0))
+                .unwrap(),
+            0
+        );
+
+        let db = Database::new_for_testing(conn);
+        let page = db.get_command_runs(&CommandRunFilter::default(), 0, 10).unwrap();
+        assert_eq!(page.runs[0].status, CommandStatus::Failed);
+        assert_eq!(page.runs[0].ended_at.as_deref(), Some("2026-01-01T00:00:00+08:00"));
+        assert_eq!(page.runs[0].duration_ms, Some(0));
+    }
+
+    #[test]
+    fn test_pipeline_operations() {
+        use crate::db::{CreatePipelineInput, UpdatePipelineInput};
+        use crate::pipeline::{PipelineStep, StepCondition, StepDependency};
+
+        let db = create_test_database();
+
+        let steps = vec![
+            PipelineStep {
+                command_id: 1,
+                depends_on: Vec::new(),
+            },
+            PipelineStep {
+                command_id: 2,
+                depends_on: vec![StepDependency {
+                    step: 0,
+                    condition: StepCondition::AfterStart,
+                }],
+            },
+        ];
+        let created = db
+            .create_pipeline(CreatePipelineInput {
+                name: "启动全部服务".to_string(),
+                steps: steps.clone(),
+            })
+            .unwrap();
+        assert_eq!(created.steps, steps);
+
+        let update_input = UpdatePipelineInput {
+            name: Some("启动后端".to_string()),
+            ..Default::default()
+        };
+        db.update_pipeline(created.id, update_input).unwrap();
+
+        let pipelines = db.get_all_pipelines().unwrap();
+        assert_eq!(pipelines.len(), 1);
+        assert_eq!(pipelines[0].name, "启动后端");
+        assert_eq!(pipelines[0].steps[1].depends_on[0].condition, StepCondition::AfterStart);
+
+        db.delete_pipeline(created.id).unwrap();
+        assert!(db.get_pipeline_by_id(created.id).is_err());
+    }
+}
@@ -0,0 +1,293 @@
+// ==================== .env 文件解析 ====================
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// 按顺序加载多个 .env 文件并合并（后面的文件覆盖前面的同名变量）
+///
+/// 相对路径基于 `base_dir`（命令的工作目录）解析。
+pub fn load_env_files(files: &[String], base_dir: &Path) -> Result<BTreeMap<String, String>, String> {
+    let mut vars = BTreeMap::new();
+
+    for file in files {
+        if file.trim().is_empty() {
+            continue;
+        }
+
+        let path = resolve_env_file_path(file, base_dir);
+
+        if !path.exists() {
+            return Err(format!("环境变量文件不存在: {:?}", path));
+        }
+
+        if !path.is_file() {
+            return Err(format!("环境变量文件路径不是文件: {:?}", path));
+        }
+
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("读取环境变量文件失败: {}。路径: {:?}", e, path))?;
+
+        parse_env_content(&content, &mut vars)
+            .map_err(|(line_no, msg)| format!("环境变量文件格式错误: {:?} 第 {} 行: {}", path, line_no, msg))?;
+    }
+
+    Ok(vars)
+}
+
+/// 解析 .env 文件路径
+fn resolve_env_file_path(file: &str, base_dir: &Path) -> PathBuf {
+    let path = PathBuf::from(file.trim());
+    if path.is_absolute() {
+        path
+    } else {
+        base_dir.join(path)
+    }
+}
+
+/// 解析 .env 内容，结果写入 `vars`
+///
+/// 支持 `KEY=VALUE`、`export KEY=VALUE`、`#` 注释、单引号（原样）和双引号（转义 + 插值），
+/// 插值语法为 `${VAR}`、`${VAR:-default}` 和 `$VAR`，先查找已解析的变量，再查找进程环境变量。
+/// 出错时返回 (行号, 错误信息)。
+fn parse_env_content(content: &str, vars: &mut BTreeMap<String, String>) -> Result<(), (usize, String)> {
+    // Windows 编辑器保存的文件可能带有 UTF-8 BOM
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    for (index, raw_line) in content.lines().enumerate() {
+        let line_no = index + 1;
+        let line = raw_line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line.strip_prefix("export ").map(str::trim_start).unwrap_or(line);
+
+        let Some((key, raw_value)) = line.split_once('=') else {
+            return Err((line_no, "缺少 '='".to_string()));
+        };
+
+        let key = key.trim();
+        if !is_valid_key(key) {
+            return Err((line_no, format!("变量名无效: {:?}", key)));
+        }
+
+        let raw_value = raw_value.trim();
+        let value = if let Some(rest) = raw_value.strip_prefix('\'') {
+            // 单引号：原样保留，不做插值
+            let Some(end) = rest.find('\'') else {
+                return Err((line_no, "单引号未闭合".to_string()));
+            };
+            rest[..end].to_string()
+        } else if let Some(rest) = raw_value.strip_prefix('"') {
+            // 双引号：处理转义和插值
+            let Some(end) = find_closing_double_quote(rest) else {
+                return Err((line_no, "双引号未闭合".to_string()));
+            };
+            interpolate(&unescape_double_quoted(&rest[..end]), vars).map_err(|e| (line_no, e))?
+        } else {
+            // 无引号：去掉行内注释后插值
+            let value = match raw_value.find(" #") {
+                Some(pos) => raw_value[..pos].trim_end(),
+                None => raw_value,
+            };
+            interpolate(value, vars).map_err(|e| (line_no, e))?
+        };
+
+        vars.insert(key.to_string(), value);
+    }
+
+    Ok(())
+}
+
+/// 检查变量名是否合法
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+/// 查找未转义的结束双引号
+fn find_closing_double_quote(s: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (idx, c) in s.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            return Some(idx);
+        }
+    }
+    None
+}
+
+/// 处理双引号内的转义字符（`\$` 保留给插值阶段处理）
+fn unescape_double_quoted(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('t') => result.push('\t'),
+            Some('"') => result.push('"'),
+            Some('\\') => result.push('\\'),
+            Some('$') => result.push_str("\\$"),
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// 变量插值
+fn interpolate(value: &str, vars: &BTreeMap<String, String>) -> Result<String, String> {
+    let lookup = |name: &str| -> Option<String> {
+        vars.get(name).cloned().or_else(|| std::env::var(name).ok())
+    };
+
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.char_indices().peekable();
+
+    while let Some((idx, c)) = chars.next() {
+        if c == '\\' && value[idx + 1..].starts_with('$') {
+            // 转义的 $，原样输出
+            result.push('$');
+            chars.next();
+            continue;
+        }
+
+        if c != '$' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.peek() {
+            Some((_, '{')) => {
+                let start = idx + 2;
+                let Some(len) = value[start..].find('}') else {
+                    return Err("变量引用 \"${\" 未闭合".to_string());
+                };
+                let expr = &value[start..start + len];
+                let (name, default) = match expr.split_once(":-") {
+                    Some((name, default)) => (name, Some(default)),
+                    None => (expr, None),
+                };
+                if !is_valid_key(name) {
+                    return Err(format!("变量引用无效: ${{{}}}", expr));
+                }
+                let resolved = lookup(name).filter(|v| !v.is_empty() || default.is_none());
+                result.push_str(&resolved.unwrap_or_else(|| default.unwrap_or("").to_string()));
+
+                // 跳过 "{...}"
+                for (i, _) in chars.by_ref() {
+                    if i == start + len {
+                        break;
+                    }
+                }
+            }
+            Some((_, next)) if next.is_ascii_alphabetic() || *next == '_' => {
+                let mut name = String::new();
+                while let Some((_, c)) = chars.peek() {
+                    if c.is_ascii_alphanumeric() || *c == '_' {
+                        name.push(*c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                result.push_str(&lookup(&name).unwrap_or_default());
+            }
+            _ => result.push('$'),
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> Result<BTreeMap<String, String>, (usize, String)> {
+        let mut vars = BTreeMap::new();
+        parse_env_content(content, &mut vars)?;
+        Ok(vars)
+    }
+
+    #[test]
+    fn test_parse_basic_and_quotes() {
+        let vars = parse(
+            "# 注释\n\
+             export NODE_ENV=production\n\
+             PORT = 3000 # 行内注释\n\
+             SINGLE='${NOT_EXPANDED}'\n\
+             DOUBLE=\"line1\\nline2\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(vars["NODE_ENV"], "production");
+        assert_eq!(vars["PORT"], "3000");
+        assert_eq!(vars["SINGLE"], "${NOT_EXPANDED}");
+        assert_eq!(vars["DOUBLE"], "line1\nline2");
+    }
+
+    #[test]
+    fn test_parse_strips_bom() {
+        let vars = parse("\u{feff}API_KEY=secret\r\nPORT=3000\r\n").unwrap();
+        assert_eq!(vars["API_KEY"], "secret");
+        assert_eq!(vars["PORT"], "3000");
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let vars = parse(
+            "HOST=localhost\n\
+             PORT=8080\n\
+             URL=http://${HOST}:$PORT/api\n\
+             FALLBACK=${SIGIL_TEST_UNDEFINED_VAR:-default}\n\
+             ESCAPED=\"\\$HOST\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(vars["URL"], "http://localhost:8080/api");
+        assert_eq!(vars["FALLBACK"], "default");
+        assert_eq!(vars["ESCAPED"], "$HOST");
+    }
+
+    #[test]
+    fn test_parse_errors_report_line() {
+        assert_eq!(parse("A=1\nINVALID LINE\n").unwrap_err().0, 2);
+        assert_eq!(parse("A=\"unterminated\n").unwrap_err().0, 1);
+        assert_eq!(parse("A=${B\n").unwrap_err().0, 1);
+        assert_eq!(parse("1A=1\n").unwrap_err().0, 1);
+    }
+
+    #[test]
+    fn test_load_env_files_relative_and_missing() {
+        let dir = std::env::temp_dir().join(format!("sigil_test_dotenv_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(".env"), "A=1\nB=2\n").unwrap();
+        std::fs::write(dir.join(".env.local"), "B=override\nC=${A}${B}\n").unwrap();
+
+        let vars = load_env_files(&[".env".to_string(), ".env.local".to_string()], &dir).unwrap();
+        assert_eq!(vars["A"], "1");
+        assert_eq!(vars["B"], "override");
+        assert_eq!(vars["C"], "1override");
+
+        let err = load_env_files(&[".env.missing".to_string()], &dir).unwrap_err();
+        assert!(err.contains("环境变量文件不存在"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
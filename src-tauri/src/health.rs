@@ -0,0 +1,214 @@
+// ==================== 健康检查 ====================
+
+use serde::{Deserialize, Serialize};
+
+use crate::readiness::{ReadinessChecker, ReadinessProbe};
+
+/// 健康检查方式
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum HealthProbe {
+    /// 不检查
+    #[default]
+    None,
+    /// HTTP GET 返回 2xx（地址为空时使用命令的 URL）
+    Http {
+        #[serde(default)]
+        url: Option<String>,
+    },
+    /// TCP 端口可以连接
+    Tcp {
+        #[serde(default = "default_host")]
+        host: String,
+        port: u16,
+    },
+    /// 执行检查命令，退出码为 0 视为健康（使用命令的工作目录和环境变量）
+    Command { command: String },
+}
+
+fn default_host() -> String {
+    "127.0.0.1".to_string()
+}
+
+/// 健康检查配置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct HealthCheckConfig {
+    pub probe: HealthProbe,
+    /// 检查间隔（秒）
+    pub interval_secs: u64,
+    /// 连续失败多少次后视为不健康
+    pub failure_threshold: u32,
+    /// 不健康时停止并重新执行命令
+    pub restart_on_unhealthy: bool,
+    /// 不健康时发送桌面通知
+    pub notify: bool,
+}
+
+impl Default for HealthCheckConfig {
+    fn default() -> Self {
+        Self {
+            probe: HealthProbe::None,
+            interval_secs: 30,
+            failure_threshold: 3,
+            restart_on_unhealthy: false,
+            notify: true,
+        }
+    }
+}
+
+impl HealthCheckConfig {
+    /// 校验配置（`url` 为命令的 URL）
+    pub fn validate(&self, url: Option<&str>) -> Result<(), String> {
+        if self.probe == HealthProbe::None {
+            return Ok(());
+        }
+        if self.interval_secs == 0 {
+            return Err("健康检查间隔必须大于 0".to_string());
+        }
+        if self.failure_threshold == 0 {
+            return Err("健康检查失败阈值必须大于 0".to_string());
+        }
+        HealthChecker::new(&self.probe, url).map(|_| ())
+    }
+}
+
+/// 健康状态
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Health {
+    Healthy,
+    Unhealthy,
+}
+
+/// 编译后的健康检查
+#[derive(Debug)]
+pub enum HealthChecker {
+    /// HTTP/TCP 检查与就绪检查相同
+    Probe(ReadinessChecker),
+    /// 检查命令行
+    Command(String),
+}
+
+impl HealthChecker {
+    /// 根据配置创建检查器，未配置检查时返回 None
+    pub fn new(probe: &HealthProbe, url: Option<&str>) -> Result<Option<Self>, String> {
+        let probe = match probe {
+            HealthProbe::None => return Ok(None),
+            HealthProbe::Http { url } => ReadinessProbe::Http { url: url.clone() },
+            HealthProbe::Tcp { host, port } => ReadinessProbe::Tcp {
+                host: host.clone(),
+                port: *port,
+            },
+            HealthProbe::Command { command } => {
+                if command.trim().is_empty() {
+                    return Err("健康检查命令不能为空".to_string());
+                }
+                return Ok(Some(HealthChecker::Command(command.trim().to_string())));
+            }
+        };
+        Ok(ReadinessChecker::new(&probe, url, None)?.map(HealthChecker::Probe))
+    }
+}
+
+/// 根据连续的检查结果计算健康状态
+#[derive(Debug, Clone)]
+pub struct HealthTracker {
+    failure_threshold: u32,
+    consecutive_failures: u32,
+    health: Option<Health>,
+}
+
+impl HealthTracker {
+    pub fn new(failure_threshold: u32) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            consecutive_failures: 0,
+            health: None,
+        }
+    }
+
+    /// 连续失败次数
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+
+    /// 记录一次检查结果，健康状态发生变化时返回新状态
+    ///
+    /// 一次成功即视为健康，连续失败达到阈值才视为不健康。
+    pub fn record(&mut self, success: bool) -> Option<Health> {
+        let health = if success {
+            self.consecutive_failures = 0;
+            Health::Healthy
+        } else {
+            self.consecutive_failures += 1;
+            if self.consecutive_failures < self.failure_threshold {
+                return None;
+            }
+            Health::Unhealthy
+        };
+
+        if self.health == Some(health) {
+            return None;
+        }
+        self.health = Some(health);
+        Some(health)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracker_threshold() {
+        let mut tracker = HealthTracker::new(3);
+        assert_eq!(tracker.record(true), Some(Health::Healthy));
+        assert_eq!(tracker.record(true), None);
+
+        // 未达到阈值前保持健康
+        assert_eq!(tracker.record(false), None);
+        assert_eq!(tracker.record(false), None);
+        assert_eq!(tracker.record(false), Some(Health::Unhealthy));
+        assert_eq!(tracker.consecutive_failures(), 3);
+        assert_eq!(tracker.record(false), None);
+
+        // 一次成功即恢复
+        assert_eq!(tracker.record(true), Some(Health::Healthy));
+        assert_eq!(tracker.consecutive_failures(), 0);
+
+        // 从未成功过时达到阈值也视为不健康
+        let mut tracker = HealthTracker::new(1);
+        assert_eq!(tracker.record(false), Some(Health::Unhealthy));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(HealthCheckConfig::default().validate(None).is_ok());
+
+        let http = HealthCheckConfig {
+            probe: HealthProbe::Http { url: None },
+            ..Default::default()
+        };
+        assert!(http.validate(None).is_err());
+        assert!(http.validate(Some("http://localhost:8080/health")).is_ok());
+
+        let command = HealthCheckConfig {
+            probe: HealthProbe::Command {
+                command: " ".to_string(),
+            },
+            ..Default::default()
+        };
+        assert!(command.validate(None).is_err());
+
+        let zero_threshold = HealthCheckConfig {
+            probe: HealthProbe::Tcp {
+                host: default_host(),
+                port: 8080,
+            },
+            failure_threshold: 0,
+            ..Default::default()
+        };
+        assert!(zero_threshold.validate(None).is_err());
+    }
+}
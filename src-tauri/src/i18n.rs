@@ -45,6 +45,14 @@ impl Translations {
         }
     }
 
+    /// 获取历史运行日志窗口标题
+    pub fn run_log_window_title(lang: Language, command_name: &str, run_id: i64) -> String {
+        match lang {
+            Language::ZhCN => format!("运行日志 #{} - {}", run_id, command_name),
+            Language::EnUS => format!("Run Log #{} - {}", run_id, command_name),
+        }
+    }
+
     /// 获取错误消息
     pub fn error_get_main_window(lang: Language) -> String {
         match lang {
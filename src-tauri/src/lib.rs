@@ -1,6 +1,8 @@
 // ==================== 模块声明 ====================
 
+mod ansi;
 mod autostart;
+mod command_params;
 mod command_runner;
 mod commands;
 mod config;
@@ -8,11 +10,28 @@ mod constants;
 mod db;
 #[cfg(test)]
 mod db_test;
+mod dotenv;
 mod error;
+mod health;
 mod i18n;
+mod log_buffer;
 mod logger;
 mod monitor;
+mod pipeline;
+mod pipeline_runner;
+mod process_tree;
+mod pty;
+mod readiness;
+mod resource_limits;
+mod restart;
+mod run_log;
+mod run_queue;
+mod schedule;
+mod scheduler;
+mod shell;
 mod single_instance;
+mod success;
+mod trigger;
 mod window;
 
 // ==================== 引入依赖 ====================
@@ -22,6 +41,9 @@ use commands::*;
 use db::Database;
 use monitor::{start_high_frequency_monitor, start_low_frequency_monitor};
 use monitor::{DiskMonitorState, MonitorState};
+use pipeline_runner::PipelineRunner;
+use run_log::RunLogStore;
+use scheduler::{start_scheduler, CommandScheduler};
 use tauri::Manager;
 use window::{setup_main_window, setup_tray};
 
@@ -120,6 +142,19 @@ pub fn run() {
                     return Err(error_msg.into());
                 }
             };
+            // 初始化运行日志存储，并按保留策略清理旧日志
+            match RunLogStore::init(app.handle()) {
+                Ok(run_log_store) => {
+                    let retention = config::ConfigManager::get_run_log_retention(&database);
+                    let deleted = run_log_store.cleanup(retention);
+                    if deleted > 0 {
+                        log::info!("已清理 {} 个过期的运行日志文件", deleted);
+                    }
+                    app.manage(run_log_store);
+                }
+                Err(e) => log::error!("运行日志存储初始化失败: {}", e),
+            }
+
             app.manage(database);
 
             // 设置主窗口
@@ -189,10 +224,17 @@ pub fn run() {
             let command_runner = CommandRunner::new(app.handle().clone());
             app.manage(command_runner);
 
+            // 初始化流水线运行器
+            app.manage(PipelineRunner::new(app.handle().clone()));
+
             // 启动监控后台线程
             start_high_frequency_monitor(app.handle().clone());
             start_low_frequency_monitor(app.handle().clone());
 
+            // 启动定时执行调度线程
+            app.manage(CommandScheduler::default());
+            start_scheduler(app.handle().clone());
+
             // 同步开机自启动状态
             #[cfg(target_os = "windows")]
             {
@@ -236,10 +278,12 @@ pub fn run() {
             // 系统监控命令
             get_system_info,
             get_disk_info,
+            get_command_metrics,
             // 数据库命令
             create_command,
             get_all_commands,
             get_command_by_id,
+            get_command_parameters,
             update_command,
             delete_command,
             update_sort_orders,
@@ -249,8 +293,29 @@ pub fn run() {
             // 命令执行命令
             execute_command,
             stop_command,
+            stop_command_instance,
+            send_command_input,
             get_command_state,
             get_all_command_states,
+            get_command_instances,
+            get_run_queue,
+            cancel_queued_run,
+            move_queued_run,
+            // 定时执行命令
+            get_command_schedules,
+            preview_schedule,
+            // 流水线命令
+            create_pipeline,
+            get_all_pipelines,
+            update_pipeline,
+            delete_pipeline,
+            run_pipeline,
+            stop_pipeline,
+            get_pipeline_states,
+            // 运行历史命令
+            get_command_runs,
+            delete_command_runs,
+            clear_command_runs,
             // 导入导出命令
             export_commands,
             import_commands,
@@ -258,8 +323,11 @@ pub fn run() {
             read_import_file,
             // 日志相关命令
             get_command_logs,
+            get_instance_logs,
             clear_command_logs,
+            get_run_logs,
             open_log_window,
+            open_run_log_window,
             update_log_window_title,
             // 开机自启动命令
             enable_autostart,
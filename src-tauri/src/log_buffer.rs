@@ -0,0 +1,292 @@
+// ==================== 内存日志缓冲 ====================
+
+use crate::ansi::{StyledLine, StyledSegment};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// 日志输出流
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+    /// 用户发送给命令的输入（回显）
+    Stdin,
+}
+
+impl LogStream {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogStream::Stdout => "stdout",
+            LogStream::Stderr => "stderr",
+            LogStream::Stdin => "stdin",
+        }
+    }
+}
+
+/// 日志行数据
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LogLine {
+    pub command_id: i64,
+    /// 所属运行实例的运行 ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<i64>,
+    /// 序号（单调递增，即该行的游标）
+    pub seq: u64,
+    /// 写入时间（RFC 3339，精确到毫秒）
+    pub timestamp: String,
+    pub stream: LogStream,
+    /// 纯文本（不含流前缀和 ANSI 转义序列）
+    pub text: String,
+    /// ANSI 样式片段，整行都没有样式时为空
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub segments: Vec<StyledSegment>,
+}
+
+/// 日志分页结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogPage {
+    /// 返回的日志行
+    pub lines: Vec<LogLine>,
+    /// 第一行的游标
+    pub cursor: u64,
+    /// 下一次增量读取使用的游标
+    pub next_cursor: u64,
+    /// 是否还有更多日志未返回
+    pub has_more: bool,
+    /// 因超出容量被丢弃的行数
+    pub dropped: u64,
+}
+
+/// 固定容量的环形日志缓冲
+///
+/// 每一行都有一个单调递增的序号（从 0 开始），序号即游标，超出容量时丢弃最旧的行。
+/// 同一命令的各个运行实例共用一个序号计数器，序号在命令内不重复，但单个缓冲中的序号可能不连续。
+/// 清空缓冲不会重置序号，已持有游标的窗口可以继续增量读取。
+#[derive(Debug, Clone)]
+pub struct LogBuffer {
+    command_id: i64,
+    run_id: Option<i64>,
+    lines: VecDeque<LogLine>,
+    capacity: usize,
+    /// 下一行的序号
+    next_seq: Arc<AtomicU64>,
+    dropped: u64,
+}
+
+impl LogBuffer {
+    /// 创建指定容量的日志缓冲（容量至少为 1）
+    pub fn new(command_id: i64, capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            command_id,
+            run_id: None,
+            lines: VecDeque::with_capacity(capacity.min(1024)),
+            capacity,
+            next_seq: Arc::new(AtomicU64::new(0)),
+            dropped: 0,
+        }
+    }
+
+    /// 创建一次运行的日志缓冲，与 `previous`（同一命令已有的缓冲）共用序号计数器
+    ///
+    /// 同一命令的新运行从已有的序号继续，同时运行的实例也不会产生重复的序号，日志窗口无需重新同步。
+    pub fn for_run(
+        command_id: i64,
+        run_id: i64,
+        capacity: usize,
+        previous: Option<&LogBuffer>,
+    ) -> Self {
+        let mut buffer = Self::new(command_id, capacity);
+        buffer.run_id = Some(run_id);
+        if let Some(previous) = previous {
+            buffer.next_seq = Arc::clone(&previous.next_seq);
+        }
+        buffer
+    }
+
+    /// 追加一行并返回分配了序号的日志行，超出容量时丢弃最旧的行
+    pub fn push(&mut self, stream: LogStream, line: StyledLine) -> LogLine {
+        if self.lines.len() >= self.capacity {
+            self.lines.pop_front();
+            self.dropped += 1;
+        }
+
+        let line = LogLine {
+            command_id: self.command_id,
+            run_id: self.run_id,
+            seq: self.next_seq.fetch_add(1, Ordering::SeqCst),
+            timestamp: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+            stream,
+            text: line.text,
+            segments: line.segments,
+        };
+        self.lines.push_back(line.clone());
+        line
+    }
+
+    /// 清空日志（游标继续递增）
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.dropped = 0;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// 下一行的游标
+    pub fn next_cursor(&self) -> u64 {
+        self.next_seq.load(Ordering::SeqCst)
+    }
+
+    /// 最后 `count` 行
+    pub fn tail(&self, count: usize) -> impl Iterator<Item = &LogLine> {
+        self.lines.iter().skip(self.lines.len().saturating_sub(count))
+    }
+
+    /// 分页读取日志
+    ///
+    /// 指定 `cursor` 时从该游标开始向后读取（早于最旧一行时从最旧一行开始）；
+    /// 未指定时返回最新的 `limit` 行。
+    pub fn page(&self, cursor: Option<u64>, limit: usize) -> LogPage {
+        merged_page(std::iter::once(self), cursor, limit)
+    }
+}
+
+/// 按序号合并读取同一命令多个运行实例的日志，分页规则与 [`LogBuffer::page`] 相同
+pub fn merged_page<'a>(
+    buffers: impl IntoIterator<Item = &'a LogBuffer>,
+    cursor: Option<u64>,
+    limit: usize,
+) -> LogPage {
+    let buffers: Vec<&LogBuffer> = buffers.into_iter().collect();
+    let end = buffers.iter().map(|buffer| buffer.next_cursor()).max().unwrap_or(0);
+
+    let mut lines: Vec<&LogLine> = buffers
+        .iter()
+        .flat_map(|buffer| buffer.lines.iter())
+        .filter(|line| line.seq >= cursor.unwrap_or(0))
+        .collect();
+    lines.sort_by_key(|line| line.seq);
+
+    let skip = match cursor {
+        Some(_) => 0,
+        None => lines.len().saturating_sub(limit),
+    };
+    let has_more = lines.len() - skip > limit;
+    let lines: Vec<LogLine> = lines
+        .into_iter()
+        .skip(skip)
+        .take(limit)
+        .cloned()
+        .collect();
+    let next_cursor = match lines.last() {
+        Some(last) if has_more => last.seq + 1,
+        _ => end,
+    };
+
+    LogPage {
+        cursor: lines.first().map_or(next_cursor, |line| line.seq),
+        lines,
+        next_cursor,
+        has_more,
+        dropped: buffers.iter().map(|buffer| buffer.dropped).sum(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filled(capacity: usize, count: usize) -> LogBuffer {
+        let mut buffer = LogBuffer::new(1, capacity);
+        for i in 0..count {
+            let stream = if i % 2 == 0 { LogStream::Stdout } else { LogStream::Stderr };
+            buffer.push(stream, StyledLine::plain(format!("line {}", i)));
+        }
+        buffer
+    }
+
+    fn texts(lines: &[LogLine]) -> Vec<&str> {
+        lines.iter().map(|line| line.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_ring_buffer_drops_oldest() {
+        let buffer = filled(3, 5);
+        assert_eq!(buffer.dropped, 2);
+
+        let page = buffer.page(Some(0), 10);
+        assert_eq!(page.cursor, 2);
+        assert_eq!(texts(&page.lines), vec!["line 2", "line 3", "line 4"]);
+        assert_eq!(page.lines[0].seq, 2);
+        assert_eq!(page.lines[0].stream, LogStream::Stdout);
+        assert_eq!(page.lines[1].stream, LogStream::Stderr);
+        assert_eq!(page.next_cursor, 5);
+        assert!(!page.has_more);
+        assert_eq!(page.dropped, 2);
+
+        let tail: Vec<&str> = buffer.tail(2).map(|line| line.text.as_str()).collect();
+        assert_eq!(tail, vec!["line 3", "line 4"]);
+    }
+
+    #[test]
+    fn test_page_with_cursor_and_limit() {
+        let mut buffer = filled(100, 10);
+
+        // 未指定游标时返回最新的若干行
+        let page = buffer.page(None, 3);
+        assert_eq!(page.cursor, 7);
+        assert_eq!(texts(&page.lines), vec!["line 7", "line 8", "line 9"]);
+
+        let page = buffer.page(Some(2), 3);
+        assert_eq!(texts(&page.lines), vec!["line 2", "line 3", "line 4"]);
+        assert_eq!(page.next_cursor, 5);
+        assert!(page.has_more);
+
+        // 清空后序号继续递增
+        buffer.clear();
+        let line = buffer.push(LogStream::Stdout, StyledLine::plain("after clear"));
+        assert_eq!(line.seq, 10);
+        let page = buffer.page(Some(10), 10);
+        assert_eq!(page.cursor, 10);
+        assert_eq!(texts(&page.lines), vec!["after clear"]);
+        assert_eq!(buffer.page(Some(11), 10).lines.len(), 0);
+
+        // 新的一次运行从上一次运行的游标继续
+        let mut next = LogBuffer::for_run(1, 7, 100, Some(&buffer));
+        let line = next.push(LogStream::Stdout, StyledLine::plain("next run"));
+        assert_eq!((line.seq, line.run_id), (11, Some(7)));
+        assert_eq!(next.page(Some(0), 10).cursor, 11);
+    }
+
+    #[test]
+    fn test_concurrent_runs_share_seq() {
+        let mut first = LogBuffer::for_run(1, 1, 100, None);
+        let mut second = LogBuffer::for_run(1, 2, 100, Some(&first));
+        for i in 0..3 {
+            first.push(LogStream::Stdout, StyledLine::plain(format!("a{}", i)));
+            second.push(LogStream::Stdout, StyledLine::plain(format!("b{}", i)));
+        }
+
+        // 单个实例的序号不连续，但增量读取不会遗漏或重复
+        let page = second.page(Some(0), 2);
+        assert_eq!(texts(&page.lines), vec!["b0", "b1"]);
+        assert_eq!((page.cursor, page.next_cursor), (1, 4));
+        assert!(page.has_more);
+        let page = second.page(Some(page.next_cursor), 10);
+        assert_eq!(texts(&page.lines), vec!["b2"]);
+        assert_eq!(page.next_cursor, 6);
+
+        // 合并读取按序号排列所有实例的日志
+        let page = merged_page([&first, &second], Some(2), 10);
+        assert_eq!(texts(&page.lines), vec!["a1", "b1", "a2", "b2"]);
+        assert_eq!(page.next_cursor, 6);
+        let page = merged_page([&first, &second], None, 3);
+        assert_eq!(texts(&page.lines), vec!["b1", "a2", "b2"]);
+        assert_eq!(page.cursor, 3);
+    }
+}
@@ -1,10 +1,27 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
-use sysinfo::{Disks, System};
-use tauri::{AppHandle, Manager};
+use sysinfo::{Disks, Pid, Process, System};
+use tauri::{AppHandle, Emitter, EventTarget, Manager};
+
+use crate::command_runner::CommandRunner;
+use crate::process_tree;
+
+#[cfg(target_os = "windows")]
+use std::collections::HashMap;
+#[cfg(target_os = "windows")]
+use windows::Win32::Foundation::CloseHandle;
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Diagnostics::ToolHelp::{
+    CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS,
+};
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Threading::{
+    GetProcessHandleCount, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+};
 
 // ==================== 数据结构定义 ====================
 
@@ -25,11 +42,38 @@ pub struct DiskInfo {
     pub disk_percent: f32, // 磁盘占用百分比
 }
 
+/// 命令进程树的资源占用（同一命令的所有运行实例合计，包括后代进程）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandMetrics {
+    pub command_id: i64,
+    pub run_ids: Vec<i64>,    // 运行中的实例（运行 ID）
+    pub process_count: usize, // 进程数
+    pub cpu_usage: f32,       // CPU 占用百分比（占全部 CPU）
+    pub memory_rss: u64,      // 常驻内存（字节）
+    pub thread_count: u64,    // 线程数
+    pub handle_count: u64,    // 打开的句柄数（Unix 为文件描述符数）
+}
+
+impl CommandMetrics {
+    fn new(command_id: i64) -> Self {
+        Self {
+            command_id,
+            run_ids: Vec::new(),
+            process_count: 0,
+            cpu_usage: 0.0,
+            memory_rss: 0,
+            thread_count: 0,
+            handle_count: 0,
+        }
+    }
+}
+
 // ==================== 监控状态管理 ====================
 
-/// 高频监控状态（CPU + 内存）- 1Hz 更新
+/// 高频监控状态（CPU + 内存 + 命令资源占用）- 1Hz 更新
 pub struct MonitorState {
     pub system_info: Arc<Mutex<SystemInfo>>,
+    pub command_metrics: Arc<Mutex<Vec<CommandMetrics>>>,
     pub monitoring_thread: Arc<AtomicBool>,
 }
 
@@ -42,6 +86,7 @@ impl Default for MonitorState {
                 memory_total: 0,
                 memory_percent: 0.0,
             })),
+            command_metrics: Arc::new(Mutex::new(Vec::new())),
             monitoring_thread: Arc::new(AtomicBool::new(false)),
         }
     }
@@ -68,7 +113,7 @@ impl Default for DiskMonitorState {
 
 // ==================== 后台监控线程 ====================
 
-/// 启动高频监控线程（CPU + 内存）- 1Hz
+/// 启动高频监控线程（CPU + 内存 + 命令资源占用）- 1Hz
 pub fn start_high_frequency_monitor(app: AppHandle) {
     let state = app.state::<MonitorState>();
 
@@ -80,10 +125,12 @@ pub fn start_high_frequency_monitor(app: AppHandle) {
     state.monitoring_thread.store(true, Ordering::Relaxed);
 
     let system_info_arc = state.system_info.clone();
+    let command_metrics_arc = state.command_metrics.clone();
     let monitoring_flag = state.monitoring_thread.clone();
 
     thread::spawn(move || {
         let mut system = System::new_all();
+        let mut had_metrics = false;
 
         // 第一次刷新
         system.refresh_all();
@@ -114,12 +161,131 @@ pub fn start_high_frequency_monitor(app: AppHandle) {
                 info.memory_percent = memory_percent;
             }
 
+            // 采样受管命令的进程树（没有运行中的命令时不刷新进程列表）
+            let running = app
+                .try_state::<CommandRunner>()
+                .map(|runner| runner.running_processes())
+                .unwrap_or_default();
+            let metrics = sample_command_metrics(&mut system, &running);
+            if let Ok(mut command_metrics) = command_metrics_arc.lock() {
+                *command_metrics = metrics.clone();
+            }
+            // 最后一个命令结束时再发送一次空列表
+            if !metrics.is_empty() || had_metrics {
+                had_metrics = !metrics.is_empty();
+                let _ = app.emit_to(EventTarget::Any, "command-metrics", metrics);
+            }
+
             // 每 1000ms 更新一次（1Hz）
             thread::sleep(Duration::from_millis(1000));
         }
     });
 }
 
+/// 汇总每个命令的进程树资源占用
+///
+/// 同一进程只计入一次（按运行顺序归属于第一个包含它的命令）。
+fn sample_command_metrics(system: &mut System, running: &[(i64, i64, u32)]) -> Vec<CommandMetrics> {
+    if running.is_empty() {
+        return Vec::new();
+    }
+    system.refresh_processes();
+    // 进程的 CPU 占用以单个核为 100%，换算为占全部 CPU 的百分比
+    let cpu_count = system.cpus().len().max(1) as f32;
+    let counter = ResourceCounter::new();
+
+    let mut metrics: BTreeMap<i64, CommandMetrics> = BTreeMap::new();
+    let mut seen: HashSet<Pid> = HashSet::new();
+    for &(command_id, run_id, pid) in running {
+        let entry = metrics
+            .entry(command_id)
+            .or_insert_with(|| CommandMetrics::new(command_id));
+        entry.run_ids.push(run_id);
+
+        for pid in process_tree::tree_pids(system, pid) {
+            let Some(process) = system.process(pid) else {
+                continue;
+            };
+            if !seen.insert(pid) {
+                continue;
+            }
+            entry.process_count += 1;
+            entry.cpu_usage += process.cpu_usage() / cpu_count;
+            entry.memory_rss += process.memory();
+            entry.thread_count += counter.threads(process);
+            entry.handle_count += counter.handles(pid);
+        }
+    }
+    metrics.into_values().collect()
+}
+
+/// 统计进程的线程数和句柄数（sysinfo 不提供，按系统分别实现，无法读取时计为 0）
+struct ResourceCounter {
+    /// Windows: 进程快照中的线程数
+    #[cfg(target_os = "windows")]
+    threads: HashMap<u32, u32>,
+}
+
+impl ResourceCounter {
+    fn new() -> Self {
+        #[cfg(target_os = "windows")]
+        {
+            let mut threads = HashMap::new();
+            unsafe {
+                if let Ok(snapshot) = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) {
+                    let mut entry: PROCESSENTRY32W = std::mem::zeroed();
+                    entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+                    let mut next = Process32FirstW(snapshot, &mut entry);
+                    while next.is_ok() {
+                        threads.insert(entry.th32ProcessID, entry.cntThreads);
+                        next = Process32NextW(snapshot, &mut entry);
+                    }
+                    let _ = CloseHandle(snapshot);
+                }
+            }
+            Self { threads }
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        Self {}
+    }
+
+    fn threads(&self, process: &Process) -> u64 {
+        #[cfg(target_os = "windows")]
+        {
+            let pid = process.pid().as_u32();
+            self.threads.get(&pid).copied().unwrap_or(0) as u64
+        }
+
+        // Linux: 每个线程（包括主线程）是 /proc/<pid>/task 下的一项
+        #[cfg(not(target_os = "windows"))]
+        std::fs::read_dir(format!("/proc/{}/task", process.pid().as_u32()))
+            .map_or(0, |entries| entries.count() as u64)
+    }
+
+    fn handles(&self, pid: Pid) -> u64 {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            let Ok(handle) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid.as_u32())
+            else {
+                return 0;
+            };
+            let mut count = 0u32;
+            let result = GetProcessHandleCount(handle, &mut count);
+            let _ = CloseHandle(handle);
+            if result.is_ok() {
+                count as u64
+            } else {
+                0
+            }
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        std::fs::read_dir(format!("/proc/{}/fd", pid.as_u32()))
+            .map_or(0, |entries| entries.count() as u64)
+    }
+}
+
 /// 启动低频监控线程（磁盘）- 每分钟
 pub fn start_low_frequency_monitor(app: AppHandle) {
     let state = app.state::<DiskMonitorState>();
@@ -0,0 +1,262 @@
+// ==================== 命令流水线 ====================
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// 步骤之间的依赖条件
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum StepCondition {
+    /// 前置步骤执行成功后启动
+    #[default]
+    AfterSuccess,
+    /// 前置步骤启动并就绪后即可启动（适用于常驻服务）
+    AfterStart,
+    /// 前置步骤结束后启动，无论成功与否
+    Always,
+}
+
+/// 对前置步骤的依赖
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StepDependency {
+    /// 前置步骤的序号（只能依赖排在前面的步骤）
+    pub step: usize,
+    #[serde(default)]
+    pub condition: StepCondition,
+}
+
+/// 流水线中的一个步骤
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PipelineStep {
+    /// 执行的已保存命令 ID
+    pub command_id: i64,
+    /// 没有依赖的步骤在流水线开始时立即启动
+    #[serde(default)]
+    pub depends_on: Vec<StepDependency>,
+}
+
+/// 步骤状态
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum StepStatus {
+    Pending,   // 等待前置步骤
+    Running,   // 正在运行
+    Succeeded, // 执行成功
+    Failed,    // 执行失败（包括启动失败）
+    Stopped,   // 被停止
+    Skipped,   // 依赖条件无法满足，未执行
+}
+
+impl StepStatus {
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, StepStatus::Pending | StepStatus::Running)
+    }
+}
+
+/// 流水线状态
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PipelineStatus {
+    Running,
+    Succeeded,
+    Failed,
+    Stopped,
+}
+
+/// 步骤运行状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepState {
+    pub command_id: i64,
+    pub status: StepStatus,
+    /// 步骤对应的运行历史记录 ID（启动后有值）
+    pub run_id: Option<i64>,
+    /// 是否已就绪（未配置就绪检查的命令启动即就绪）
+    pub ready: bool,
+    /// 启动失败等错误信息
+    pub error: Option<String>,
+}
+
+impl StepState {
+    pub fn new(command_id: i64) -> Self {
+        Self {
+            command_id,
+            status: StepStatus::Pending,
+            run_id: None,
+            ready: false,
+            error: None,
+        }
+    }
+}
+
+/// 待启动步骤的依赖检查结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Readiness {
+    /// 继续等待前置步骤
+    Wait,
+    /// 可以启动
+    Ready,
+    /// 依赖条件已无法满足
+    Skip,
+}
+
+/// 校验流水线步骤：至少一个步骤、命令不重复、只依赖排在前面的步骤（保证无环）
+pub fn validate_steps(steps: &[PipelineStep]) -> Result<(), String> {
+    if steps.is_empty() {
+        return Err("流水线至少需要一个步骤".to_string());
+    }
+
+    let mut command_ids = HashSet::new();
+    for (index, step) in steps.iter().enumerate() {
+        if !command_ids.insert(step.command_id) {
+            return Err(format!("命令 {} 在流水线中重复出现", step.command_id));
+        }
+        if step
+            .depends_on
+            .iter()
+            .any(|dependency| dependency.step >= index)
+        {
+            return Err(format!("步骤 {} 只能依赖排在它前面的步骤", index + 1));
+        }
+    }
+    Ok(())
+}
+
+/// 检查步骤的依赖是否满足
+pub fn readiness(step: &PipelineStep, states: &[StepState]) -> Readiness {
+    let mut ready = true;
+    for dependency in &step.depends_on {
+        let state = &states[dependency.step];
+        let satisfied = match dependency.condition {
+            StepCondition::AfterSuccess => match state.status {
+                StepStatus::Succeeded => Some(true),
+                StepStatus::Pending | StepStatus::Running => Some(false),
+                _ => None,
+            },
+            StepCondition::AfterStart => match state.status {
+                _ if state.ready => Some(true),
+                StepStatus::Pending | StepStatus::Running => Some(false),
+                _ => None,
+            },
+            StepCondition::Always => Some(state.status.is_finished()),
+        };
+        match satisfied {
+            Some(satisfied) => ready &= satisfied,
+            None => return Readiness::Skip,
+        }
+    }
+
+    if ready {
+        Readiness::Ready
+    } else {
+        Readiness::Wait
+    }
+}
+
+/// 所有步骤结束后的流水线结果，仍有步骤未结束时返回 None
+pub fn final_status(states: &[StepState], stop_requested: bool) -> Option<PipelineStatus> {
+    if !states.iter().all(|state| state.status.is_finished()) {
+        return None;
+    }
+    let status = if stop_requested {
+        PipelineStatus::Stopped
+    } else if states
+        .iter()
+        .all(|state| state.status == StepStatus::Succeeded)
+    {
+        PipelineStatus::Succeeded
+    } else {
+        PipelineStatus::Failed
+    };
+    Some(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(command_id: i64, depends_on: &[(usize, StepCondition)]) -> PipelineStep {
+        PipelineStep {
+            command_id,
+            depends_on: depends_on
+                .iter()
+                .map(|&(step, condition)| StepDependency { step, condition })
+                .collect(),
+        }
+    }
+
+    fn states(statuses: &[(StepStatus, bool)]) -> Vec<StepState> {
+        statuses
+            .iter()
+            .enumerate()
+            .map(|(index, &(status, ready))| StepState {
+                status,
+                run_id: ready.then_some(index as i64),
+                ready,
+                ..StepState::new(index as i64)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_steps() {
+        use StepCondition::*;
+
+        assert!(validate_steps(&[]).is_err());
+        assert!(validate_steps(&[step(1, &[]), step(1, &[])]).is_err());
+        assert!(validate_steps(&[step(1, &[(1, AfterSuccess)]), step(2, &[])]).is_err());
+        assert!(validate_steps(&[
+            step(1, &[]),
+            step(2, &[(0, AfterSuccess)]),
+            step(3, &[(0, AfterSuccess)]),
+            step(4, &[(1, AfterStart), (2, AfterStart)]),
+        ])
+        .is_ok());
+    }
+
+    #[test]
+    fn test_readiness_by_condition() {
+        use StepStatus::*;
+
+        let after_success = step(9, &[(0, StepCondition::AfterSuccess)]);
+        let after_start = step(9, &[(0, StepCondition::AfterStart)]);
+        let always = step(9, &[(0, StepCondition::Always)]);
+
+        let running = states(&[(Running, true)]);
+        assert_eq!(readiness(&after_success, &running), Readiness::Wait);
+        assert_eq!(readiness(&after_start, &running), Readiness::Ready);
+        assert_eq!(readiness(&always, &running), Readiness::Wait);
+
+        let failed = states(&[(Failed, true)]);
+        assert_eq!(readiness(&after_success, &failed), Readiness::Skip);
+        assert_eq!(readiness(&after_start, &failed), Readiness::Ready);
+        assert_eq!(readiness(&always, &failed), Readiness::Ready);
+
+        // 等待就绪的步骤不满足“启动后”条件，未就绪即结束则跳过
+        let starting = states(&[(Running, false)]);
+        assert_eq!(readiness(&after_start, &starting), Readiness::Wait);
+        let not_ready = states(&[(Failed, false)]);
+        assert_eq!(readiness(&after_start, &not_ready), Readiness::Skip);
+    }
+
+    #[test]
+    fn test_final_status() {
+        use StepStatus::*;
+
+        assert_eq!(
+            final_status(&states(&[(Succeeded, true), (Running, true)]), false),
+            None
+        );
+        assert_eq!(
+            final_status(&states(&[(Succeeded, true), (Succeeded, true)]), false),
+            Some(PipelineStatus::Succeeded)
+        );
+        assert_eq!(
+            final_status(&states(&[(Failed, true), (Skipped, false)]), false),
+            Some(PipelineStatus::Failed)
+        );
+        assert_eq!(
+            final_status(&states(&[(Stopped, true), (Stopped, false)]), true),
+            Some(PipelineStatus::Stopped)
+        );
+    }
+}
@@ -0,0 +1,306 @@
+// ==================== 流水线执行 ====================
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, EventTarget, Manager};
+
+use crate::command_runner::{CommandRunner, CommandStatus, ExecuteCommandParams, RunTrigger};
+use crate::constants;
+use crate::db::{Database, Pipeline};
+use crate::pipeline::{self, PipelineStatus, Readiness, StepState, StepStatus};
+
+/// 流水线运行状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineRunState {
+    pub pipeline_id: i64,
+    pub pipeline_name: String,
+    pub status: PipelineStatus,
+    /// 与流水线步骤一一对应
+    pub steps: Vec<StepState>,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+}
+
+/// 步骤状态变化事件数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineStepEvent {
+    pub pipeline_id: i64,
+    /// 步骤序号
+    pub step: usize,
+    pub state: StepState,
+}
+
+/// 流水线运行器 - 按依赖关系通过 CommandRunner 依次启动各步骤
+pub struct PipelineRunner {
+    runs: Arc<Mutex<HashMap<i64, PipelineRunState>>>,
+    stop_requests: Arc<Mutex<HashSet<i64>>>,
+    app_handle: AppHandle,
+}
+
+impl PipelineRunner {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self {
+            runs: Arc::new(Mutex::new(HashMap::new())),
+            stop_requests: Arc::new(Mutex::new(HashSet::new())),
+            app_handle,
+        }
+    }
+
+    /// 所有流水线最近一次的运行状态
+    pub fn get_states(&self) -> Vec<PipelineRunState> {
+        let runs = self.runs.lock().unwrap();
+        let mut states: Vec<PipelineRunState> = runs.values().cloned().collect();
+        states.sort_by_key(|state| state.pipeline_id);
+        states
+    }
+
+    /// 流水线是否正在运行
+    pub fn is_running(&self, pipeline_id: i64) -> bool {
+        let runs = self.runs.lock().unwrap();
+        runs.get(&pipeline_id)
+            .is_some_and(|state| state.status == PipelineStatus::Running)
+    }
+
+    /// 运行流水线
+    pub fn run(&self, pipeline: Pipeline) -> Result<(), String> {
+        pipeline::validate_steps(&pipeline.steps)?;
+
+        let state = {
+            let mut runs = self.runs.lock().unwrap();
+            if runs
+                .get(&pipeline.id)
+                .is_some_and(|state| state.status == PipelineStatus::Running)
+            {
+                return Err("流水线正在运行".to_string());
+            }
+            let state = PipelineRunState {
+                pipeline_id: pipeline.id,
+                pipeline_name: pipeline.name.clone(),
+                status: PipelineStatus::Running,
+                steps: pipeline
+                    .steps
+                    .iter()
+                    .map(|step| StepState::new(step.command_id))
+                    .collect(),
+                started_at: chrono::Local::now().to_rfc3339(),
+                ended_at: None,
+            };
+            runs.insert(pipeline.id, state.clone());
+            state
+        };
+        self.stop_requests.lock().unwrap().remove(&pipeline.id);
+
+        log::info!("开始运行流水线 {}（{}）", pipeline.id, pipeline.name);
+        self.emit_state(&state);
+
+        let runner = self.clone_for_thread();
+        std::thread::spawn(move || {
+            runner.orchestrate(pipeline);
+        });
+
+        Ok(())
+    }
+
+    /// 停止流水线：停止所有运行中的步骤，未启动的步骤不再启动
+    pub fn stop(&self, pipeline_id: i64) -> Result<(), String> {
+        if !self.is_running(pipeline_id) {
+            return Err("流水线未在运行".to_string());
+        }
+        self.stop_requests.lock().unwrap().insert(pipeline_id);
+        Ok(())
+    }
+
+    /// 编排流水线直到所有步骤结束
+    fn orchestrate(&self, pipeline: Pipeline) {
+        let (Some(runner), Some(database)) = (
+            self.app_handle.try_state::<CommandRunner>(),
+            self.app_handle.try_state::<Database>(),
+        ) else {
+            return;
+        };
+
+        let mut steps: Vec<StepState> = pipeline
+            .steps
+            .iter()
+            .map(|step| StepState::new(step.command_id))
+            .collect();
+        let mut stop_sent = false;
+
+        let status = loop {
+            let stop_requested = self.stop_requests.lock().unwrap().contains(&pipeline.id);
+            let mut changed = Vec::new();
+
+            // 同步运行中步骤的状态
+            for (index, step) in steps.iter_mut().enumerate() {
+                let Some(run_id) = step.run_id.filter(|_| step.status == StepStatus::Running)
+                else {
+                    continue;
+                };
+                // 排队的运行启动后、自动重启后跟随新的运行
+                let run_id = runner.follow_run(run_id);
+                if step.run_id != Some(run_id) {
+                    step.run_id = Some(run_id);
+                    changed.push(index);
+                }
+                let Some(state) = runner.get_instance_state(run_id) else {
+                    // 排队中的运行被取消
+                    step.status = StepStatus::Stopped;
+                    changed.push(index);
+                    continue;
+                };
+                if !step.ready
+                    && matches!(state.status, CommandStatus::Running | CommandStatus::Ready)
+                {
+                    step.ready = true;
+                    changed.push(index);
+                }
+                let status = match state.status {
+                    CommandStatus::Success => StepStatus::Succeeded,
+                    CommandStatus::Failed | CommandStatus::TimedOut
+                        if state.next_retry_at.is_none() =>
+                    {
+                        StepStatus::Failed
+                    }
+                    CommandStatus::Stopped => StepStatus::Stopped,
+                    _ => StepStatus::Running,
+                };
+                if status != step.status {
+                    step.status = status;
+                    changed.push(index);
+                }
+            }
+
+            if stop_requested {
+                // 停止所有运行中的步骤，未启动的步骤标记为已停止
+                for (index, step) in steps.iter_mut().enumerate() {
+                    match step.status {
+                        StepStatus::Running if !stop_sent => {
+                            let result = step
+                                .run_id
+                                .ok_or_else(|| "步骤没有运行 ID".to_string())
+                                .and_then(|run_id| runner.stop_instance(run_id));
+                            if let Err(e) = result {
+                                log::warn!("停止流水线步骤 {} 失败: {}", step.command_id, e);
+                            }
+                        }
+                        StepStatus::Pending => {
+                            step.status = StepStatus::Stopped;
+                            changed.push(index);
+                        }
+                        _ => {}
+                    }
+                }
+                stop_sent = true;
+            } else {
+                // 启动依赖已满足的步骤（启动后状态可能立即影响后续步骤，按顺序检查）
+                for (index, definition) in pipeline.steps.iter().enumerate() {
+                    if steps[index].status != StepStatus::Pending {
+                        continue;
+                    }
+                    match pipeline::readiness(definition, &steps) {
+                        Readiness::Wait => continue,
+                        Readiness::Skip => steps[index].status = StepStatus::Skipped,
+                        Readiness::Ready => {
+                            let result = database
+                                .get_command_by_id(definition.command_id)
+                                .and_then(|command| {
+                                    let params =
+                                        ExecuteCommandParams::from_command(command, HashMap::new());
+                                    runner.execute(params, RunTrigger::Pipeline)
+                                });
+                            let step = &mut steps[index];
+                            match result {
+                                Ok(run_id) => {
+                                    let state = runner.get_instance_state(run_id);
+                                    step.status = StepStatus::Running;
+                                    step.run_id = Some(run_id);
+                                    step.ready = state.is_some_and(|state| {
+                                        state.status == CommandStatus::Running
+                                    });
+                                }
+                                Err(e) => {
+                                    log::error!(
+                                        "流水线 {} 启动命令 {} 失败: {}",
+                                        pipeline.id,
+                                        definition.command_id,
+                                        e
+                                    );
+                                    step.status = StepStatus::Failed;
+                                    step.error = Some(e);
+                                }
+                            }
+                        }
+                    }
+                    changed.push(index);
+                }
+            }
+
+            if !changed.is_empty() {
+                self.update_steps(pipeline.id, &steps, &changed);
+            }
+
+            if let Some(status) = pipeline::final_status(&steps, stop_requested) {
+                break status;
+            }
+
+            std::thread::sleep(Duration::from_millis(constants::pipeline::POLL_INTERVAL_MS));
+        };
+
+        self.stop_requests.lock().unwrap().remove(&pipeline.id);
+        let state = {
+            let mut runs = self.runs.lock().unwrap();
+            runs.get_mut(&pipeline.id).map(|state| {
+                state.status = status;
+                state.ended_at = Some(chrono::Local::now().to_rfc3339());
+                state.clone()
+            })
+        };
+        log::info!("流水线 {} 运行结束: {:?}", pipeline.id, status);
+        if let Some(state) = state {
+            self.emit_state(&state);
+        }
+    }
+
+    /// 保存步骤状态并发送变化事件
+    fn update_steps(&self, pipeline_id: i64, steps: &[StepState], changed: &[usize]) {
+        let state = {
+            let mut runs = self.runs.lock().unwrap();
+            let Some(state) = runs.get_mut(&pipeline_id) else {
+                return;
+            };
+            state.steps = steps.to_vec();
+            state.clone()
+        };
+
+        let mut changed = changed.to_vec();
+        changed.dedup();
+        for index in changed {
+            let event = PipelineStepEvent {
+                pipeline_id,
+                step: index,
+                state: steps[index].clone(),
+            };
+            let _ = self
+                .app_handle
+                .emit_to(EventTarget::Any, "pipeline-step-status", event);
+        }
+        self.emit_state(&state);
+    }
+
+    fn emit_state(&self, state: &PipelineRunState) {
+        let _ = self
+            .app_handle
+            .emit_to(EventTarget::Any, "pipeline-status", state);
+    }
+
+    /// 克隆用于线程传递
+    fn clone_for_thread(&self) -> Self {
+        Self {
+            runs: Arc::clone(&self.runs),
+            stop_requests: Arc::clone(&self.stop_requests),
+            app_handle: self.app_handle.clone(),
+        }
+    }
+}
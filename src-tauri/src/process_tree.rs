@@ -0,0 +1,236 @@
+// ==================== 进程树控制 ====================
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::process::Command;
+use sysinfo::{Pid, System};
+
+#[cfg(unix)]
+use std::collections::HashSet;
+
+#[cfg(target_os = "windows")]
+use std::sync::Mutex;
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Console::{
+    AttachConsole, FreeConsole, GenerateConsoleCtrlEvent, SetConsoleCtrlHandler, CTRL_BREAK_EVENT,
+};
+
+/// 停止命令的方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum StopMode {
+    /// 先请求优雅退出，超过宽限期后强制终止
+    #[default]
+    Graceful,
+    /// 立即强制终止
+    Kill,
+    /// 执行自定义停止命令（如 `docker compose down`），超过宽限期后强制终止
+    Command,
+}
+
+/// 控制台附加是进程级别的状态，同一时间只能附加一个控制台
+#[cfg(target_os = "windows")]
+static CONSOLE_LOCK: Mutex<()> = Mutex::new(());
+
+/// 让子进程在独立的进程组中运行，便于向整个进程树发送信号
+///
+/// Windows 通过 CREATE_NEW_PROCESS_GROUP 创建标志实现，这里只处理 Unix。
+pub fn configure_process_group(cmd: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+
+    #[cfg(not(unix))]
+    let _ = cmd;
+}
+
+/// 请求进程组优雅退出
+///
+/// Unix 向进程组发送 SIGTERM；Windows 向进程组发送 CTRL_BREAK。
+pub fn request_graceful_stop(pid: u32) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        signal_process_group(pid, libc::SIGTERM)
+            .map_err(|e| format!("向进程组 {} 发送 SIGTERM 失败: {}", pid, e))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let _guard = CONSOLE_LOCK.lock().map_err(|e| format!("获取控制台锁失败: {}", e))?;
+
+        unsafe {
+            // GUI 进程没有控制台，需要先附加到子进程的控制台才能发送控制事件
+            let _ = FreeConsole();
+            AttachConsole(pid).map_err(|e| format!("附加到进程控制台失败: {:?}", e))?;
+
+            // 忽略发送给自身的控制事件
+            let _ = SetConsoleCtrlHandler(None, true);
+            let result = GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pid);
+            let _ = FreeConsole();
+
+            // 等待事件投递完成后再恢复默认处理
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            let _ = SetConsoleCtrlHandler(None, false);
+
+            result.map_err(|e| format!("发送 CTRL_BREAK 失败: {:?}", e))
+        }
+    }
+}
+
+/// 进程标识（pid + 启动时间，避免 pid 被复用后误杀无关进程）
+#[cfg(unix)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProcessIdentity {
+    pub pid: u32,
+    start_time: u64,
+}
+
+/// 收集进程当前的所有后代进程
+///
+/// 后代进程可能通过 setsid/setpgid 脱离进程组，且在父进程退出后会被重新挂到 init 下，
+/// 因此需要在停止前记录下来，供 [`kill_process_tree`] 清理。
+#[cfg(unix)]
+pub fn collect_descendants(pid: u32) -> Vec<ProcessIdentity> {
+    let mut system = System::new();
+    system.refresh_processes();
+    descendants_in(&system, pid)
+}
+
+/// 强制终止整个进程树（Windows 通过关闭 Job Object 实现）
+///
+/// 依次处理：进程组内的所有进程、当前仍能找到的后代进程，以及之前记录的后代进程
+/// （仅在 pid 与启动时间都匹配时终止）。进程组已不存在时不视为错误。
+#[cfg(unix)]
+pub fn kill_process_tree(pid: u32, known_descendants: &[ProcessIdentity]) -> Result<(), String> {
+    let mut system = System::new();
+    system.refresh_processes();
+
+    let mut targets: HashSet<ProcessIdentity> = descendants_in(&system, pid).into_iter().collect();
+    targets.extend(
+        known_descendants
+            .iter()
+            .filter(|identity| identity_of(&system, identity.pid) == Some(**identity)),
+    );
+
+    let result = match signal_process_group(pid, libc::SIGKILL) {
+        Err(e) if e.raw_os_error() != Some(libc::ESRCH) => Err(format!(
+            "向进程组 {} 发送 SIGKILL 失败: {}",
+            pid, e
+        )),
+        _ => Ok(()),
+    };
+
+    for identity in targets {
+        unsafe {
+            libc::kill(identity.pid as libc::pid_t, libc::SIGKILL);
+        }
+    }
+
+    result
+}
+
+/// 在进程快照中按父子关系查找所有后代进程
+#[cfg(unix)]
+fn descendants_in(system: &System, pid: u32) -> Vec<ProcessIdentity> {
+    tree_pids(system, pid)
+        .into_iter()
+        .skip(1)
+        .filter_map(|pid| identity_of(system, pid.as_u32()))
+        .collect()
+}
+
+/// 在进程快照中按父子关系查找进程及其所有后代进程，第一个为 `pid` 本身
+///
+/// Linux 上的线程也作为进程列出（父进程为所属进程），这里不包括线程。
+pub fn tree_pids(system: &System, pid: u32) -> Vec<Pid> {
+    let mut children: HashMap<Pid, Vec<Pid>> = HashMap::new();
+    for (child_pid, process) in system.processes() {
+        if let (Some(parent), None) = (process.parent(), process.thread_kind()) {
+            children.entry(parent).or_default().push(*child_pid);
+        }
+    }
+
+    let mut result = vec![Pid::from_u32(pid)];
+    let mut index = 0;
+    while let Some(current) = result.get(index).copied() {
+        result.extend(children.get(&current).into_iter().flatten());
+        index += 1;
+    }
+    result
+}
+
+/// 获取进程快照中指定 pid 的进程标识
+#[cfg(unix)]
+fn identity_of(system: &System, pid: u32) -> Option<ProcessIdentity> {
+    system.process(Pid::from_u32(pid)).map(|process| ProcessIdentity {
+        pid,
+        start_time: process.start_time(),
+    })
+}
+
+/// 向以 `pid` 为组长的进程组发送信号
+#[cfg(unix)]
+fn signal_process_group(pid: u32, signal: libc::c_int) -> std::io::Result<()> {
+    let ret = unsafe { libc::kill(-(pid as libc::pid_t), signal) };
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    /// 进程是否仍存活（僵尸进程视为已退出）
+    fn is_alive(pid: u32) -> bool {
+        match std::fs::read_to_string(format!("/proc/{}/stat", pid)) {
+            // 状态字段位于最后一个 ')' 之后
+            Ok(stat) => stat
+                .rsplit_once(')')
+                .map(|(_, rest)| !rest.trim_start().starts_with('Z'))
+                .unwrap_or(false),
+            Err(_) => false,
+        }
+    }
+
+    fn wait_until(deadline: Duration, condition: impl Fn() -> bool) -> bool {
+        let start = Instant::now();
+        while start.elapsed() < deadline {
+            if condition() {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        condition()
+    }
+
+    #[test]
+    fn test_kill_process_tree_leaves_no_descendants() {
+        // 一个留在进程组内的孙进程，一个通过 setsid 脱离进程组的孙进程
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "sleep 30 & (exec setsid sleep 30) & wait"]);
+        configure_process_group(&mut cmd);
+        let mut child = cmd.spawn().unwrap();
+        let pid = child.id();
+
+        assert!(wait_until(Duration::from_secs(5), || collect_descendants(pid).len() >= 2));
+        let descendants = collect_descendants(pid);
+
+        // 只终止 sh，模拟组长已退出、后代进程被重新挂到 init 下的情况
+        unsafe {
+            libc::kill(pid as libc::pid_t, libc::SIGKILL);
+        }
+        child.wait().unwrap();
+
+        kill_process_tree(pid, &descendants).unwrap();
+
+        assert!(wait_until(Duration::from_secs(5), || descendants
+            .iter()
+            .all(|identity| !is_alive(identity.pid))));
+    }
+}
@@ -0,0 +1,455 @@
+// ==================== 伪终端 ====================
+
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io;
+use std::process::{Child, ExitStatus};
+
+#[cfg(unix)]
+use std::process::Command;
+
+#[cfg(target_os = "windows")]
+use std::collections::BTreeMap;
+#[cfg(target_os = "windows")]
+use std::path::Path;
+
+#[cfg(target_os = "windows")]
+use windows::core::{PCWSTR, PWSTR};
+#[cfg(target_os = "windows")]
+use windows::Win32::Foundation::{CloseHandle, HANDLE, WAIT_OBJECT_0};
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Console::{ClosePseudoConsole, CreatePseudoConsole, COORD, HPCON};
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Pipes::CreatePipe;
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Threading::{
+    CreateProcessW, DeleteProcThreadAttributeList, GetExitCodeProcess,
+    InitializeProcThreadAttributeList, TerminateProcess, UpdateProcThreadAttribute,
+    WaitForSingleObject, CREATE_UNICODE_ENVIRONMENT, EXTENDED_STARTUPINFO_PRESENT,
+    LPPROC_THREAD_ATTRIBUTE_LIST, PROCESS_INFORMATION, PROC_THREAD_ATTRIBUTE_PSEUDOCONSOLE,
+    STARTUPINFOEXW,
+};
+
+/// 伪终端配置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PtyConfig {
+    /// 是否在伪终端中运行（stdout/stderr 合并为终端输出）
+    pub enabled: bool,
+    /// 终端列数
+    pub cols: u16,
+    /// 终端行数
+    pub rows: u16,
+}
+
+impl Default for PtyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cols: 120,
+            rows: 30,
+        }
+    }
+}
+
+impl PtyConfig {
+    /// 终端尺寸（至少 1x1）
+    fn size(&self) -> (u16, u16) {
+        (self.cols.max(1), self.rows.max(1))
+    }
+}
+
+/// 运行中的子进程
+pub enum ChildProcess {
+    /// 通过 `std::process::Command` 启动（管道或 Unix 伪终端）
+    Std(Child),
+    /// 通过 ConPTY 启动
+    #[cfg(target_os = "windows")]
+    ConPty(ConPtyProcess),
+}
+
+impl ChildProcess {
+    pub fn id(&self) -> u32 {
+        match self {
+            ChildProcess::Std(child) => child.id(),
+            #[cfg(target_os = "windows")]
+            ChildProcess::ConPty(process) => process.pid,
+        }
+    }
+
+    pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        match self {
+            ChildProcess::Std(child) => child.try_wait(),
+            #[cfg(target_os = "windows")]
+            ChildProcess::ConPty(process) => process.try_wait(),
+        }
+    }
+
+    pub fn kill(&mut self) -> io::Result<()> {
+        match self {
+            ChildProcess::Std(child) => child.kill(),
+            #[cfg(target_os = "windows")]
+            ChildProcess::ConPty(process) => process.kill(),
+        }
+    }
+
+    /// 请求进程优雅退出
+    ///
+    /// ConPTY 中的进程不属于当前控制台，无法接收 CTRL_BREAK，改为向终端输入 Ctrl+C。
+    pub fn request_graceful_stop(&mut self) -> Result<(), String> {
+        match self {
+            ChildProcess::Std(child) => crate::process_tree::request_graceful_stop(child.id()),
+            #[cfg(target_os = "windows")]
+            ChildProcess::ConPty(process) => {
+                use std::io::Write;
+                process
+                    .input
+                    .write_all(b"\x03")
+                    .map_err(|e| format!("向伪终端发送 Ctrl+C 失败: {}", e))
+            }
+        }
+    }
+}
+
+/// 在伪终端中启动命令，返回（子进程，终端输出，终端输入）
+///
+/// Unix 使用 openpty，子进程在新会话中运行并以终端从端作为控制终端（会话首进程即进程组首进程，
+/// 可以像普通命令一样向进程组发送信号）；Windows 使用 ConPTY，直接执行完整的命令行（包括 shell）。
+#[cfg(unix)]
+pub fn spawn(mut cmd: Command, config: &PtyConfig) -> io::Result<(ChildProcess, File, File)> {
+    use std::os::unix::process::CommandExt;
+    use std::process::Stdio;
+
+    let (master, slave) = open_pty(config)?;
+
+    // 图形界面启动时通常没有 TERM，终端程序需要它来决定输出格式
+    let has_term = cmd.get_envs().any(|(key, value)| key == "TERM" && value.is_some());
+    if !has_term && std::env::var_os("TERM").is_none() {
+        cmd.env("TERM", "xterm-256color");
+    }
+
+    cmd.stdin(Stdio::from(slave.try_clone()?))
+        .stdout(Stdio::from(slave.try_clone()?))
+        .stderr(Stdio::from(slave));
+
+    unsafe {
+        cmd.pre_exec(|| {
+            if libc::setsid() == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            if libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+
+    let child = cmd.spawn()?;
+    let input = master.try_clone()?;
+    // cmd 在这里释放，关闭父进程持有的从端，子进程退出后读取主端会结束
+    Ok((ChildProcess::Std(child), master, input))
+}
+
+/// 打开伪终端，返回（主端，从端）
+#[cfg(unix)]
+fn open_pty(config: &PtyConfig) -> io::Result<(File, File)> {
+    use std::os::unix::io::FromRawFd;
+
+    let (cols, rows) = config.size();
+    let mut winsize = libc::winsize {
+        ws_row: rows,
+        ws_col: cols,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    let mut master: libc::c_int = -1;
+    let mut slave: libc::c_int = -1;
+
+    let result = unsafe {
+        libc::openpty(
+            &mut master,
+            &mut slave,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            &mut winsize as *mut libc::winsize,
+        )
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // 避免其它子进程继承终端句柄
+    for fd in [master, slave] {
+        unsafe {
+            libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC);
+        }
+    }
+
+    Ok(unsafe { (File::from_raw_fd(master), File::from_raw_fd(slave)) })
+}
+
+/// 在伪终端中启动命令，返回（子进程，终端输出，终端输入）
+///
+/// `env` 为在当前进程环境基础上覆盖的环境变量。
+#[cfg(target_os = "windows")]
+pub fn spawn(
+    command_line: &str,
+    env: &BTreeMap<String, String>,
+    working_dir: Option<&Path>,
+    config: &PtyConfig,
+) -> io::Result<(ChildProcess, File, File)> {
+    let (process, output) = ConPtyProcess::spawn(command_line, env, working_dir, config)?;
+    let input = process.input.try_clone()?;
+    Ok((ChildProcess::ConPty(process), output, input))
+}
+
+/// ConPTY 中运行的进程
+#[cfg(target_os = "windows")]
+pub struct ConPtyProcess {
+    pid: u32,
+    process: HANDLE,
+    /// 进程退出后关闭，输出管道随之结束
+    console: Option<HPCON>,
+    /// 终端输入
+    input: File,
+    exit_status: Option<ExitStatus>,
+}
+
+// HANDLE/HPCON 只是内核对象句柄，可以在线程间传递
+#[cfg(target_os = "windows")]
+unsafe impl Send for ConPtyProcess {}
+
+#[cfg(target_os = "windows")]
+impl ConPtyProcess {
+    fn spawn(
+        command_line: &str,
+        env: &BTreeMap<String, String>,
+        working_dir: Option<&Path>,
+        config: &PtyConfig,
+    ) -> io::Result<(Self, File)> {
+        use std::os::windows::io::FromRawHandle;
+
+        let (cols, rows) = config.size();
+
+        unsafe {
+            let mut input_read = HANDLE::default();
+            let mut input_write = HANDLE::default();
+            let mut output_read = HANDLE::default();
+            let mut output_write = HANDLE::default();
+            CreatePipe(&mut input_read, &mut input_write, None, 0).map_err(win_error)?;
+            if let Err(e) = CreatePipe(&mut output_read, &mut output_write, None, 0) {
+                let _ = CloseHandle(input_read);
+                let _ = CloseHandle(input_write);
+                return Err(win_error(e));
+            }
+
+            let size = COORD {
+                X: cols.min(i16::MAX as u16) as i16,
+                Y: rows.min(i16::MAX as u16) as i16,
+            };
+            let console = CreatePseudoConsole(size, input_read, output_write, 0);
+
+            // ConPTY 已持有这两个句柄的副本
+            let _ = CloseHandle(input_read);
+            let _ = CloseHandle(output_write);
+
+            let input = File::from_raw_handle(input_write.0 as _);
+            let output = File::from_raw_handle(output_read.0 as _);
+            let console = console.map_err(win_error)?;
+
+            match Self::create_process(command_line, env, working_dir, console) {
+                Ok(info) => {
+                    let _ = CloseHandle(info.hThread);
+                    Ok((
+                        Self {
+                            pid: info.dwProcessId,
+                            process: info.hProcess,
+                            console: Some(console),
+                            input,
+                            exit_status: None,
+                        },
+                        output,
+                    ))
+                }
+                Err(e) => {
+                    ClosePseudoConsole(console);
+                    Err(e)
+                }
+            }
+        }
+    }
+
+    /// 创建挂接到伪终端的进程
+    unsafe fn create_process(
+        command_line: &str,
+        env: &BTreeMap<String, String>,
+        working_dir: Option<&Path>,
+        console: HPCON,
+    ) -> io::Result<PROCESS_INFORMATION> {
+        let mut list_size = 0usize;
+        // 第一次调用只用于获取所需大小，预期返回错误
+        let _ = InitializeProcThreadAttributeList(
+            LPPROC_THREAD_ATTRIBUTE_LIST(std::ptr::null_mut()),
+            1,
+            0,
+            &mut list_size,
+        );
+        let mut list_buffer = vec![0u8; list_size];
+        let attribute_list = LPPROC_THREAD_ATTRIBUTE_LIST(list_buffer.as_mut_ptr() as _);
+        InitializeProcThreadAttributeList(attribute_list, 1, 0, &mut list_size)
+            .map_err(win_error)?;
+
+        let result = (|| {
+            UpdateProcThreadAttribute(
+                attribute_list,
+                0,
+                PROC_THREAD_ATTRIBUTE_PSEUDOCONSOLE as usize,
+                Some(console.0 as *const std::ffi::c_void),
+                std::mem::size_of::<HPCON>(),
+                None,
+                None,
+            )
+            .map_err(win_error)?;
+
+            let mut startup_info = STARTUPINFOEXW::default();
+            startup_info.StartupInfo.cb = std::mem::size_of::<STARTUPINFOEXW>() as u32;
+            startup_info.lpAttributeList = attribute_list;
+
+            let mut command_line = to_wide(command_line);
+            let environment = environment_block(env);
+            let working_dir = working_dir.map(|dir| to_wide(&dir.to_string_lossy()));
+
+            let mut info = PROCESS_INFORMATION::default();
+            CreateProcessW(
+                PCWSTR::null(),
+                PWSTR(command_line.as_mut_ptr()),
+                None,
+                None,
+                false,
+                EXTENDED_STARTUPINFO_PRESENT | CREATE_UNICODE_ENVIRONMENT,
+                Some(environment.as_ptr() as *const std::ffi::c_void),
+                working_dir
+                    .as_ref()
+                    .map_or(PCWSTR::null(), |dir| PCWSTR(dir.as_ptr())),
+                &startup_info.StartupInfo,
+                &mut info,
+            )
+            .map_err(win_error)?;
+            Ok(info)
+        })();
+
+        DeleteProcThreadAttributeList(attribute_list);
+        result
+    }
+
+    fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        use std::os::windows::process::ExitStatusExt;
+
+        if let Some(status) = self.exit_status {
+            return Ok(Some(status));
+        }
+
+        unsafe {
+            if WaitForSingleObject(self.process, 0) != WAIT_OBJECT_0 {
+                return Ok(None);
+            }
+            let mut code = 0u32;
+            GetExitCodeProcess(self.process, &mut code).map_err(win_error)?;
+            self.close_console();
+
+            let status = ExitStatus::from_raw(code);
+            self.exit_status = Some(status);
+            Ok(Some(status))
+        }
+    }
+
+    fn kill(&mut self) -> io::Result<()> {
+        if self.exit_status.is_some() {
+            return Ok(());
+        }
+        unsafe { TerminateProcess(self.process, 1).map_err(win_error) }
+    }
+
+    /// 关闭伪终端，读取输出的线程随之结束
+    fn close_console(&mut self) {
+        if let Some(console) = self.console.take() {
+            unsafe { ClosePseudoConsole(console) };
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl Drop for ConPtyProcess {
+    fn drop(&mut self) {
+        self.close_console();
+        unsafe {
+            let _ = CloseHandle(self.process);
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn win_error(e: windows::core::Error) -> io::Error {
+    io::Error::other(e.to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn to_wide(value: &str) -> Vec<u16> {
+    value.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// 构建 Unicode 环境块：当前进程环境 + 覆盖项，按变量名（不区分大小写）排序
+#[cfg(target_os = "windows")]
+fn environment_block(overrides: &BTreeMap<String, String>) -> Vec<u16> {
+    let mut vars: BTreeMap<String, (String, String)> = std::env::vars_os()
+        .map(|(key, value)| {
+            let key = key.to_string_lossy().to_string();
+            (key.to_uppercase(), (key, value.to_string_lossy().to_string()))
+        })
+        .collect();
+    for (key, value) in overrides {
+        vars.insert(key.to_uppercase(), (key.clone(), value.clone()));
+    }
+
+    let mut block: Vec<u16> = Vec::new();
+    for (key, value) in vars.values() {
+        block.extend(format!("{}={}", key, value).encode_utf16());
+        block.push(0);
+    }
+    block.push(0);
+    block
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_spawn_in_pty_reports_tty() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "test -t 1 && echo tty; stty size"]);
+        let config = PtyConfig {
+            enabled: true,
+            cols: 100,
+            rows: 40,
+        };
+
+        let (mut child, mut output, _input) = spawn(cmd, &config).unwrap();
+
+        let mut text = String::new();
+        let mut buffer = [0u8; 1024];
+        // 子进程退出后读取主端返回 EIO
+        while let Ok(n) = output.read(&mut buffer) {
+            if n == 0 {
+                break;
+            }
+            text.push_str(&String::from_utf8_lossy(&buffer[..n]));
+        }
+
+        assert!(text.contains("tty"), "output: {:?}", text);
+        assert!(text.contains("40 100"), "output: {:?}", text);
+        let ChildProcess::Std(child) = &mut child;
+        assert!(child.wait().unwrap().success());
+    }
+}
@@ -0,0 +1,339 @@
+// ==================== 就绪检查 ====================
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::constants;
+
+/// 就绪检查方式
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ReadinessProbe {
+    /// 不检查，进程启动即视为就绪
+    #[default]
+    None,
+    /// TCP 端口可以连接
+    Tcp {
+        #[serde(default = "default_host")]
+        host: String,
+        port: u16,
+    },
+    /// HTTP GET 返回 2xx（地址为空时使用命令的 URL）
+    Http {
+        #[serde(default)]
+        url: Option<String>,
+    },
+    /// 日志输出匹配正则表达式
+    LogPattern { pattern: String },
+    /// 文件出现（相对路径基于工作目录）
+    File { path: String },
+}
+
+fn default_host() -> String {
+    "127.0.0.1".to_string()
+}
+
+/// 就绪检查配置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ReadinessConfig {
+    pub probe: ReadinessProbe,
+    /// 等待就绪的超时时间（秒），超时后停止命令并视为失败
+    pub timeout_secs: u64,
+    /// 检查间隔（毫秒）
+    pub interval_ms: u64,
+    /// 就绪后在浏览器中打开命令的 URL
+    pub open_url: bool,
+}
+
+impl Default for ReadinessConfig {
+    fn default() -> Self {
+        Self {
+            probe: ReadinessProbe::None,
+            timeout_secs: 60,
+            interval_ms: 500,
+            open_url: false,
+        }
+    }
+}
+
+impl ReadinessConfig {
+    /// 校验配置（`url` 为命令的 URL）
+    pub fn validate(&self, url: Option<&str>) -> Result<(), String> {
+        ReadinessChecker::new(&self.probe, url, None).map(|_| ())
+    }
+}
+
+/// HTTP 检查目标
+#[derive(Debug, Clone, PartialEq)]
+struct HttpTarget {
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl HttpTarget {
+    /// 解析 `http://host[:port][/path]`
+    fn parse(url: &str) -> Result<Self, String> {
+        let url = url.trim();
+        let rest = match url.split_once("://") {
+            Some((scheme, rest)) if scheme.eq_ignore_ascii_case("http") => rest,
+            Some((scheme, _)) if scheme.eq_ignore_ascii_case("https") => {
+                return Err("就绪检查暂不支持 HTTPS 地址".to_string());
+            }
+            _ => return Err(format!("无效的 HTTP 地址: {}", url)),
+        };
+
+        let (authority, path) = match rest.find(['/', '?']) {
+            Some(index) => rest.split_at(index),
+            None => (rest, "/"),
+        };
+        let path = if path.starts_with('/') {
+            path.to_string()
+        } else {
+            format!("/{}", path)
+        };
+
+        // IPv6 地址形如 [::1]:8080
+        let (host, port) = if let Some(stripped) = authority.strip_prefix('[') {
+            let (host, after) = stripped
+                .split_once(']')
+                .ok_or_else(|| format!("无效的 HTTP 地址: {}", url))?;
+            (host, after.strip_prefix(':'))
+        } else {
+            match authority.rsplit_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (authority, None),
+            }
+        };
+        if host.is_empty() {
+            return Err(format!("无效的 HTTP 地址: {}", url));
+        }
+        let port = match port {
+            Some(port) => port
+                .parse()
+                .map_err(|_| format!("无效的 HTTP 端口: {}", url))?,
+            None => 80,
+        };
+
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path,
+        })
+    }
+}
+
+/// 编译后的就绪检查
+#[derive(Debug)]
+enum Check {
+    Tcp { host: String, port: u16 },
+    Http(HttpTarget),
+    LogPattern(Regex),
+    File(PathBuf),
+}
+
+/// 就绪检查器
+#[derive(Debug)]
+pub struct ReadinessChecker {
+    check: Check,
+}
+
+impl ReadinessChecker {
+    /// 根据配置创建检查器，未配置检查时返回 None
+    pub fn new(
+        probe: &ReadinessProbe,
+        url: Option<&str>,
+        working_dir: Option<&Path>,
+    ) -> Result<Option<Self>, String> {
+        let check = match probe {
+            ReadinessProbe::None => return Ok(None),
+            ReadinessProbe::Tcp { host, port } => {
+                if *port == 0 {
+                    return Err("就绪检查端口不能为 0".to_string());
+                }
+                Check::Tcp {
+                    host: host.clone(),
+                    port: *port,
+                }
+            }
+            ReadinessProbe::Http { url: probe_url } => {
+                let url = probe_url
+                    .as_deref()
+                    .or(url)
+                    .filter(|url| !url.trim().is_empty())
+                    .ok_or("HTTP 就绪检查需要填写地址或命令的 URL")?;
+                Check::Http(HttpTarget::parse(url)?)
+            }
+            ReadinessProbe::LogPattern { pattern } => Check::LogPattern(
+                Regex::new(pattern).map_err(|e| format!("无效的就绪日志正则表达式: {}", e))?,
+            ),
+            ReadinessProbe::File { path } => {
+                if path.trim().is_empty() {
+                    return Err("就绪检查文件路径不能为空".to_string());
+                }
+                let path = PathBuf::from(path);
+                Check::File(match working_dir {
+                    Some(dir) if path.is_relative() => dir.join(path),
+                    _ => path,
+                })
+            }
+        };
+        Ok(Some(Self { check }))
+    }
+
+    /// 执行一次检查，`new_lines` 为上次检查以来新增的日志
+    pub fn check(&self, new_lines: &[String]) -> bool {
+        let timeout = Duration::from_millis(constants::readiness::PROBE_IO_TIMEOUT_MS);
+        match &self.check {
+            Check::Tcp { host, port } => connect(host, *port, timeout).is_some(),
+            Check::Http(target) => http_get_succeeds(target, timeout),
+            Check::LogPattern(regex) => new_lines.iter().any(|line| regex.is_match(line)),
+            Check::File(path) => path.exists(),
+        }
+    }
+}
+
+/// 连接 TCP 端口（依次尝试解析出的所有地址）
+fn connect(host: &str, port: u16, timeout: Duration) -> Option<TcpStream> {
+    let addrs = (host, port).to_socket_addrs().ok()?;
+    addrs
+        .into_iter()
+        .find_map(|addr| TcpStream::connect_timeout(&addr, timeout).ok())
+}
+
+/// 发送 HTTP GET 请求，返回状态码是否为 2xx
+fn http_get_succeeds(target: &HttpTarget, timeout: Duration) -> bool {
+    let Some(mut stream) = connect(&target.host, target.port, timeout) else {
+        return false;
+    };
+    let _ = stream.set_read_timeout(Some(timeout));
+    let _ = stream.set_write_timeout(Some(timeout));
+
+    let host = if target.host.contains(':') {
+        format!("[{}]:{}", target.host, target.port)
+    } else {
+        format!("{}:{}", target.host, target.port)
+    };
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: {}\r\nConnection: close\r\n\r\n",
+        target.path,
+        host,
+        constants::APP_NAME
+    );
+    if stream.write_all(request.as_bytes()).is_err() {
+        return false;
+    }
+
+    // 只需要状态行：HTTP/1.1 200 OK
+    let mut buffer = [0u8; 64];
+    let mut len = 0;
+    while len < buffer.len() {
+        match stream.read(&mut buffer[len..]) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => len += n,
+        }
+        if buffer[..len].contains(&b'\n') {
+            break;
+        }
+    }
+    let status_line = String::from_utf8_lossy(&buffer[..len]);
+    status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok())
+        .is_some_and(|code| (200..300).contains(&code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_parse_http_target() {
+        assert_eq!(
+            HttpTarget::parse("http://localhost:3000/health?full=1").unwrap(),
+            HttpTarget {
+                host: "localhost".to_string(),
+                port: 3000,
+                path: "/health?full=1".to_string(),
+            }
+        );
+        let target = HttpTarget::parse("http://[::1]").unwrap();
+        assert_eq!(
+            (target.host.as_str(), target.port, target.path.as_str()),
+            ("::1", 80, "/")
+        );
+        assert!(HttpTarget::parse("https://example.com").is_err());
+        assert!(HttpTarget::parse("localhost:3000").is_err());
+    }
+
+    #[test]
+    fn test_tcp_and_http_probes() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            for status in ["503 Service Unavailable", "204 No Content"] {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0u8; 256];
+                let _ = stream.read(&mut request);
+                let response = format!("HTTP/1.1 {}\r\nContent-Length: 0\r\n\r\n", status);
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        let http = ReadinessProbe::Http { url: None };
+        let url = format!("http://127.0.0.1:{}/ready", port);
+        let checker = ReadinessChecker::new(&http, Some(&url), None)
+            .unwrap()
+            .unwrap();
+        assert!(!checker.check(&[]));
+        assert!(checker.check(&[]));
+        server.join().unwrap();
+
+        // 服务关闭后端口不可连接
+        let tcp = ReadinessProbe::Tcp {
+            host: default_host(),
+            port,
+        };
+        let checker = ReadinessChecker::new(&tcp, None, None).unwrap().unwrap();
+        assert!(!checker.check(&[]));
+    }
+
+    #[test]
+    fn test_log_pattern_and_file_probes() {
+        let probe = ReadinessProbe::LogPattern {
+            pattern: r"listening on .*:\d+".to_string(),
+        };
+        let checker = ReadinessChecker::new(&probe, None, None).unwrap().unwrap();
+        assert!(!checker.check(&["compiling...".to_string()]));
+        assert!(checker.check(&["server listening on 0.0.0.0:8080".to_string()]));
+
+        let dir = std::env::temp_dir().join(format!("sigil_ready_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let probe = ReadinessProbe::File {
+            path: "ready.flag".to_string(),
+        };
+        let checker = ReadinessChecker::new(&probe, None, Some(&dir))
+            .unwrap()
+            .unwrap();
+        assert!(!checker.check(&[]));
+        std::fs::write(dir.join("ready.flag"), b"").unwrap();
+        assert!(checker.check(&[]));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(ReadinessConfig::default().validate(None).is_ok());
+        let invalid = ReadinessConfig {
+            probe: ReadinessProbe::LogPattern {
+                pattern: "(".to_string(),
+            },
+            ..Default::default()
+        };
+        assert!(invalid.validate(None).is_err());
+    }
+}
@@ -0,0 +1,343 @@
+// ==================== 资源限制 ====================
+//
+// Windows 通过每次运行的 Job Object 限制；Linux 优先使用 cgroup v2 子树，
+// 不可用时只能通过 setrlimit 限制内存（CPU 和进程数限制需要 cgroup）。
+
+use serde::{Deserialize, Serialize};
+
+/// 命令的资源限制（为空的项不限制，包括所有子进程）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ResourceLimits {
+    /// 最大内存（MB）
+    pub max_memory_mb: Option<u64>,
+    /// CPU 使用率上限（占全部 CPU 的百分比，1-100）
+    pub cpu_percent: Option<u32>,
+    /// 最大进程数
+    pub max_processes: Option<u32>,
+}
+
+impl ResourceLimits {
+    /// 校验限制
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_memory_mb == Some(0) {
+            return Err("最大内存必须大于 0".to_string());
+        }
+        if self
+            .cpu_percent
+            .is_some_and(|percent| !(1..=100).contains(&percent))
+        {
+            return Err("CPU 使用率上限必须在 1 到 100 之间".to_string());
+        }
+        if self.max_processes == Some(0) {
+            return Err("最大进程数必须大于 0".to_string());
+        }
+        Ok(())
+    }
+
+    /// 是否没有任何限制
+    pub fn is_empty(&self) -> bool {
+        self.max_memory_mb.is_none() && self.cpu_percent.is_none() && self.max_processes.is_none()
+    }
+
+    pub fn max_memory_bytes(&self) -> Option<u64> {
+        self.max_memory_mb.map(|mb| mb.saturating_mul(1024 * 1024))
+    }
+}
+
+/// 导致运行被终止的资源限制
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum LimitedResource {
+    Memory,
+    Processes,
+}
+
+/// cgroup v2 `cpu.max` 的值（周期 100ms，按 CPU 核数换算配额）
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn cpu_max(percent: u32, cpus: usize) -> String {
+    const PERIOD_US: u64 = 100_000;
+    let quota = (PERIOD_US * cpus as u64 * u64::from(percent) / 100).max(1000);
+    format!("{} {}", quota, PERIOD_US)
+}
+
+/// 限制所需但 `cgroup.subtree_control` 中未启用的控制器
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn missing_controller(limits: &ResourceLimits, subtree_control: &str) -> Option<&'static str> {
+    [
+        limits.max_memory_mb.map(|_| "memory"),
+        limits.cpu_percent.map(|_| "cpu"),
+        limits.max_processes.map(|_| "pids"),
+    ]
+    .into_iter()
+    .flatten()
+    .find(|controller| !subtree_control.split_whitespace().any(|c| c == *controller))
+}
+
+/// 为 Unix 子进程配置资源限制
+///
+/// 指定 cgroup 时子进程在 exec 前加入该 cgroup，否则通过 setrlimit 限制地址空间大小
+/// （超出内存的判断见 [`rlimit_memory_exceeded`]）。
+#[cfg(unix)]
+pub fn configure_command(
+    cmd: &mut std::process::Command,
+    limits: &ResourceLimits,
+    cgroup_procs: Option<&std::path::Path>,
+) {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::process::CommandExt;
+
+    let procs = cgroup_procs.and_then(|path| CString::new(path.as_os_str().as_bytes()).ok());
+    let memory = limits.max_memory_bytes().filter(|_| procs.is_none());
+    if procs.is_none() && memory.is_none() {
+        return;
+    }
+
+    // pre_exec 在 fork 之后运行，只能使用系统调用
+    unsafe {
+        cmd.pre_exec(move || {
+            if let Some(procs) = &procs {
+                let fd = libc::open(procs.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+                if fd == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                // 写入 0 表示将当前进程加入 cgroup
+                let written = libc::write(fd, b"0".as_ptr().cast(), 1);
+                let error = std::io::Error::last_os_error();
+                libc::close(fd);
+                if written == -1 {
+                    return Err(error);
+                }
+            }
+            if let Some(bytes) = memory {
+                let limit = libc::rlimit {
+                    rlim_cur: bytes as libc::rlim_t,
+                    rlim_max: bytes as libc::rlim_t,
+                };
+                if libc::setrlimit(libc::RLIMIT_AS, &limit) == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            Ok(())
+        });
+    }
+}
+
+/// 通过 setrlimit 限制内存时，运行的退出方式是否表明超出了内存限制
+///
+/// 超出 RLIMIT_AS 只会使内存分配失败（ENOMEM），多数程序随后 abort 或访问空指针而被信号终止。
+/// 命令经由 shell 执行时 shell 以 128 + 信号值作为退出码报告子进程被信号终止。
+#[cfg(unix)]
+pub fn rlimit_memory_exceeded(status: &std::process::ExitStatus) -> bool {
+    use std::os::unix::process::ExitStatusExt;
+
+    const SIGNALS: [i32; 4] = [libc::SIGKILL, libc::SIGSEGV, libc::SIGABRT, libc::SIGBUS];
+    let signal = status
+        .signal()
+        .or_else(|| status.code().filter(|code| *code > 128).map(|code| code - 128));
+    signal.is_some_and(|signal| SIGNALS.contains(&signal))
+}
+
+/// 一次运行使用的 cgroup（释放时终止其中残留的进程并删除）
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+pub struct RunCgroup {
+    path: std::path::PathBuf,
+}
+
+#[cfg(target_os = "linux")]
+impl RunCgroup {
+    const ROOT: &'static str = "/sys/fs/cgroup";
+
+    /// 在当前进程所在 cgroup 的父级下创建子 cgroup 并写入限制
+    ///
+    /// 系统不是 cgroup v2、没有委派写权限或父级未向子 cgroup 启用所需控制器时返回 None。
+    /// 父级 cgroup（用户会话或 systemd scope）不属于本应用，不修改其 `cgroup.subtree_control`。
+    pub fn create(name: &str, limits: &ResourceLimits) -> Option<Self> {
+        use std::ffi::CString;
+        use std::fs;
+        use std::os::unix::ffi::OsStrExt;
+        use std::path::Path;
+
+        let root = Path::new(Self::ROOT);
+        if !root.join("cgroup.controllers").exists() {
+            return None;
+        }
+        let own = fs::read_to_string("/proc/self/cgroup").ok()?;
+        let own = own.lines().find_map(|line| line.strip_prefix("0::"))?;
+        let own = root.join(own.trim().trim_start_matches('/'));
+        let parent = if own == root {
+            own
+        } else {
+            own.parent()?.to_path_buf()
+        };
+
+        // 加入 cgroup 需要对源和目标共同祖先的 cgroup.procs 有写权限
+        let procs = CString::new(parent.join("cgroup.procs").as_os_str().as_bytes()).ok()?;
+        if unsafe { libc::access(procs.as_ptr(), libc::W_OK) } != 0 {
+            log::debug!("没有 cgroup {:?} 的写权限，不使用 cgroup", parent);
+            return None;
+        }
+
+        // 只使用父级已委派给子 cgroup 的控制器
+        let enabled = fs::read_to_string(parent.join("cgroup.subtree_control")).unwrap_or_default();
+        if let Some(controller) = missing_controller(limits, &enabled) {
+            log::debug!("cgroup {:?} 未启用 {} 控制器，不使用 cgroup", parent, controller);
+            return None;
+        }
+
+        let cgroup = Self {
+            path: parent.join(name),
+        };
+        if let Err(e) = fs::create_dir(&cgroup.path) {
+            log::debug!("创建 cgroup {:?} 失败: {}", cgroup.path, e);
+            return None;
+        }
+
+        let mut files = Vec::new();
+        if let Some(bytes) = limits.max_memory_bytes() {
+            files.push(("memory.max", bytes.to_string()));
+        }
+        if let Some(percent) = limits.cpu_percent {
+            let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+            files.push(("cpu.max", cpu_max(percent, cpus)));
+        }
+        if let Some(max) = limits.max_processes {
+            files.push(("pids.max", max.to_string()));
+        }
+        for (file, value) in files {
+            if let Err(e) = fs::write(cgroup.path.join(file), value) {
+                log::warn!("写入 cgroup {:?} 的 {} 失败: {}", cgroup.path, file, e);
+                return None;
+            }
+        }
+        // 超出内存限制时直接终止，而不是换出到交换分区
+        if limits.max_memory_mb.is_some() {
+            let _ = fs::write(cgroup.path.join("memory.swap.max"), "0");
+        }
+
+        Some(cgroup)
+    }
+
+    /// 子进程加入 cgroup 时写入的文件
+    pub fn procs_path(&self) -> std::path::PathBuf {
+        self.path.join("cgroup.procs")
+    }
+
+    /// cgroup 中是否已没有进程（无法读取时按没有处理）
+    pub fn is_empty(&self) -> bool {
+        std::fs::read_to_string(self.procs_path()).map_or(true, |procs| procs.trim().is_empty())
+    }
+
+    /// 运行期间触发的资源限制（内存超限被 OOM 终止或进程数达到上限）
+    pub fn exceeded(&self) -> Option<LimitedResource> {
+        let event = |file: &str, key: &str| -> u64 {
+            std::fs::read_to_string(self.path.join(file))
+                .ok()
+                .and_then(|content| {
+                    content.lines().find_map(|line| {
+                        let (name, value) = line.split_once(' ')?;
+                        (name == key).then(|| value.trim().parse().ok())?
+                    })
+                })
+                .unwrap_or(0)
+        };
+        if event("memory.events", "oom_kill") > 0 {
+            Some(LimitedResource::Memory)
+        } else if event("pids.events", "max") > 0 {
+            Some(LimitedResource::Processes)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for RunCgroup {
+    fn drop(&mut self) {
+        // 终止残留的进程（cgroup.kill 需要 Linux 5.14+），进程退出后才能删除 cgroup
+        let _ = std::fs::write(self.path.join("cgroup.kill"), "1");
+        for _ in 0..10 {
+            if std::fs::remove_dir(&self.path).is_ok() {
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+        log::warn!("删除 cgroup {:?} 失败", self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert!(ResourceLimits::default().validate().is_ok());
+        assert!(ResourceLimits::default().is_empty());
+
+        let limits = ResourceLimits {
+            max_memory_mb: Some(512),
+            cpu_percent: Some(50),
+            max_processes: Some(32),
+        };
+        assert!(limits.validate().is_ok());
+        assert_eq!(limits.max_memory_bytes(), Some(512 * 1024 * 1024));
+
+        for invalid in [
+            ResourceLimits {
+                max_memory_mb: Some(0),
+                ..Default::default()
+            },
+            ResourceLimits {
+                cpu_percent: Some(101),
+                ..Default::default()
+            },
+            ResourceLimits {
+                max_processes: Some(0),
+                ..Default::default()
+            },
+        ] {
+            assert!(invalid.validate().is_err(), "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_cpu_max() {
+        // 占全部 CPU 的百分比：4 核的 50% 相当于 2 个核
+        assert_eq!(cpu_max(50, 4), "200000 100000");
+        assert_eq!(cpu_max(100, 1), "100000 100000");
+        assert_eq!(cpu_max(1, 0), "1000 100000");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rlimit_memory_exceeded() {
+        use std::os::unix::process::ExitStatusExt;
+        use std::process::ExitStatus;
+
+        // 直接被信号终止
+        assert!(rlimit_memory_exceeded(&ExitStatus::from_raw(libc::SIGABRT)));
+        assert!(rlimit_memory_exceeded(&ExitStatus::from_raw(libc::SIGSEGV)));
+        assert!(!rlimit_memory_exceeded(&ExitStatus::from_raw(libc::SIGTERM)));
+        // shell 报告的子进程被信号终止（退出码 128 + 信号值）
+        assert!(rlimit_memory_exceeded(&ExitStatus::from_raw((128 + libc::SIGKILL) << 8)));
+        // 正常退出
+        assert!(!rlimit_memory_exceeded(&ExitStatus::from_raw(0)));
+        assert!(!rlimit_memory_exceeded(&ExitStatus::from_raw(1 << 8)));
+    }
+
+    #[test]
+    fn test_missing_controller() {
+        let limits = ResourceLimits {
+            max_memory_mb: Some(512),
+            max_processes: Some(32),
+            ..Default::default()
+        };
+        assert_eq!(missing_controller(&limits, "cpu memory pids\n"), None);
+        assert_eq!(missing_controller(&limits, "memory"), Some("pids"));
+        assert_eq!(missing_controller(&limits, ""), Some("memory"));
+        assert_eq!(missing_controller(&ResourceLimits::default(), ""), None);
+    }
+}
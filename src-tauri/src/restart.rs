@@ -0,0 +1,176 @@
+// ==================== 自动重启策略 ====================
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// 重启模式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartMode {
+    /// 从不自动重启
+    #[default]
+    Never,
+    /// 仅在执行失败（包括超时）时重启
+    OnFailure,
+    /// 除手动停止外，进程退出后总是重启
+    Always,
+}
+
+/// 重启策略
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RestartPolicy {
+    pub mode: RestartMode,
+    /// 最大连续重启次数，0 表示不限制
+    pub max_retries: u32,
+    /// 第一次重启前的等待时间（秒），之后每次翻倍
+    pub initial_delay_secs: u64,
+    /// 重启等待时间的上限（秒）
+    pub max_delay_secs: u64,
+    /// 单次运行超过该时长（秒）视为稳定，重置重启计数；0 表示不重置
+    pub reset_window_secs: u64,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            mode: RestartMode::Never,
+            max_retries: 5,
+            initial_delay_secs: 1,
+            max_delay_secs: 60,
+            reset_window_secs: 60,
+        }
+    }
+}
+
+/// 一次计划中的重启
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestartPlan {
+    /// 本次重启是连续第几次
+    pub restart_count: u32,
+    pub delay: Duration,
+}
+
+impl RestartPolicy {
+    /// 根据本次运行结果计划下一次重启，返回 None 表示不重启
+    ///
+    /// `restart_count` 为此前已连续重启的次数，`run_duration` 为本次运行时长。
+    pub fn plan(
+        &self,
+        succeeded: bool,
+        restart_count: u32,
+        run_duration: Duration,
+    ) -> Option<RestartPlan> {
+        let wanted = match self.mode {
+            RestartMode::Never => false,
+            RestartMode::OnFailure => !succeeded,
+            RestartMode::Always => true,
+        };
+        if !wanted {
+            return None;
+        }
+
+        // 稳定运行超过重置窗口后重新计数
+        let previous = if self.reset_window_secs > 0
+            && run_duration >= Duration::from_secs(self.reset_window_secs)
+        {
+            0
+        } else {
+            restart_count
+        };
+
+        if self.max_retries > 0 && previous >= self.max_retries {
+            return None;
+        }
+
+        Some(RestartPlan {
+            restart_count: previous + 1,
+            delay: self.backoff(previous),
+        })
+    }
+
+    /// 因不健康被停止时使用的策略：未开启自动重启时按失败重启处理，仍遵循次数和退避限制
+    pub fn for_unhealthy(&self) -> Self {
+        let mode = match self.mode {
+            RestartMode::Never => RestartMode::OnFailure,
+            mode => mode,
+        };
+        Self {
+            mode,
+            ..self.clone()
+        }
+    }
+
+    /// 指数退避：initial * 2^attempt，不超过上限
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u64.checked_shl(attempt.min(63)).unwrap_or(u64::MAX);
+        let secs = self
+            .initial_delay_secs
+            .saturating_mul(factor)
+            .min(self.max_delay_secs.max(self.initial_delay_secs));
+        Duration::from_secs(secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(mode: RestartMode) -> RestartPolicy {
+        RestartPolicy {
+            mode,
+            max_retries: 3,
+            initial_delay_secs: 2,
+            max_delay_secs: 5,
+            reset_window_secs: 30,
+        }
+    }
+
+    #[test]
+    fn test_plan_by_mode() {
+        let short = Duration::from_secs(1);
+
+        assert_eq!(policy(RestartMode::Never).plan(false, 0, short), None);
+        assert_eq!(policy(RestartMode::OnFailure).plan(true, 0, short), None);
+        assert!(policy(RestartMode::OnFailure)
+            .plan(false, 0, short)
+            .is_some());
+        assert!(policy(RestartMode::Always).plan(true, 0, short).is_some());
+        assert!(policy(RestartMode::Never)
+            .for_unhealthy()
+            .plan(false, 0, short)
+            .is_some());
+    }
+
+    #[test]
+    fn test_backoff_and_max_retries() {
+        let policy = policy(RestartMode::Always);
+        let short = Duration::from_secs(1);
+
+        let delays: Vec<u64> = (0..3)
+            .map(|count| policy.plan(false, count, short).unwrap().delay.as_secs())
+            .collect();
+        assert_eq!(delays, vec![2, 4, 5]);
+        assert_eq!(policy.plan(false, 2, short).unwrap().restart_count, 3);
+        assert_eq!(policy.plan(false, 3, short), None);
+
+        // 不限制次数时退避时间保持在上限
+        let unlimited = RestartPolicy {
+            max_retries: 0,
+            ..policy
+        };
+        assert_eq!(
+            unlimited.plan(false, 100, short).unwrap().delay.as_secs(),
+            5
+        );
+    }
+
+    #[test]
+    fn test_reset_window() {
+        let policy = policy(RestartMode::OnFailure);
+
+        let plan = policy.plan(false, 3, Duration::from_secs(30)).unwrap();
+        assert_eq!(plan.restart_count, 1);
+        assert_eq!(plan.delay.as_secs(), 2);
+    }
+}
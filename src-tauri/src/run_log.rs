@@ -0,0 +1,312 @@
+// ==================== 运行日志文件 ====================
+
+use crate::log_buffer::LogLine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, LineWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager};
+
+/// 运行日志目录名（位于应用数据目录下，与 sigil.log 同级）
+const RUN_LOG_DIR_NAME: &str = "run_logs";
+
+/// 运行日志中的一行
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RunLogLine {
+    pub timestamp: String,
+    pub stream: String,
+    pub text: String,
+}
+
+/// 运行日志分页结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunLogPage {
+    pub run_id: i64,
+    pub offset: usize,
+    pub total: usize,
+    pub lines: Vec<RunLogLine>,
+}
+
+/// 运行日志保留策略
+#[derive(Debug, Clone, Copy)]
+pub struct RunLogRetention {
+    /// 超过该时长的日志文件会被删除，None 表示不限制
+    pub max_age: Option<Duration>,
+    /// 日志文件总大小上限（字节），超出时从最旧的文件开始删除，None 表示不限制
+    pub max_total_bytes: Option<u64>,
+}
+
+/// 运行日志存储 - 每次运行的输出写入单独的文件
+pub struct RunLogStore {
+    dir: PathBuf,
+    writers: Mutex<HashMap<i64, LineWriter<File>>>,
+}
+
+impl RunLogStore {
+    /// 初始化运行日志目录
+    pub fn init(app_handle: &AppHandle) -> Result<Self, String> {
+        let app_dir = app_handle
+            .path()
+            .resolve("", tauri::path::BaseDirectory::AppLocalData)
+            .map_err(|e| format!("无法获取应用数据目录: {}", e))?
+            .join(crate::constants::APP_NAME);
+
+        Self::with_dir(app_dir.join(RUN_LOG_DIR_NAME))
+    }
+
+    /// 使用指定目录创建运行日志存储
+    pub fn with_dir(dir: PathBuf) -> Result<Self, String> {
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("创建运行日志目录失败: {}。路径: {:?}", e, dir))?;
+
+        Ok(Self {
+            dir,
+            writers: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// 运行日志文件路径
+    fn path(&self, run_id: i64) -> PathBuf {
+        self.dir.join(format!("run-{}.log", run_id))
+    }
+
+    /// 为一次运行创建日志文件
+    pub fn create(&self, run_id: i64) -> Result<(), String> {
+        let path = self.path(run_id);
+        let file = File::create(&path)
+            .map_err(|e| format!("创建运行日志文件失败: {}。路径: {:?}", e, path))?;
+
+        let mut writers = self.writers.lock().unwrap();
+        writers.insert(run_id, LineWriter::new(file));
+        Ok(())
+    }
+
+    /// 追加一行日志（格式：`<RFC 3339 时间戳> [<stream>] <内容>`）
+    pub fn append(&self, run_id: i64, log_line: &LogLine) {
+        let line = format!(
+            "{} [{}] {}\n",
+            log_line.timestamp,
+            log_line.stream.as_str(),
+            log_line.text.replace(['\r', '\n'], " ")
+        );
+
+        let mut writers = self.writers.lock().unwrap();
+        if let Some(writer) = writers.get_mut(&run_id) {
+            let _ = writer.write_all(line.as_bytes());
+            return;
+        }
+        drop(writers);
+
+        // 运行结束后才读取到的输出（读取线程晚于监控线程结束），直接追加到已有文件
+        if let Ok(mut file) = OpenOptions::new().append(true).open(self.path(run_id)) {
+            let _ = file.write_all(line.as_bytes());
+        }
+    }
+
+    /// 运行结束，关闭日志文件
+    pub fn finish(&self, run_id: i64) {
+        let mut writers = self.writers.lock().unwrap();
+        if let Some(mut writer) = writers.remove(&run_id) {
+            let _ = writer.flush();
+        }
+    }
+
+    /// 分页读取运行日志
+    pub fn read(&self, run_id: i64, offset: usize, limit: usize) -> Result<RunLogPage, String> {
+        let path = self.path(run_id);
+        if !path.exists() {
+            return Err(format!("运行日志不存在: {}", run_id));
+        }
+
+        // 确保正在写入的内容已落盘
+        {
+            let mut writers = self.writers.lock().unwrap();
+            if let Some(writer) = writers.get_mut(&run_id) {
+                let _ = writer.flush();
+            }
+        }
+
+        let file = File::open(&path)
+            .map_err(|e| format!("读取运行日志失败: {}。路径: {:?}", e, path))?;
+
+        let mut total = 0;
+        let mut lines = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| format!("读取运行日志失败: {}", e))?;
+            if total >= offset && lines.len() < limit {
+                lines.push(parse_line(&line));
+            }
+            total += 1;
+        }
+
+        Ok(RunLogPage {
+            run_id,
+            offset,
+            total,
+            lines,
+        })
+    }
+
+    /// 删除运行日志文件
+    pub fn delete(&self, run_ids: &[i64]) {
+        for run_id in run_ids {
+            self.finish(*run_id);
+            let _ = std::fs::remove_file(self.path(*run_id));
+        }
+    }
+
+    /// 按保留策略清理运行日志，返回删除的文件数（正在写入的日志不会被删除）
+    pub fn cleanup(&self, retention: RunLogRetention) -> usize {
+        let active: Vec<PathBuf> = {
+            let writers = self.writers.lock().unwrap();
+            writers.keys().map(|run_id| self.path(*run_id)).collect()
+        };
+
+        let mut files: Vec<(PathBuf, SystemTime, u64)> = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries
+                .filter_map(Result::ok)
+                .filter(|entry| is_run_log_file(&entry.path()) && !active.contains(&entry.path()))
+                .filter_map(|entry| {
+                    let metadata = entry.metadata().ok()?;
+                    Some((entry.path(), metadata.modified().ok()?, metadata.len()))
+                })
+                .collect(),
+            Err(e) => {
+                log::warn!("读取运行日志目录失败: {}", e);
+                return 0;
+            }
+        };
+
+        // 从最旧的文件开始处理
+        files.sort_by_key(|(_, modified, _)| *modified);
+
+        let now = SystemTime::now();
+        let mut total_bytes: u64 = files.iter().map(|(_, _, size)| size).sum();
+        let mut deleted = 0;
+
+        for (path, modified, size) in files {
+            let expired = retention.max_age.is_some_and(|max_age| {
+                now.duration_since(modified).unwrap_or_default() > max_age
+            });
+            let over_size = retention
+                .max_total_bytes
+                .is_some_and(|max_total| total_bytes > max_total);
+
+            if !expired && !over_size {
+                continue;
+            }
+
+            if std::fs::remove_file(&path).is_ok() {
+                total_bytes = total_bytes.saturating_sub(size);
+                deleted += 1;
+            }
+        }
+
+        deleted
+    }
+}
+
+/// 是否为运行日志文件
+fn is_run_log_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with("run-") && name.ends_with(".log"))
+}
+
+/// 解析日志文件中的一行
+fn parse_line(line: &str) -> RunLogLine {
+    let parsed = line.split_once(' ').and_then(|(timestamp, rest)| {
+        let (stream, text) = rest.strip_prefix('[')?.split_once("] ")?;
+        Some(RunLogLine {
+            timestamp: timestamp.to_string(),
+            stream: stream.to_string(),
+            text: text.to_string(),
+        })
+    });
+
+    // 无法解析的行原样返回
+    parsed.unwrap_or_else(|| RunLogLine {
+        timestamp: String::new(),
+        stream: String::new(),
+        text: line.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ansi::StyledLine;
+    use crate::log_buffer::{LogBuffer, LogStream};
+
+    fn create_test_store(name: &str) -> RunLogStore {
+        let dir = std::env::temp_dir().join(format!(
+            "sigil_test_run_logs_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        RunLogStore::with_dir(dir).unwrap()
+    }
+
+    #[test]
+    fn test_write_and_read_page() {
+        let store = create_test_store("read");
+        let mut buffer = LogBuffer::new(1, 100);
+        store.create(1).unwrap();
+        for i in 0..5 {
+            let stream = if i % 2 == 0 { LogStream::Stdout } else { LogStream::Stderr };
+            store.append(1, &buffer.push(stream, StyledLine::plain(format!("line {}", i))));
+        }
+        store.finish(1);
+        store.append(1, &buffer.push(LogStream::Stdout, StyledLine::plain("late line")));
+
+        let page = store.read(1, 2, 2).unwrap();
+        assert_eq!(page.total, 6);
+        assert_eq!(page.lines.len(), 2);
+        assert_eq!(page.lines[0].stream, "stdout");
+        assert_eq!(page.lines[0].text, "line 2");
+        assert!(!page.lines[0].timestamp.is_empty());
+
+        let page = store.read(1, 5, 10).unwrap();
+        assert_eq!(page.lines[0].text, "late line");
+
+        assert!(store.read(2, 0, 10).is_err());
+        let _ = std::fs::remove_dir_all(&store.dir);
+    }
+
+    #[test]
+    fn test_cleanup_by_size_keeps_active_runs() {
+        let store = create_test_store("cleanup");
+        let mut buffer = LogBuffer::new(1, 100);
+        for run_id in 1..=3 {
+            store.create(run_id).unwrap();
+            store.append(run_id, &buffer.push(LogStream::Stdout, StyledLine::plain("x".repeat(100))));
+            store.finish(run_id);
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        store.create(4).unwrap();
+        store.append(4, &buffer.push(LogStream::Stdout, StyledLine::plain("x".repeat(100))));
+
+        // 总大小上限只够保留一个已结束的文件
+        let deleted = store.cleanup(RunLogRetention {
+            max_age: None,
+            max_total_bytes: Some(200),
+        });
+        assert_eq!(deleted, 2);
+        assert!(!store.path(1).exists());
+        assert!(!store.path(2).exists());
+        assert!(store.path(3).exists());
+        assert!(store.path(4).exists());
+
+        let deleted = store.cleanup(RunLogRetention {
+            max_age: Some(Duration::ZERO),
+            max_total_bytes: None,
+        });
+        assert_eq!(deleted, 1);
+        assert!(store.path(4).exists());
+        let _ = std::fs::remove_dir_all(&store.dir);
+    }
+}
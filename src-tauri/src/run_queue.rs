@@ -0,0 +1,229 @@
+// ==================== 并发限制与运行队列 ====================
+
+use std::collections::{HashMap, VecDeque};
+
+/// 并发限制
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConcurrencyLimits {
+    /// 同时运行的最大命令数，为空时不限制
+    pub max_concurrent_runs: Option<usize>,
+    /// 并发组名称 -> 组内同时运行的最大数量
+    pub groups: HashMap<String, usize>,
+}
+
+impl ConcurrencyLimits {
+    /// 解析并发组配置（JSON 对象，如 `{"heavy builds": 1}`），空值表示没有并发组
+    pub fn parse_groups(value: &str) -> Result<HashMap<String, usize>, String> {
+        if value.trim().is_empty() {
+            return Ok(HashMap::new());
+        }
+        let groups: HashMap<String, usize> =
+            serde_json::from_str(value).map_err(|e| format!("无效的并发组配置: {}", e))?;
+        for (name, max) in &groups {
+            if name.trim().is_empty() {
+                return Err("并发组名称不能为空".to_string());
+            }
+            if *max == 0 {
+                return Err(format!("并发组 {} 的最大运行数必须大于 0", name));
+            }
+        }
+        Ok(groups)
+    }
+
+    /// 在当前运行数下是否可以再启动一个属于 `group` 的运行（未配置的组不限制）
+    pub fn allows(&self, group: Option<&str>, running: &RunningCounts) -> bool {
+        if self
+            .max_concurrent_runs
+            .is_some_and(|max| running.total >= max)
+        {
+            return false;
+        }
+        match group.and_then(|group| Some((group, self.groups.get(group)?))) {
+            Some((group, max)) => running.group(group) < *max,
+            None => true,
+        }
+    }
+}
+
+/// 正在运行的数量
+#[derive(Debug, Clone, Default)]
+pub struct RunningCounts {
+    pub total: usize,
+    groups: HashMap<String, usize>,
+}
+
+impl RunningCounts {
+    /// 记录一个正在运行的命令
+    pub fn add(&mut self, group: Option<&str>) {
+        self.total += 1;
+        if let Some(group) = group {
+            *self.groups.entry(group.to_string()).or_default() += 1;
+        }
+    }
+
+    fn group(&self, group: &str) -> usize {
+        self.groups.get(group).copied().unwrap_or(0)
+    }
+}
+
+/// 排队中的运行
+#[derive(Debug, Clone)]
+pub struct QueueEntry<T> {
+    /// 排队 ID（负数，与临时运行 ID 共用编号）
+    pub id: i64,
+    pub command_id: i64,
+    pub group: Option<String>,
+    /// 进入队列的时间（RFC 3339）
+    pub queued_at: String,
+    pub payload: T,
+}
+
+/// 等待并发名额的运行队列（先进先出，可以取消和调整顺序）
+#[derive(Debug)]
+pub struct RunQueue<T> {
+    entries: VecDeque<QueueEntry<T>>,
+}
+
+impl<T> Default for RunQueue<T> {
+    fn default() -> Self {
+        Self {
+            entries: VecDeque::new(),
+        }
+    }
+}
+
+impl<T> RunQueue<T> {
+    /// 加入队尾，返回排队位置（从 1 开始）
+    pub fn push(&mut self, entry: QueueEntry<T>) -> usize {
+        self.entries.push_back(entry);
+        self.entries.len()
+    }
+
+    /// 从队列中移除
+    pub fn remove(&mut self, id: i64) -> Option<QueueEntry<T>> {
+        let index = self.entries.iter().position(|entry| entry.id == id)?;
+        self.entries.remove(index)
+    }
+
+    /// 移除命令的所有排队运行
+    pub fn remove_command(&mut self, command_id: i64) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|entry| entry.command_id != command_id);
+        before - self.entries.len()
+    }
+
+    /// 移动到指定位置（从 1 开始，超出范围时移到队尾）
+    pub fn move_to(&mut self, id: i64, position: usize) -> Result<(), String> {
+        let entry = self.remove(id).ok_or("排队中的运行不存在")?;
+        let index = position.saturating_sub(1).min(self.entries.len());
+        self.entries.insert(index, entry);
+        Ok(())
+    }
+
+    /// 按队列顺序遍历，附带排队位置（从 1 开始）
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &QueueEntry<T>)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| (index + 1, entry))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 取出下一个可以启动的运行
+    ///
+    /// 按队列顺序选择第一个不超出限制的运行；所在组已满的运行让位给后面其他组的运行，
+    /// 同一组内仍按先后顺序启动。
+    pub fn take_next(
+        &mut self,
+        limits: &ConcurrencyLimits,
+        running: &RunningCounts,
+    ) -> Option<QueueEntry<T>> {
+        let index = self
+            .entries
+            .iter()
+            .position(|entry| limits.allows(entry.group.as_deref(), running))?;
+        self.entries.remove(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: i64, group: Option<&str>) -> QueueEntry<()> {
+        QueueEntry {
+            id,
+            command_id: id,
+            group: group.map(str::to_string),
+            queued_at: String::new(),
+            payload: (),
+        }
+    }
+
+    fn ids(queue: &RunQueue<()>) -> Vec<i64> {
+        queue.iter().map(|(_, entry)| entry.id).collect()
+    }
+
+    #[test]
+    fn test_take_next_respects_limits() {
+        let limits = ConcurrencyLimits {
+            max_concurrent_runs: Some(2),
+            groups: [("heavy".to_string(), 1)].into(),
+        };
+        let mut queue = RunQueue::default();
+        queue.push(entry(1, Some("heavy")));
+        queue.push(entry(2, Some("heavy")));
+        queue.push(entry(3, None));
+
+        let mut running = RunningCounts::default();
+        running.add(Some("heavy"));
+
+        // 组已满时让位给其他组，组内按顺序
+        assert_eq!(queue.take_next(&limits, &running).unwrap().id, 3);
+        running.add(None);
+        // 总数已满
+        assert!(queue.take_next(&limits, &running).is_none());
+
+        let mut running = RunningCounts::default();
+        running.add(None);
+        assert_eq!(queue.take_next(&limits, &running).unwrap().id, 1);
+        running.add(Some("heavy"));
+        assert!(queue.take_next(&limits, &running).is_none());
+        assert_eq!(ids(&queue), vec![2]);
+
+        // 未配置的组和没有总数限制时不限制
+        assert!(ConcurrencyLimits::default().allows(Some("other"), &running));
+    }
+
+    #[test]
+    fn test_cancel_and_reorder() {
+        let mut queue = RunQueue::default();
+        for id in 1..=4 {
+            assert_eq!(queue.push(entry(id, None)), id as usize);
+        }
+
+        queue.move_to(4, 1).unwrap();
+        assert_eq!(ids(&queue), vec![4, 1, 2, 3]);
+        queue.move_to(4, 10).unwrap();
+        assert_eq!(ids(&queue), vec![1, 2, 3, 4]);
+        assert!(queue.move_to(5, 1).is_err());
+
+        assert_eq!(queue.remove(2).unwrap().id, 2);
+        assert_eq!(queue.remove_command(3), 1);
+        assert_eq!(ids(&queue), vec![1, 4]);
+        assert_eq!(queue.iter().last().unwrap().0, 2);
+    }
+
+    #[test]
+    fn test_parse_groups() {
+        let groups = ConcurrencyLimits::parse_groups(r#"{"heavy builds": 1}"#).unwrap();
+        assert_eq!(groups.get("heavy builds"), Some(&1));
+        assert!(ConcurrencyLimits::parse_groups("").unwrap().is_empty());
+        assert!(ConcurrencyLimits::parse_groups(r#"{"heavy": 0}"#).is_err());
+        assert!(ConcurrencyLimits::parse_groups(r#"{"heavy": -1}"#).is_err());
+        assert!(ConcurrencyLimits::parse_groups("[1]").is_err());
+    }
+}
@@ -0,0 +1,443 @@
+// ==================== 定时执行计划 ====================
+
+use chrono::{
+    DateTime, Datelike, Local, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Timelike,
+};
+use crate::command_params::{self, CommandParameter};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// 错过执行时间（应用未运行或系统休眠）后的处理方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum MissedRunPolicy {
+    /// 跳过错过的执行，等待下一次
+    #[default]
+    Skip,
+    /// 启动（或系统唤醒）后补执行一次
+    RunOnce,
+}
+
+/// 到达执行时间时上一次运行仍未结束的处理方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum OverlapPolicy {
+    /// 跳过本次执行
+    #[default]
+    Skip,
+    /// 等上一次运行结束后立即执行
+    Queue,
+    /// 停止上一次运行后重新执行
+    Restart,
+}
+
+/// 命令的定时执行配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CommandSchedule {
+    pub enabled: bool,
+    /// cron 表达式（`分 时 日 月 周`）、`@daily` 等别名，或固定间隔（如 `@every 30m`）
+    pub expression: String,
+    pub missed_run: MissedRunPolicy,
+    pub overlap: OverlapPolicy,
+}
+
+impl CommandSchedule {
+    /// 是否已启用且填写了表达式
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.expression.trim().is_empty()
+    }
+
+    /// 校验执行计划
+    ///
+    /// 定时执行时无法填写参数，命令中有未设置默认值的参数时不能启用。
+    pub fn validate(&self, command: &str, parameters: &[CommandParameter]) -> Result<(), String> {
+        if !self.is_active() {
+            return Ok(());
+        }
+        Schedule::parse(&self.expression)?;
+        let required: Vec<String> = command_params::resolve_parameters(command, parameters)?
+            .into_iter()
+            .filter(|parameter| parameter.default.is_none())
+            .map(|parameter| parameter.name)
+            .collect();
+        if !required.is_empty() {
+            return Err(format!(
+                "定时执行时无法填写参数，请为参数 {} 设置默认值",
+                required.join(", ")
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 解析后的执行计划
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schedule {
+    Cron(CronSchedule),
+    /// 固定间隔，从上一次执行（或启用）的时间开始计算
+    Interval(Duration),
+}
+
+impl Schedule {
+    /// 解析执行计划表达式
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expression = expression.trim();
+        if let Some(interval) = expression.strip_prefix("@every") {
+            return parse_interval(interval.trim()).map(Schedule::Interval);
+        }
+
+        let cron = match expression.to_ascii_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            _ if expression.starts_with('@') => {
+                return Err(format!("不支持的执行计划: {}", expression));
+            }
+            _ => expression,
+        };
+        CronSchedule::parse(cron).map(Schedule::Cron)
+    }
+
+    /// `after` 之后的下一次执行时间（不包含 `after` 本身）
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        match self {
+            Schedule::Cron(cron) => cron.next_after(after),
+            Schedule::Interval(interval) => {
+                after.checked_add_signed(chrono::Duration::from_std(*interval).ok()?)
+            }
+        }
+    }
+
+    /// 从 `after` 开始的若干次执行时间
+    pub fn upcoming(&self, after: DateTime<Local>, count: usize) -> Vec<DateTime<Local>> {
+        let mut times = Vec::with_capacity(count);
+        let mut current = after;
+        while times.len() < count {
+            match self.next_after(current) {
+                Some(next) => {
+                    times.push(next);
+                    current = next;
+                }
+                None => break,
+            }
+        }
+        times
+    }
+}
+
+/// 解析间隔，如 `90s`、`5m`、`1h30m`、`2d`
+fn parse_interval(text: &str) -> Result<Duration, String> {
+    let invalid = || format!("无效的执行间隔: {}", text);
+    if text.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut secs: u64 = 0;
+    let mut number = String::new();
+    for c in text.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86400,
+            _ => return Err(invalid()),
+        };
+        let value: u64 = number.parse().map_err(|_| invalid())?;
+        secs = value
+            .checked_mul(unit)
+            .and_then(|v| secs.checked_add(v))
+            .ok_or_else(invalid)?;
+        number.clear();
+    }
+    if !number.is_empty() {
+        return Err(invalid());
+    }
+    if secs == 0 {
+        return Err("执行间隔必须大于 0".to_string());
+    }
+    Ok(Duration::from_secs(secs))
+}
+
+/// 五段式 cron 表达式：分 时 日 月 周
+///
+/// 每一段支持 `*`、数字、范围 `a-b`、步长 `*/n`、`a-b/n` 以及逗号分隔的列表；
+/// 月份和星期可以使用英文缩写（`jan`、`mon`），星期中 0 和 7 都表示周日。
+/// 与标准 cron 相同，日和周同时被限定时满足其一即可。
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// 向后查找下一次执行时间的最大范围（年），防止 `0 0 30 2 *` 这类永不触发的表达式死循环
+const MAX_SEARCH_YEARS: i32 = 5;
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!(
+                "cron 表达式应包含 5 段（分 时 日 月 周）: {}",
+                expression
+            ));
+        }
+
+        let mut weekdays = parse_field(fields[4], 0, 7, &WEEKDAY_NAMES, 0)?;
+        // 7 同样表示周日
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59, &[], 0)?,
+            hours: parse_field(fields[1], 0, 23, &[], 0)?,
+            days: parse_field(fields[2], 1, 31, &[], 0)?,
+            months: parse_field(fields[3], 1, 12, &MONTH_NAMES, 1)?,
+            weekdays,
+            // 与 Vixie cron 相同，以 `*` 开头（如 `*/2`）视为未限定
+            days_restricted: !fields[2].starts_with('*'),
+            weekdays_restricted: !fields[4].starts_with('*'),
+        })
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        if self.days_restricted && self.weekdays_restricted {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start =
+            after.naive_local().with_second(0)?.with_nanosecond(0)? + chrono::Duration::minutes(1);
+        let limit = start.year() + MAX_SEARCH_YEARS;
+        let mut current = start;
+
+        while current.year() <= limit {
+            let date = current.date();
+            if self.months & (1 << current.month()) == 0 {
+                // 跳到下个月 1 日
+                let (year, month) = if current.month() == 12 {
+                    (current.year() + 1, 1)
+                } else {
+                    (current.year(), current.month() + 1)
+                };
+                current = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.matches_day(date) {
+                current = date.succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if self.hours & (1 << current.hour()) == 0 {
+                current = date.and_hms_opt(current.hour(), 0, 0)? + chrono::Duration::hours(1);
+                continue;
+            }
+            if self.minutes & (1 << current.minute()) == 0 {
+                current += chrono::Duration::minutes(1);
+                continue;
+            }
+
+            match to_local(current) {
+                Some(time) => return Some(time),
+                // 夏令时跳过的时间不存在，继续向后查找
+                None => current += chrono::Duration::minutes(1),
+            }
+        }
+        None
+    }
+}
+
+/// 本地时间转换（夏令时回拨导致重复时取较早的一个）
+fn to_local(time: NaiveDateTime) -> Option<DateTime<Local>> {
+    match Local.from_local_datetime(&time) {
+        LocalResult::Single(time) => Some(time),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest),
+        LocalResult::None => None,
+    }
+}
+
+/// 解析 cron 的一段，返回按位表示的取值集合
+///
+/// `names` 为可用的英文缩写，第 i 个缩写对应 `name_offset + i`。
+fn parse_field(
+    field: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    name_offset: u32,
+) -> Result<u64, String> {
+    let invalid = || format!("无效的 cron 字段: {}", field);
+    let value = |text: &str| -> Result<u32, String> {
+        let lower = text.to_ascii_lowercase();
+        let value = match names.iter().position(|name| *name == lower) {
+            Some(index) => index as u32 + name_offset,
+            None => text.parse().map_err(|_| invalid())?,
+        };
+        if value < min || value > max {
+            return Err(format!("cron 字段 {} 超出范围 {}-{}", field, min, max));
+        }
+        Ok(value)
+    };
+
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| invalid())?;
+                if step == 0 {
+                    return Err(invalid());
+                }
+                (range, Some(step))
+            }
+            None => (part, None),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (value(start)?, value(end)?)
+        } else {
+            let start = value(range)?;
+            // `a/n` 表示从 a 开始到最大值
+            (start, if step.is_some() { max } else { start })
+        };
+        if start > end {
+            return Err(invalid());
+        }
+
+        for v in (start..=end).step_by(step.unwrap_or(1) as usize) {
+            bits |= 1 << v;
+        }
+    }
+    Ok(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(text: &str) -> DateTime<Local> {
+        let naive = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap();
+        to_local(naive).unwrap()
+    }
+
+    fn upcoming(expression: &str, after: &str, count: usize) -> Vec<String> {
+        Schedule::parse(expression)
+            .unwrap()
+            .upcoming(local(after), count)
+            .iter()
+            .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_requires_parameter_defaults() {
+        let schedule = CommandSchedule {
+            enabled: true,
+            expression: "@every 1h".to_string(),
+            ..Default::default()
+        };
+        assert!(schedule.validate("npm run {{script}}", &[]).is_err());
+        assert!(schedule.validate("npm run {{script=build}}", &[]).is_ok());
+
+        // 已保存的参数定义提供默认值
+        let parameters = vec![CommandParameter {
+            name: "script".to_string(),
+            param_type: Default::default(),
+            default: Some("build".to_string()),
+            description: None,
+            options: Vec::new(),
+        }];
+        assert!(schedule.validate("npm run {{script}}", &parameters).is_ok());
+
+        // 未启用时不校验
+        let disabled = CommandSchedule {
+            enabled: false,
+            ..schedule.clone()
+        };
+        assert!(disabled.validate("npm run {{script}}", &[]).is_ok());
+        let invalid = CommandSchedule {
+            expression: "not a schedule".to_string(),
+            ..schedule
+        };
+        assert!(invalid.validate("echo", &[]).is_err());
+    }
+
+    #[test]
+    fn test_cron_next_runs() {
+        assert_eq!(
+            upcoming("30 2 * * *", "2024-03-01 02:30", 2),
+            vec!["2024-03-02 02:30", "2024-03-03 02:30"]
+        );
+        assert_eq!(
+            upcoming("*/20 9-10 * * mon-fri", "2024-03-01 10:50", 3),
+            vec!["2024-03-04 09:00", "2024-03-04 09:20", "2024-03-04 09:40"]
+        );
+        // 日和周同时限定时满足其一即可
+        assert_eq!(
+            upcoming("0 0 13 * 5", "2024-09-01 00:00", 3),
+            vec!["2024-09-06 00:00", "2024-09-13 00:00", "2024-09-20 00:00"]
+        );
+        assert_eq!(
+            upcoming("@monthly", "2024-01-31 12:00", 1),
+            vec!["2024-02-01 00:00"]
+        );
+        assert_eq!(
+            upcoming("0 12 29 feb 7", "2024-02-20 00:00", 2),
+            vec!["2024-02-25 12:00", "2024-02-29 12:00"]
+        );
+    }
+
+    #[test]
+    fn test_interval() {
+        assert_eq!(
+            Schedule::parse("@every 1h30m").unwrap(),
+            Schedule::Interval(Duration::from_secs(5400))
+        );
+        assert_eq!(
+            upcoming("@every 45s", "2024-03-01 08:00", 2),
+            vec!["2024-03-01 08:00", "2024-03-01 08:01"]
+        );
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        for expression in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "@every",
+            "@every 0s",
+            "@every 5x",
+            "@often",
+        ] {
+            assert!(Schedule::parse(expression).is_err(), "{}", expression);
+        }
+        // 永不触发的日期
+        let never = Schedule::parse("0 0 30 2 *").unwrap();
+        assert_eq!(never.next_after(local("2024-01-01 00:00")), None);
+    }
+}
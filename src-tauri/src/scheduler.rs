@@ -0,0 +1,274 @@
+// ==================== 定时执行调度 ====================
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::command_runner::{CommandRunner, CommandStatus, ExecuteCommandParams, RunTrigger};
+use crate::constants;
+use crate::db::{CommandRunFilter, Database};
+use crate::schedule::{CommandSchedule, MissedRunPolicy, OverlapPolicy, Schedule};
+
+/// 命令的定时执行状态（供前端展示）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleStatus {
+    pub command_id: i64,
+    pub expression: String,
+    /// 下一次执行时间（RFC 3339），表达式无效或不会再触发时为空
+    pub next_run_at: Option<String>,
+    /// 本次启动以来最近一次定时触发的时间
+    pub last_run_at: Option<String>,
+    /// 是否在等待上一次运行结束（重叠策略为排队或重启时）
+    pub pending: bool,
+    /// 表达式解析错误
+    pub error: Option<String>,
+}
+
+/// 单个命令的调度项
+struct ScheduleEntry {
+    schedule: CommandSchedule,
+    parsed: Result<Schedule, String>,
+    next_run: Option<DateTime<Local>>,
+    last_run: Option<DateTime<Local>>,
+    pending: bool,
+}
+
+impl ScheduleEntry {
+    fn new(schedule: CommandSchedule, now: DateTime<Local>) -> Self {
+        let parsed = Schedule::parse(&schedule.expression);
+        let next_run = parsed
+            .as_ref()
+            .ok()
+            .and_then(|parsed| parsed.next_after(now));
+        Self {
+            schedule,
+            parsed,
+            next_run,
+            last_run: None,
+            pending: false,
+        }
+    }
+
+    fn status(&self, command_id: i64) -> ScheduleStatus {
+        ScheduleStatus {
+            command_id,
+            expression: self.schedule.expression.clone(),
+            next_run_at: self.next_run.map(|time| time.to_rfc3339()),
+            last_run_at: self.last_run.map(|time| time.to_rfc3339()),
+            pending: self.pending,
+            error: self.parsed.as_ref().err().cloned(),
+        }
+    }
+}
+
+/// 定时执行调度器
+pub struct CommandScheduler {
+    entries: Arc<Mutex<HashMap<i64, ScheduleEntry>>>,
+    scheduling_thread: Arc<AtomicBool>,
+}
+
+impl Default for CommandScheduler {
+    fn default() -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            scheduling_thread: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+impl CommandScheduler {
+    /// 从数据库重新加载定时配置（命令新增、修改、删除后调用）
+    ///
+    /// 配置未变化的命令保留原有的下一次执行时间。
+    pub fn reload(&self, database: &Database) {
+        self.load(database, false);
+    }
+
+    /// 加载定时配置；`startup` 为 true 时按错过执行策略处理应用未运行期间错过的执行
+    fn load(&self, database: &Database, startup: bool) {
+        let commands = match database.get_all_commands() {
+            Ok(commands) => commands,
+            Err(e) => {
+                log::error!("加载定时执行配置失败: {}", e);
+                return;
+            }
+        };
+
+        let now = Local::now();
+        let mut entries = self.entries.lock().unwrap();
+        let mut loaded = HashMap::new();
+        for command in commands {
+            if !command.schedule.is_active() {
+                continue;
+            }
+            let entry = match entries.remove(&command.id) {
+                Some(entry) if entry.schedule == command.schedule => entry,
+                _ => {
+                    let mut entry = ScheduleEntry::new(command.schedule, now);
+                    if startup && entry.schedule.missed_run == MissedRunPolicy::RunOnce {
+                        if let Some(missed) = Self::missed_run(database, command.id, &entry, now) {
+                            log::info!(
+                                "命令 {} 错过了 {} 的定时执行，立即补执行",
+                                command.id,
+                                missed
+                            );
+                            entry.next_run = Some(now);
+                        }
+                    }
+                    entry
+                }
+            };
+            loaded.insert(command.id, entry);
+        }
+        *entries = loaded;
+    }
+
+    /// 根据最近一次定时运行记录判断应用未运行期间是否错过了执行
+    fn missed_run(
+        database: &Database,
+        command_id: i64,
+        entry: &ScheduleEntry,
+        now: DateTime<Local>,
+    ) -> Option<DateTime<Local>> {
+        let parsed = entry.parsed.as_ref().ok()?;
+        let filter = CommandRunFilter {
+            command_id: Some(command_id),
+            trigger: Some(RunTrigger::Schedule),
+            ..Default::default()
+        };
+        let last_run = database.get_command_runs(&filter, 0, 1).ok()?.runs.pop()?;
+        let last_started = DateTime::parse_from_rfc3339(&last_run.started_at)
+            .ok()?
+            .with_timezone(&Local);
+        parsed.next_after(last_started).filter(|due| *due <= now)
+    }
+
+    /// 所有命令的定时执行状态
+    pub fn get_statuses(&self) -> Vec<ScheduleStatus> {
+        let entries = self.entries.lock().unwrap();
+        let mut statuses: Vec<ScheduleStatus> = entries
+            .iter()
+            .map(|(command_id, entry)| entry.status(*command_id))
+            .collect();
+        statuses.sort_by_key(|status| status.command_id);
+        statuses
+    }
+
+    /// 检查并触发到期的定时执行
+    fn tick(&self, app: &AppHandle) {
+        let now = Local::now();
+        let grace = chrono::Duration::seconds(constants::schedule::MISSED_RUN_GRACE_SECS);
+
+        let due: Vec<(i64, OverlapPolicy, bool)> = {
+            let mut entries = self.entries.lock().unwrap();
+            let mut due = Vec::new();
+            for (command_id, entry) in entries.iter_mut() {
+                let Some(next_run) = entry.next_run.filter(|next_run| *next_run <= now) else {
+                    if entry.pending {
+                        due.push((*command_id, entry.schedule.overlap, true));
+                    }
+                    continue;
+                };
+
+                // 先计算下一次执行时间，错过的多次执行只补一次
+                entry.next_run = entry
+                    .parsed
+                    .as_ref()
+                    .ok()
+                    .and_then(|parsed| parsed.next_after(now));
+
+                // 系统休眠等原因导致严重延迟时，按错过执行策略处理
+                if now - next_run > grace && entry.schedule.missed_run == MissedRunPolicy::Skip {
+                    log::info!("命令 {} 错过了 {} 的定时执行，已跳过", command_id, next_run);
+                    continue;
+                }
+                due.push((*command_id, entry.schedule.overlap, entry.pending));
+            }
+            due
+        };
+
+        if due.is_empty() {
+            return;
+        }
+        let (Some(runner), Some(database)) = (
+            app.try_state::<CommandRunner>(),
+            app.try_state::<Database>(),
+        ) else {
+            return;
+        };
+
+        for (command_id, overlap, pending) in due {
+            // 排队中的运行也视为尚未结束
+            let running = runner.get_state(command_id).is_some_and(|state| {
+                state.status.is_active() || state.status == CommandStatus::Queued
+            });
+
+            if running {
+                match overlap {
+                    OverlapPolicy::Skip => {
+                        log::info!("命令 {} 上一次运行尚未结束，跳过本次定时执行", command_id);
+                    }
+                    OverlapPolicy::Queue => self.set_pending(command_id, true),
+                    OverlapPolicy::Restart => {
+                        if !pending {
+                            log::info!("命令 {} 上一次运行尚未结束，停止后重新执行", command_id);
+                            if let Err(e) = runner.stop(command_id) {
+                                log::warn!("停止命令 {} 失败: {}", command_id, e);
+                            }
+                        }
+                        self.set_pending(command_id, true);
+                    }
+                }
+                continue;
+            }
+
+            self.set_pending(command_id, false);
+            let result = database.get_command_by_id(command_id).and_then(|command| {
+                let params = ExecuteCommandParams::from_command(command, HashMap::new());
+                runner.execute(params, RunTrigger::Schedule)
+            });
+            match result {
+                Ok(_) => {
+                    log::info!("定时执行命令 {}", command_id);
+                    if let Some(entry) = self.entries.lock().unwrap().get_mut(&command_id) {
+                        entry.last_run = Some(now);
+                    }
+                }
+                Err(e) => log::error!("定时执行命令 {} 失败: {}", command_id, e),
+            }
+        }
+    }
+
+    fn set_pending(&self, command_id: i64, pending: bool) {
+        if let Some(entry) = self.entries.lock().unwrap().get_mut(&command_id) {
+            entry.pending = pending;
+        }
+    }
+}
+
+/// 启动定时执行调度线程
+pub fn start_scheduler(app: AppHandle) {
+    let scheduler = app.state::<CommandScheduler>();
+
+    // 如果已经在调度，直接返回
+    if scheduler.scheduling_thread.swap(true, Ordering::Relaxed) {
+        return;
+    }
+
+    if let Some(database) = app.try_state::<Database>() {
+        scheduler.load(&database, true);
+    }
+
+    let scheduling_flag = scheduler.scheduling_thread.clone();
+    thread::spawn(move || {
+        while scheduling_flag.load(Ordering::Relaxed) {
+            app.state::<CommandScheduler>().tick(&app);
+            thread::sleep(Duration::from_millis(constants::schedule::TICK_INTERVAL_MS));
+        }
+    });
+}
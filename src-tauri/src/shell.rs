@@ -0,0 +1,310 @@
+// ==================== Shell 选择 ====================
+
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use crate::command_params::QuoteStyle;
+
+/// 执行命令使用的 shell
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Shell {
+    /// 命令上表示使用全局默认 shell；全局默认为该值时使用系统 shell（Windows 为 cmd，其他为 sh）
+    #[default]
+    SystemDefault,
+    Bash,
+    Zsh,
+    Pwsh,
+    Powershell,
+    Cmd,
+    /// 不经过 shell，将命令拆分为参数后直接执行
+    None,
+}
+
+impl Shell {
+    /// 解析配置值（如 `bash`），空值视为系统默认
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Ok(Shell::SystemDefault);
+        }
+        serde_json::from_value(serde_json::Value::String(value.to_string()))
+            .map_err(|_| format!("无效的 shell: {}", value))
+    }
+
+    /// 命令未指定 shell 时使用全局默认 shell
+    pub fn or_default(self, default: Shell) -> Shell {
+        match self {
+            Shell::SystemDefault => default,
+            shell => shell,
+        }
+    }
+
+    /// 参数值的引用方式
+    pub fn quote_style(&self) -> QuoteStyle {
+        match self {
+            #[cfg(target_os = "windows")]
+            Shell::SystemDefault => QuoteStyle::Cmd,
+            #[cfg(not(target_os = "windows"))]
+            Shell::SystemDefault => QuoteStyle::Posix,
+            Shell::Bash | Shell::Zsh => QuoteStyle::Posix,
+            Shell::Pwsh | Shell::Powershell => QuoteStyle::PowerShell,
+            Shell::Cmd => QuoteStyle::Cmd,
+            Shell::None => QuoteStyle::Argv,
+        }
+    }
+
+    /// 执行命令行的程序和参数
+    pub fn program_and_args(&self, command_line: &str) -> Result<(String, Vec<String>), String> {
+        let with = |program: &str, args: &[&str]| {
+            let mut args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+            args.push(command_line.to_string());
+            Ok((program.to_string(), args))
+        };
+        match self {
+            #[cfg(target_os = "windows")]
+            Shell::SystemDefault => with("cmd", &["/C"]),
+            #[cfg(not(target_os = "windows"))]
+            Shell::SystemDefault => with("sh", &["-c"]),
+            Shell::Bash => with("bash", &["-c"]),
+            Shell::Zsh => with("zsh", &["-c"]),
+            Shell::Pwsh => with("pwsh", &["-NoProfile", "-Command"]),
+            Shell::Powershell => with("powershell", &["-NoProfile", "-Command"]),
+            Shell::Cmd => with("cmd", &["/C"]),
+            Shell::None => {
+                let mut argv = split_command_line(command_line)?;
+                let program = argv.remove(0);
+                Ok((program, argv))
+            }
+        }
+    }
+
+    /// 完整的 Windows 命令行（ConPTY 使用）
+    ///
+    /// cmd 按原样传递命令行，其他程序的参数按 CommandLineToArgvW 规则加引号。
+    #[cfg(target_os = "windows")]
+    pub fn windows_command_line(&self, command_line: &str) -> Result<String, String> {
+        let (program, args) = self.program_and_args(command_line)?;
+        if program == "cmd" {
+            return Ok(format!("cmd /C {}", command_line));
+        }
+        let mut result = quote_arg_if_needed(&program);
+        for arg in &args {
+            result.push(' ');
+            result.push_str(&quote_arg_if_needed(arg));
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn quote_arg_if_needed(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+        arg.to_string()
+    } else {
+        crate::command_params::quote_windows_arg(arg)
+    }
+}
+
+/// 将命令行拆分为参数
+///
+/// 以空白分隔；单引号内按原样保留；双引号内 `\"` 和 `\\` 为转义，其他反斜杠按原样保留
+/// （避免破坏 Windows 路径）。
+pub fn split_command_line(command_line: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut chars = command_line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            '\'' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err("命令中的单引号未闭合".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') if matches!(chars.peek(), Some('"' | '\\')) => {
+                            current.push(chars.next().unwrap());
+                        }
+                        Some(c) => current.push(c),
+                        None => return Err("命令中的双引号未闭合".to_string()),
+                    }
+                }
+            }
+            c => {
+                in_arg = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+
+    if args.is_empty() {
+        return Err("命令不能为空".to_string());
+    }
+    Ok(args)
+}
+
+/// 在 PATH 中查找可执行文件（`path_var` 为空时使用当前进程的 PATH）
+///
+/// 包含路径分隔符的程序直接检查文件（相对路径基于 `working_dir`）；Windows 按 PATHEXT 补全扩展名。
+pub fn find_executable(
+    program: &str,
+    path_var: Option<&OsStr>,
+    working_dir: Option<&Path>,
+) -> Option<PathBuf> {
+    let candidates = |base: PathBuf| -> Vec<PathBuf> {
+        #[cfg(target_os = "windows")]
+        {
+            let mut candidates = vec![base.clone()];
+            if base.extension().is_none() {
+                let exts =
+                    std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".into());
+                candidates.extend(
+                    exts.split(';')
+                        .filter(|ext| !ext.is_empty())
+                        .map(|ext| base.with_extension(ext.trim_start_matches('.'))),
+                );
+            }
+            candidates
+        }
+        #[cfg(not(target_os = "windows"))]
+        {
+            vec![base]
+        }
+    };
+
+    if program.contains(['/', '\\']) {
+        let path = match working_dir {
+            Some(dir) => dir.join(program),
+            None => PathBuf::from(program),
+        };
+        return candidates(path)
+            .into_iter()
+            .find(|path| is_executable(path));
+    }
+
+    let path_var = path_var
+        .map(|path| path.to_os_string())
+        .or_else(|| std::env::var_os("PATH"))?;
+    std::env::split_paths(&path_var)
+        .flat_map(|dir| candidates(dir.join(program)))
+        .find(|path| is_executable(path))
+}
+
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        path.metadata()
+            .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_params;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_split_command_line() {
+        assert_eq!(
+            split_command_line(r#"git commit -m "fix \"quoted\" bug" 'a b'"#).unwrap(),
+            vec!["git", "commit", "-m", r#"fix "quoted" bug"#, "a b"]
+        );
+        // 引号外的反斜杠按原样保留
+        assert_eq!(
+            split_command_line(r"C:\tools\app.exe --dir C:\data").unwrap(),
+            vec![r"C:\tools\app.exe", "--dir", r"C:\data"]
+        );
+        assert_eq!(split_command_line("echo ''").unwrap(), vec!["echo", ""]);
+        assert!(split_command_line("echo 'open").is_err());
+        assert!(split_command_line("   ").is_err());
+    }
+
+    #[test]
+    fn test_program_and_args() {
+        let (program, args) = Shell::Bash.program_and_args("echo $HOME").unwrap();
+        assert_eq!(
+            (program.as_str(), args),
+            ("bash", vec!["-c".to_string(), "echo $HOME".to_string()])
+        );
+
+        let (program, args) = Shell::None
+            .program_and_args("node server.js --port 3000")
+            .unwrap();
+        assert_eq!(program, "node");
+        assert_eq!(args, vec!["server.js", "--port", "3000"]);
+
+        assert_eq!(Shell::parse("pwsh").unwrap(), Shell::Pwsh);
+        assert_eq!(Shell::parse("").unwrap(), Shell::SystemDefault);
+        assert!(Shell::parse("fish").is_err());
+        assert_eq!(Shell::SystemDefault.or_default(Shell::Zsh), Shell::Zsh);
+        assert_eq!(Shell::None.or_default(Shell::Zsh), Shell::None);
+    }
+
+    #[test]
+    fn test_argv_quoting_round_trip() {
+        let values: HashMap<String, String> =
+            [("msg".to_string(), r#"it's "a" C:\path"#.to_string())].into();
+        let rendered =
+            command_params::render_command("echo {{msg}}", &[], &values, QuoteStyle::Argv).unwrap();
+        assert_eq!(
+            split_command_line(&rendered).unwrap(),
+            vec!["echo", r#"it's "a" C:\path"#]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_executable() {
+        assert!(find_executable("sh", None, None).is_some());
+        assert!(find_executable("/bin/sh", None, None).is_some());
+        assert!(find_executable("./sh", None, Some(Path::new("/bin"))).is_some());
+        assert!(find_executable("definitely-not-a-real-shell", None, None).is_none());
+        assert!(find_executable("sh", Some(OsStr::new("/nonexistent")), None).is_none());
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn test_windows_command_line() {
+        assert_eq!(
+            Shell::Pwsh.windows_command_line("Get-Date").unwrap(),
+            "pwsh -NoProfile -Command Get-Date"
+        );
+        assert_eq!(
+            Shell::Cmd.windows_command_line("echo a & echo b").unwrap(),
+            "cmd /C echo a & echo b"
+        );
+        assert_eq!(
+            Shell::None
+                .windows_command_line(r#"app.exe "a b""#)
+                .unwrap(),
+            r#"app.exe "a b""#
+        );
+    }
+}
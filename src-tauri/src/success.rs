@@ -0,0 +1,223 @@
+// ==================== 成功判定规则 ====================
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+use crate::log_buffer::LogStream;
+use crate::resource_limits::LimitedResource;
+
+/// 成功判定规则
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SuccessRule {
+    /// 视为成功的退出码，逗号分隔，支持范围（如 `0-7` 或 `0,1`），为空时只有 0
+    pub exit_codes: String,
+    /// 标准错误输出匹配该正则表达式时视为失败
+    pub stderr_fail_pattern: Option<String>,
+    /// 标准输出没有匹配该正则表达式的行时视为失败
+    pub stdout_require_pattern: Option<String>,
+}
+
+impl SuccessRule {
+    /// 校验规则
+    pub fn validate(&self) -> Result<(), String> {
+        SuccessChecker::new(self).map(|_| ())
+    }
+}
+
+/// 判定为失败的原因
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum FailureReason {
+    /// 退出码不在允许范围内（被信号终止时没有退出码）
+    ExitCode { code: Option<i32> },
+    /// 标准错误输出匹配了失败规则
+    StderrMatched { line: String },
+    /// 标准输出没有匹配必需的规则
+    StdoutMissing,
+    /// 被输出触发器标记为失败
+    Trigger,
+    /// 超出资源限制被终止
+    ResourceLimit { resource: LimitedResource },
+}
+
+/// 解析退出码列表，如 `0-7, 9`
+fn parse_exit_codes(spec: &str) -> Result<Vec<RangeInclusive<i32>>, String> {
+    let spec = spec.trim();
+    if spec.is_empty() {
+        return Ok(vec![0..=0]);
+    }
+
+    let parse = |value: &str| {
+        value
+            .trim()
+            .parse::<i32>()
+            .map_err(|_| format!("无效的退出码: {}", value.trim()))
+    };
+    spec.split(',')
+        .map(|part| {
+            // 从第二个字符开始查找 `-`，允许以负数开头（如 Windows 的 -1）
+            let range = match part.trim().get(1..).and_then(|rest| rest.find('-')) {
+                Some(index) => {
+                    let (start, end) = part.trim().split_at(index + 1);
+                    parse(start)?..=parse(&end[1..])?
+                }
+                None => parse(part)?..=parse(part)?,
+            };
+            if range.is_empty() {
+                return Err(format!("无效的退出码范围: {}", part.trim()));
+            }
+            Ok(range)
+        })
+        .collect()
+}
+
+/// 一次运行中的成功判定状态
+#[derive(Debug)]
+pub struct SuccessChecker {
+    exit_codes: Vec<RangeInclusive<i32>>,
+    stderr_fail: Option<Regex>,
+    stdout_require: Option<Regex>,
+    stderr_matched: Option<String>,
+    stdout_matched: bool,
+}
+
+impl SuccessChecker {
+    pub fn new(rule: &SuccessRule) -> Result<Self, String> {
+        let compile = |pattern: &Option<String>, name: &str| {
+            pattern
+                .as_deref()
+                .filter(|pattern| !pattern.is_empty())
+                .map(|pattern| {
+                    Regex::new(pattern).map_err(|e| format!("无效的{}正则表达式: {}", name, e))
+                })
+                .transpose()
+        };
+        Ok(Self {
+            exit_codes: parse_exit_codes(&rule.exit_codes)?,
+            stderr_fail: compile(&rule.stderr_fail_pattern, "标准错误失败")?,
+            stdout_require: compile(&rule.stdout_require_pattern, "标准输出必需")?,
+            stderr_matched: None,
+            stdout_matched: false,
+        })
+    }
+
+    /// 检查一行输出
+    pub fn observe(&mut self, stream: LogStream, line: &str) {
+        match stream {
+            LogStream::Stdout if !self.stdout_matched => {
+                self.stdout_matched = self
+                    .stdout_require
+                    .as_ref()
+                    .is_some_and(|regex| regex.is_match(line));
+            }
+            LogStream::Stderr if self.stderr_matched.is_none() => {
+                let matched = self
+                    .stderr_fail
+                    .as_ref()
+                    .is_some_and(|regex| regex.is_match(line));
+                if matched {
+                    self.stderr_matched = Some(line.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 根据退出码和输出判定结果，成功时返回 None
+    pub fn evaluate(&self, exit_code: Option<i32>) -> Option<FailureReason> {
+        let code_allowed =
+            exit_code.is_some_and(|code| self.exit_codes.iter().any(|range| range.contains(&code)));
+        if !code_allowed {
+            return Some(FailureReason::ExitCode { code: exit_code });
+        }
+        if let Some(line) = &self.stderr_matched {
+            return Some(FailureReason::StderrMatched { line: line.clone() });
+        }
+        if self.stdout_require.is_some() && !self.stdout_matched {
+            return Some(FailureReason::StdoutMissing);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checker(exit_codes: &str) -> SuccessChecker {
+        SuccessChecker::new(&SuccessRule {
+            exit_codes: exit_codes.to_string(),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(checker("").evaluate(Some(0)), None);
+        assert_eq!(
+            checker("").evaluate(Some(1)),
+            Some(FailureReason::ExitCode { code: Some(1) })
+        );
+        assert_eq!(
+            checker("").evaluate(None),
+            Some(FailureReason::ExitCode { code: None })
+        );
+
+        // robocopy：0-7 视为成功
+        let robocopy = checker("0-7");
+        assert_eq!(robocopy.evaluate(Some(7)), None);
+        assert!(robocopy.evaluate(Some(8)).is_some());
+
+        let list = checker(" 0, 1 ,-1");
+        assert_eq!(list.evaluate(Some(1)), None);
+        assert_eq!(list.evaluate(Some(-1)), None);
+        assert!(list.evaluate(Some(2)).is_some());
+        assert_eq!(checker("-2-0").evaluate(Some(-1)), None);
+
+        for invalid in ["a", "7-0", "1,,2", "1-"] {
+            let rule = SuccessRule {
+                exit_codes: invalid.to_string(),
+                ..Default::default()
+            };
+            assert!(rule.validate().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_output_rules() {
+        let rule = SuccessRule {
+            exit_codes: String::new(),
+            stderr_fail_pattern: Some("(?i)error".to_string()),
+            stdout_require_pattern: Some("^done$".to_string()),
+        };
+
+        let mut checker = SuccessChecker::new(&rule).unwrap();
+        checker.observe(LogStream::Stdout, "working");
+        assert_eq!(
+            checker.evaluate(Some(0)),
+            Some(FailureReason::StdoutMissing)
+        );
+        checker.observe(LogStream::Stdout, "done");
+        assert_eq!(checker.evaluate(Some(0)), None);
+
+        // 标准输出中的 error 不影响结果
+        checker.observe(LogStream::Stdout, "0 errors");
+        assert_eq!(checker.evaluate(Some(0)), None);
+        checker.observe(LogStream::Stderr, "ERROR: disk full");
+        assert_eq!(
+            checker.evaluate(Some(0)),
+            Some(FailureReason::StderrMatched {
+                line: "ERROR: disk full".to_string()
+            })
+        );
+
+        let invalid = SuccessRule {
+            stderr_fail_pattern: Some("(".to_string()),
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+}
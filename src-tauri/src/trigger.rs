@@ -0,0 +1,222 @@
+// ==================== 输出触发器 ====================
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// 触发器匹配后执行的动作
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum TriggerAction {
+    /// 发送桌面通知（内容为空时使用匹配的日志行）
+    Notify {
+        #[serde(default)]
+        message: Option<String>,
+    },
+    /// 将本次运行标记为失败（即使退出码为 0）
+    MarkFailed,
+    /// 停止命令
+    Stop,
+    /// 执行另一个已保存的命令
+    RunCommand { command_id: i64 },
+    /// 在浏览器中打开命令的 URL
+    OpenUrl,
+}
+
+/// 输出触发器
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct OutputTrigger {
+    /// 匹配日志行的正则表达式
+    pub pattern: String,
+    pub actions: Vec<TriggerAction>,
+    /// 冷却时间（秒），期间再次匹配不会触发
+    pub cooldown_secs: u64,
+}
+
+impl Default for OutputTrigger {
+    fn default() -> Self {
+        Self {
+            pattern: String::new(),
+            actions: Vec::new(),
+            cooldown_secs: 10,
+        }
+    }
+}
+
+/// 校验触发器列表
+pub fn validate_triggers(
+    triggers: &[OutputTrigger],
+    command_id: Option<i64>,
+) -> Result<(), String> {
+    OutputTriggerSet::compile(triggers).map(|_| ())?;
+    for (index, trigger) in triggers.iter().enumerate() {
+        if trigger.actions.is_empty() {
+            return Err(format!("触发器 {} 至少需要一个动作", index + 1));
+        }
+        let runs_itself = trigger.actions.iter().any(|action| {
+            matches!(action, TriggerAction::RunCommand { command_id: id } if Some(*id) == command_id)
+        });
+        if runs_itself {
+            return Err(format!("触发器 {} 不能执行命令自身", index + 1));
+        }
+    }
+    Ok(())
+}
+
+/// 触发器中执行的其他命令的 ID
+pub fn run_command_targets(triggers: &[OutputTrigger]) -> impl Iterator<Item = i64> + '_ {
+    triggers
+        .iter()
+        .flat_map(|trigger| trigger.actions.iter())
+        .filter_map(|action| match action {
+            TriggerAction::RunCommand { command_id } => Some(*command_id),
+            _ => None,
+        })
+}
+
+/// 按 `ids`（原 ID -> 新 ID）替换触发器中执行的命令 ID，返回没有对应新 ID 的原 ID
+pub fn remap_run_command_targets(
+    triggers: &mut [OutputTrigger],
+    ids: &HashMap<i64, i64>,
+) -> Vec<i64> {
+    let mut missing = Vec::new();
+    for action in triggers.iter_mut().flat_map(|trigger| trigger.actions.iter_mut()) {
+        if let TriggerAction::RunCommand { command_id } = action {
+            match ids.get(command_id) {
+                Some(id) => *command_id = *id,
+                None => missing.push(*command_id),
+            }
+        }
+    }
+    missing
+}
+
+/// 一次运行中编译后的触发器及其冷却状态
+#[derive(Debug)]
+pub struct OutputTriggerSet {
+    triggers: Vec<(Regex, OutputTrigger)>,
+    last_fired: Vec<Option<Instant>>,
+}
+
+impl OutputTriggerSet {
+    /// 编译触发器，没有触发器时返回 None
+    pub fn compile(triggers: &[OutputTrigger]) -> Result<Option<Self>, String> {
+        if triggers.is_empty() {
+            return Ok(None);
+        }
+        let compiled = triggers
+            .iter()
+            .enumerate()
+            .map(|(index, trigger)| {
+                if trigger.pattern.is_empty() {
+                    return Err(format!("触发器 {} 的正则表达式不能为空", index + 1));
+                }
+                let regex = Regex::new(&trigger.pattern)
+                    .map_err(|e| format!("触发器 {} 的正则表达式无效: {}", index + 1, e))?;
+                Ok((regex, trigger.clone()))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Some(Self {
+            last_fired: vec![None; compiled.len()],
+            triggers: compiled,
+        }))
+    }
+
+    /// 检查一行日志，返回本次触发的触发器序号（处于冷却期的不触发）
+    pub fn fire(&mut self, line: &str, now: Instant) -> Vec<usize> {
+        let mut fired = Vec::new();
+        for (index, (regex, trigger)) in self.triggers.iter().enumerate() {
+            let cooling = self.last_fired[index].is_some_and(|last| {
+                now.duration_since(last) < Duration::from_secs(trigger.cooldown_secs)
+            });
+            if !cooling && regex.is_match(line) {
+                self.last_fired[index] = Some(now);
+                fired.push(index);
+            }
+        }
+        fired
+    }
+
+    pub fn get(&self, index: usize) -> &OutputTrigger {
+        &self.triggers[index].1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trigger(pattern: &str, cooldown_secs: u64) -> OutputTrigger {
+        OutputTrigger {
+            pattern: pattern.to_string(),
+            actions: vec![TriggerAction::Notify { message: None }],
+            cooldown_secs,
+        }
+    }
+
+    #[test]
+    fn test_remap_run_command_targets() {
+        let mut triggers = vec![OutputTrigger {
+            pattern: "ready".to_string(),
+            actions: vec![
+                TriggerAction::RunCommand { command_id: 3 },
+                TriggerAction::OpenUrl,
+                TriggerAction::RunCommand { command_id: 9 },
+            ],
+            cooldown_secs: 0,
+        }];
+        assert_eq!(run_command_targets(&triggers).collect::<Vec<_>>(), vec![3, 9]);
+
+        let ids = HashMap::from([(3, 12)]);
+        assert_eq!(remap_run_command_targets(&mut triggers, &ids), vec![9]);
+        assert_eq!(run_command_targets(&triggers).collect::<Vec<_>>(), vec![12, 9]);
+    }
+
+    #[test]
+    fn test_fire_with_cooldown() {
+        let triggers = [
+            trigger("ERROR", 10),
+            trigger("(?i)compiled successfully", 0),
+        ];
+        let mut set = OutputTriggerSet::compile(&triggers).unwrap().unwrap();
+        let now = Instant::now();
+
+        assert_eq!(set.fire("ERROR: EADDRINUSE", now), vec![0]);
+        assert!(set
+            .fire("ERROR again", now + Duration::from_secs(5))
+            .is_empty());
+        assert_eq!(
+            set.fire("ERROR again", now + Duration::from_secs(10)),
+            vec![0]
+        );
+
+        // 冷却时间为 0 时每次匹配都触发
+        assert_eq!(set.fire("Compiled successfully", now), vec![1]);
+        assert_eq!(set.fire("compiled successfully", now), vec![1]);
+        assert!(set.fire("info", now).is_empty());
+    }
+
+    #[test]
+    fn test_validate_triggers() {
+        assert!(OutputTriggerSet::compile(&[]).unwrap().is_none());
+        assert!(validate_triggers(&[trigger("ok", 0)], Some(1)).is_ok());
+        assert!(validate_triggers(&[trigger("(", 0)], Some(1)).is_err());
+        assert!(validate_triggers(&[trigger("", 0)], Some(1)).is_err());
+
+        let no_action = OutputTrigger {
+            actions: Vec::new(),
+            ..trigger("ok", 0)
+        };
+        assert!(validate_triggers(&[no_action], Some(1)).is_err());
+
+        let runs_itself = OutputTrigger {
+            actions: vec![TriggerAction::RunCommand { command_id: 1 }],
+            ..trigger("ok", 0)
+        };
+        let triggers = [runs_itself];
+        assert!(validate_triggers(&triggers, Some(1)).is_err());
+        assert!(validate_triggers(&triggers, Some(2)).is_ok());
+    }
+}
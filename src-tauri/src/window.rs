@@ -1,5 +1,5 @@
 use crate::db::Database;
-use crate::i18n::{get_language_from_db, Translations};
+use crate::i18n::{get_language_from_db, Language, Translations};
 use image::GenericImageView;
 use tauri::menu::{Menu, MenuItem};
 use tauri::tray::TrayIconBuilder;
@@ -165,6 +165,11 @@ fn get_command_log_path(command_id: i64, command_name: &str) -> String {
     format!("{}?name={}", base_path, encoded_name)
 }
 
+/// 生成历史运行日志路由路径（在命令日志路由上附加 runId 参数）
+fn get_run_log_path(run_id: i64, command_id: i64, command_name: &str) -> String {
+    format!("{}&runId={}", get_command_log_path(command_id, command_name), run_id)
+}
+
 // ==================== 日志窗口管理 ====================
 
 /// 创建或显示日志窗口
@@ -173,14 +178,47 @@ pub fn create_log_window(
     command_id: i64,
     command_name: &str,
 ) -> Result<(), String> {
-    // 获取数据库以读取语言设置
     let database = app.state::<Database>();
     let language = get_language_from_db(database.inner());
-    
-    let window_label = format!("log-{}", command_id);
 
+    show_or_create_log_window(
+        app,
+        &format!("log-{}", command_id),
+        &get_command_log_path(command_id, command_name),
+        Translations::log_window_title(language, command_name),
+        language,
+    )
+}
+
+/// 创建或显示历史运行日志窗口
+pub fn create_run_log_window(
+    app: &AppHandle,
+    run_id: i64,
+    command_id: i64,
+    command_name: &str,
+) -> Result<(), String> {
+    let database = app.state::<Database>();
+    let language = get_language_from_db(database.inner());
+
+    show_or_create_log_window(
+        app,
+        &format!("log-run-{}", run_id),
+        &get_run_log_path(run_id, command_id, command_name),
+        Translations::run_log_window_title(language, command_name, run_id),
+        language,
+    )
+}
+
+/// 显示已存在的日志窗口，不存在时创建
+fn show_or_create_log_window(
+    app: &AppHandle,
+    window_label: &str,
+    url_path: &str,
+    title: String,
+    language: Language,
+) -> Result<(), String> {
     // 检查窗口是否已存在
-    if let Some(window) = app.get_webview_window(&window_label) {
+    if let Some(window) = app.get_webview_window(window_label) {
         // 窗口已存在，显示并聚焦
         window.show().map_err(|e| Translations::error_show_window(language, &e.to_string()))?;
         window.set_focus().map_err(|e| Translations::error_focus_window(language, &e.to_string()))?;
@@ -188,12 +226,9 @@ pub fn create_log_window(
     }
 
     // 创建新窗口 - 使用路由
-    let url_path = get_command_log_path(command_id, command_name);
-    let title = Translations::log_window_title(language, command_name);
-
     WebviewWindowBuilder::new(
         app,
-        &window_label,
+        window_label,
         WebviewUrl::App(url_path.parse().unwrap())
     )
     .title(title)
@@ -205,4 +240,3 @@ pub fn create_log_window(
 
     Ok(())
 }
-
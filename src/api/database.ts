@@ -6,6 +6,16 @@ import type {
   ImportResult,
   CommandState,
   CommandStatus,
+  LogPage,
+  RunLogPage,
+  CommandRunFilter,
+  CommandRunPage,
+  ScheduleStatus,
+  Pipeline,
+  PipelineStep,
+  PipelineRunState,
+  QueuedRun,
+  CommandMetrics,
 } from "../types";
 import { CONFIG_KEYS as TYPES_CONFIG_KEYS } from "../types/config";
 
@@ -17,6 +27,7 @@ export type {
   ImportResult,
   CommandState,
   CommandStatus,
+  LogPage,
 };
 
 // ==================== 命令管理 API ====================
@@ -133,19 +144,38 @@ export const configApi = {
  */
 export const commandExecutionApi = {
   /**
-   * 执行命令
+   * 执行命令，返回本次运行的运行 ID
    */
-  execute: async (commandId: number): Promise<void> => {
-    return await invoke<void>("execute_command", { commandId });
+  execute: async (commandId: number): Promise<number> => {
+    return await invoke<number>("execute_command", { commandId });
   },
 
   /**
-   * 停止命令
+   * 停止命令（所有运行实例）
    */
   stop: async (commandId: number): Promise<void> => {
     return await invoke<void>("stop_command", { commandId });
   },
 
+  /**
+   * 停止命令的单个运行实例（排队中的运行直接取消）
+   */
+  stopInstance: async (runId: number): Promise<void> => {
+    return await invoke<void>("stop_command_instance", { runId });
+  },
+
+  /**
+   * 向运行中的命令发送输入（默认追加换行，未指定 runId 时发送给最近启动的实例）
+   */
+  sendInput: async (
+    commandId: number,
+    text: string,
+    appendNewline?: boolean,
+    runId?: number
+  ): Promise<void> => {
+    return await invoke<void>("send_command_input", { commandId, text, appendNewline, runId });
+  },
+
   /**
    * 获取命令状态
    */
@@ -161,10 +191,137 @@ export const commandExecutionApi = {
   },
 
   /**
-   * 获取命令执行日志
+   * 获取命令的所有运行实例（按启动顺序）
+   */
+  getInstances: async (commandId: number): Promise<CommandState[]> => {
+    return await invoke<CommandState[]>("get_command_instances", { commandId });
+  },
+
+  /**
+   * 获取运行中命令的资源占用（每秒更新）
+   */
+  getMetrics: async (): Promise<CommandMetrics[]> => {
+    return await invoke<CommandMetrics[]>("get_command_metrics");
+  },
+
+  /**
+   * 获取运行队列（按排队顺序）
+   */
+  getQueue: async (): Promise<QueuedRun[]> => {
+    return await invoke<QueuedRun[]>("get_run_queue");
+  },
+
+  /**
+   * 取消排队中的运行
+   */
+  cancelQueuedRun: async (queueId: number): Promise<void> => {
+    return await invoke<void>("cancel_queued_run", { queueId });
+  },
+
+  /**
+   * 调整排队中的运行的位置（从 1 开始）
+   */
+  moveQueuedRun: async (queueId: number, position: number): Promise<void> => {
+    return await invoke<void>("move_queued_run", { queueId, position });
+  },
+
+  /**
+   * 获取所有定时执行命令的状态（包括下一次执行时间）
+   */
+  getSchedules: async (): Promise<ScheduleStatus[]> => {
+    return await invoke<ScheduleStatus[]>("get_command_schedules");
+  },
+
+  /**
+   * 获取所有流水线
+   */
+  getPipelines: async (): Promise<Pipeline[]> => {
+    return await invoke<Pipeline[]>("get_all_pipelines");
+  },
+
+  /**
+   * 创建流水线
+   */
+  createPipeline: async (name: string, steps: PipelineStep[]): Promise<Pipeline> => {
+    return await invoke<Pipeline>("create_pipeline", { name, steps });
+  },
+
+  /**
+   * 更新流水线
+   */
+  updatePipeline: async (id: number, name?: string, steps?: PipelineStep[]): Promise<void> => {
+    return await invoke<void>("update_pipeline", { id, name, steps });
+  },
+
+  /**
+   * 删除流水线
+   */
+  deletePipeline: async (id: number): Promise<void> => {
+    return await invoke<void>("delete_pipeline", { id });
+  },
+
+  /**
+   * 运行流水线
+   */
+  runPipeline: async (pipelineId: number): Promise<void> => {
+    return await invoke<void>("run_pipeline", { pipelineId });
+  },
+
+  /**
+   * 停止流水线（停止所有步骤）
+   */
+  stopPipeline: async (pipelineId: number): Promise<void> => {
+    return await invoke<void>("stop_pipeline", { pipelineId });
+  },
+
+  /**
+   * 获取所有流水线最近一次的运行状态
+   */
+  getPipelineStates: async (): Promise<PipelineRunState[]> => {
+    return await invoke<PipelineRunState[]>("get_pipeline_states");
+  },
+
+  /**
+   * 预览执行计划表达式接下来的若干次执行时间
+   */
+  previewSchedule: async (expression: string, count?: number): Promise<string[]> => {
+    return await invoke<string[]>("preview_schedule", { expression, count });
+  },
+
+  /**
+   * 分页获取命令执行日志（cursor 为上次返回的 next_cursor，不传时返回最新的日志）
+   */
+  getLogs: async (
+    commandId: number,
+    cursor?: number,
+    limit?: number
+  ): Promise<LogPage> => {
+    return await invoke<LogPage>("get_command_logs", { commandId, cursor, limit });
+  },
+
+  /**
+   * 分页获取单个运行实例的日志
+   */
+  getInstanceLogs: async (runId: number, cursor?: number, limit?: number): Promise<LogPage> => {
+    return await invoke<LogPage>("get_instance_logs", { runId, cursor, limit });
+  },
+
+  /**
+   * 分页读取某次运行的日志文件（offset 为起始行号）
+   */
+  getRunLogs: async (runId: number, offset?: number, limit?: number): Promise<RunLogPage> => {
+    return await invoke<RunLogPage>("get_run_logs", { runId, offset, limit });
+  },
+
+  /**
+   * 分页查询运行历史（按开始时间倒序）
    */
-  getLogs: async (commandId: number): Promise<string[]> => {
-    return await invoke<string[]>("get_command_logs", { commandId });
+  getRuns: async (
+    filter?: CommandRunFilter,
+    offset?: number,
+    limit?: number
+  ): Promise<CommandRunPage> => {
+    return await invoke<CommandRunPage>("get_command_runs", { filter, offset, limit });
   },
 
   /**
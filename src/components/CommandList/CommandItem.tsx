@@ -6,6 +6,8 @@ import {
   CopyOutlined,
   DeleteOutlined,
   FileTextOutlined,
+  HistoryOutlined,
+  SyncOutlined,
 } from "@ant-design/icons";
 import { Dropdown, message, Tooltip } from "antd";
 import type { MenuProps } from "antd";
@@ -24,9 +26,18 @@ interface CommandItemProps {
   onCopy?: (id: string) => void;
   onDelete?: (id: string) => void;
   onViewLogs?: (id: string) => void;
+  onViewHistory?: (id: string) => void;
 }
 
-function CommandItem({ command, onPlay, onEdit, onCopy, onDelete, onViewLogs }: CommandItemProps) {
+function CommandItem({
+  command,
+  onPlay,
+  onEdit,
+  onCopy,
+  onDelete,
+  onViewLogs,
+  onViewHistory,
+}: CommandItemProps) {
   const { t } = useTranslation();
 
   const { attributes, listeners, setNodeRef, transform, transition, isDragging } = useSortable({
@@ -56,6 +67,10 @@ function CommandItem({ command, onPlay, onEdit, onCopy, onDelete, onViewLogs }:
     onDelete?.(command.id);
   };
 
+  const handleViewHistory = () => {
+    onViewHistory?.(command.id);
+  };
+
   const handleOpenUrl = async (e: React.MouseEvent) => {
     e.stopPropagation();
     if (command.url) {
@@ -80,6 +95,12 @@ function CommandItem({ command, onPlay, onEdit, onCopy, onDelete, onViewLogs }:
       icon: <CopyOutlined />,
       onClick: handleCopy,
     },
+    {
+      key: "history",
+      label: t("components.commandItem.runHistory"),
+      icon: <HistoryOutlined />,
+      onClick: handleViewHistory,
+    },
     {
       key: "delete",
       label: t("components.commandItem.delete"),
@@ -114,6 +135,22 @@ function CommandItem({ command, onPlay, onEdit, onCopy, onDelete, onViewLogs }:
             <FileTextOutlined />
           </StatusIcon>
         )}
+        {(command.nextRetryAt || !!command.restartCount) && (
+          <Tooltip
+            title={
+              command.nextRetryAt
+                ? t("components.commandItem.restartPending", {
+                    time: new Date(command.nextRetryAt).toLocaleTimeString(),
+                    count: command.restartCount,
+                  })
+                : t("components.commandItem.restartCount", { count: command.restartCount })
+            }
+          >
+            <StatusIcon>
+              <SyncOutlined spin={!!command.nextRetryAt} />
+            </StatusIcon>
+          </Tooltip>
+        )}
         {command.url && (
           <StatusIcon onClick={handleOpenUrl} title={t("components.commandItem.openUrl")}>
             <LinkOutlined />
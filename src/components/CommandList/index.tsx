@@ -27,10 +27,11 @@ export interface CommandListProps {
   onCopyCommand?: (id: string) => void;
   onDeleteCommand?: (id: string) => void;
   onViewLogs?: (id: string) => void;
+  onViewHistory?: (id: string) => void;
   onReorder?: (commands: CommandItem[]) => void;
 }
 
-function CommandList({ commands, onPlayCommand, onEditCommand, onCopyCommand, onDeleteCommand, onViewLogs, onReorder }: CommandListProps) {
+function CommandList({ commands, onPlayCommand, onEditCommand, onCopyCommand, onDeleteCommand, onViewLogs, onViewHistory, onReorder }: CommandListProps) {
   const { t } = useTranslation();
   const sensors = useSensors(
     useSensor(PointerSensor, {
@@ -88,6 +89,7 @@ function CommandList({ commands, onPlayCommand, onEditCommand, onCopyCommand, on
               onCopy={onCopyCommand}
               onDelete={onDeleteCommand}
               onViewLogs={onViewLogs}
+              onViewHistory={onViewHistory}
             />
           ))}
         </ListContainer>
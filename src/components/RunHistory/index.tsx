@@ -0,0 +1,156 @@
+import { useState, useEffect } from "react";
+import { Button, Modal, Table, Tag, message } from "antd";
+import type { TableColumnsType } from "antd";
+import { FileTextOutlined } from "@ant-design/icons";
+import { useTranslation } from "react-i18next";
+import { invoke } from "@tauri-apps/api/core";
+import { commandExecutionApi } from "../../api/database";
+import type { CommandRun, CommandStatus } from "../../types";
+
+const PAGE_SIZE = 20;
+
+// 运行状态对应的标签颜色
+const STATUS_COLORS: Partial<Record<CommandStatus, string>> = {
+  success: "success",
+  failed: "error",
+  timedout: "warning",
+  stopped: "default",
+};
+
+interface RunHistoryProps {
+  open: boolean;
+  commandId?: number;
+  commandName?: string;
+  onClose: () => void;
+}
+
+function RunHistory({ open, commandId, commandName, onClose }: RunHistoryProps) {
+  const { t } = useTranslation();
+  const [runs, setRuns] = useState<CommandRun[]>([]);
+  const [total, setTotal] = useState(0);
+  const [page, setPage] = useState(1);
+  const [loading, setLoading] = useState(false);
+
+  // 切换命令时回到第一页
+  useEffect(() => {
+    setPage(1);
+  }, [commandId]);
+
+  // 加载运行历史
+  useEffect(() => {
+    if (!open || commandId === undefined) {
+      return;
+    }
+
+    const loadRuns = async () => {
+      setLoading(true);
+      try {
+        const result = await commandExecutionApi.getRuns(
+          { command_id: commandId },
+          (page - 1) * PAGE_SIZE,
+          PAGE_SIZE
+        );
+        setRuns(result.runs);
+        setTotal(result.total);
+      } catch (error) {
+        console.error("加载运行历史失败:", error);
+        message.error(t("components.runHistory.loadFailed"));
+      } finally {
+        setLoading(false);
+      }
+    };
+
+    loadRuns();
+  }, [open, commandId, page]);
+
+  // 打开某次运行的日志窗口
+  const handleViewLogs = async (run: CommandRun) => {
+    try {
+      await invoke("open_run_log_window", {
+        runId: run.id,
+        commandId: run.command_id,
+        commandName: run.command_name,
+      });
+    } catch (error) {
+      console.error("打开日志窗口失败:", error);
+      message.error(t("components.runHistory.openLogsFailed"));
+    }
+  };
+
+  // 格式化运行时长
+  const formatDuration = (durationMs?: number) => {
+    if (durationMs == null) {
+      return "-";
+    }
+    const seconds = durationMs / 1000;
+    if (seconds < 60) {
+      return `${seconds.toFixed(1)}s`;
+    }
+    return `${Math.floor(seconds / 60)}m ${Math.floor(seconds % 60)}s`;
+  };
+
+  const columns: TableColumnsType<CommandRun> = [
+    {
+      title: t("components.runHistory.startedAt"),
+      dataIndex: "started_at",
+      render: (startedAt: string) => new Date(startedAt).toLocaleString(),
+    },
+    {
+      title: t("components.runHistory.status"),
+      dataIndex: "status",
+      render: (status: CommandStatus, run) => (
+        <Tag color={STATUS_COLORS[status] ?? "processing"}>
+          {status}
+          {typeof run.exit_code === "number" ? ` (${run.exit_code})` : ""}
+        </Tag>
+      ),
+    },
+    {
+      title: t("components.runHistory.trigger"),
+      dataIndex: "trigger",
+    },
+    {
+      title: t("components.runHistory.duration"),
+      dataIndex: "duration_ms",
+      render: (durationMs?: number) => formatDuration(durationMs),
+    },
+    {
+      key: "actions",
+      render: (_, run) => (
+        <Button
+          type="text"
+          icon={<FileTextOutlined />}
+          onClick={() => handleViewLogs(run)}
+          title={t("components.runHistory.viewLogs")}
+        />
+      ),
+    },
+  ];
+
+  return (
+    <Modal
+      open={open}
+      title={t("components.runHistory.title", { name: commandName })}
+      footer={null}
+      width={720}
+      onCancel={onClose}
+    >
+      <Table
+        rowKey="id"
+        size="small"
+        columns={columns}
+        dataSource={runs}
+        loading={loading}
+        pagination={{
+          current: page,
+          pageSize: PAGE_SIZE,
+          total,
+          showSizeChanger: false,
+          onChange: setPage,
+        }}
+      />
+    </Modal>
+  );
+}
+
+export default RunHistory;
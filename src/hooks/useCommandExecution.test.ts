@@ -56,7 +56,7 @@ describe("useCommandExecution", () => {
 
   it("应该执行命令", async () => {
     vi.mocked(commandExecutionApi.getAllStates).mockResolvedValue({});
-    vi.mocked(commandExecutionApi.execute).mockResolvedValue(undefined);
+    vi.mocked(commandExecutionApi.execute).mockResolvedValue(1);
 
     const { result } = renderHook(() => useCommandExecution());
 
@@ -131,6 +131,13 @@ describe("useCommandExecution", () => {
         created_at: "2024-01-01T00:00:00Z",
         updated_at: "2024-01-01T00:00:00Z",
       },
+      3: {
+        command_id: 3,
+        status: "stopping",
+        pid: 12346,
+        created_at: "2024-01-01T00:00:00Z",
+        updated_at: "2024-01-01T00:00:00Z",
+      },
     };
 
     vi.mocked(commandExecutionApi.getAllStates).mockResolvedValue(mockStates);
@@ -140,6 +147,7 @@ describe("useCommandExecution", () => {
     await waitFor(() => {
       expect(result.current.isRunning(1)).toBe(true);
       expect(result.current.isRunning(2)).toBe(false);
+      expect(result.current.isRunning(3)).toBe(true);
     });
   });
 
@@ -152,6 +160,12 @@ describe("useCommandExecution", () => {
         created_at: "2024-01-01T00:00:00Z",
         updated_at: "2024-01-01T00:00:00Z",
       },
+      3: {
+        command_id: 3,
+        status: "timedout",
+        created_at: "2024-01-01T00:00:00Z",
+        updated_at: "2024-01-01T00:00:00Z",
+      },
     };
 
     vi.mocked(commandExecutionApi.getAllStates).mockResolvedValue(mockStates);
@@ -161,6 +175,7 @@ describe("useCommandExecution", () => {
     await waitFor(() => {
       expect(result.current.hasLogs(1)).toBe(true);
       expect(result.current.hasLogs(2)).toBe(false);
+      expect(result.current.hasLogs(3)).toBe(true);
     });
   });
 });
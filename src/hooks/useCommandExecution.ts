@@ -79,7 +79,10 @@ export function useCommandExecution() {
    * 检查命令是否运行中
    */
   const isRunning = useCallback((commandId: number): boolean => {
-    return getCommandStatus(commandId) === "running";
+    const status = getCommandStatus(commandId);
+    return (
+      status === "starting" || status === "running" || status === "ready" || status === "stopping"
+    );
   }, [getCommandStatus]);
 
   /**
@@ -87,8 +90,10 @@ export function useCommandExecution() {
    */
   const hasLogs = useCallback((commandId: number): boolean => {
     const status = getCommandStatus(commandId);
-    return status === "running" || status === "success" || status === "failed";
-  }, [getCommandStatus]);
+    return (
+      isRunning(commandId) || status === "success" || status === "failed" || status === "timedout"
+    );
+  }, [getCommandStatus, isRunning]);
 
   // 监听命令状态变化事件
   useEffect(() => {
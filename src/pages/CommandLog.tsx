@@ -1,4 +1,4 @@
-import { useState, useEffect, useRef } from "react";
+import { useState, useEffect, useRef, type CSSProperties } from "react";
 import { useParams, useSearchParams } from "react-router";
 import styled from "styled-components";
 import { Button, message, Empty } from "antd";
@@ -11,6 +11,7 @@ import { useTranslation } from "react-i18next";
 import { listen } from "@tauri-apps/api/event";
 import { invoke } from "@tauri-apps/api/core";
 import { commandExecutionApi } from "../api/database";
+import type { LogLine as LogLineData, StyledSegment } from "../types";
 
 // ==================== 样式组件 ====================
 
@@ -50,6 +51,11 @@ const LogContent = styled.div`
   -ms-user-select: text;
 `;
 
+const LogTime = styled.span`
+  margin-right: 8px;
+  color: #808080;
+`;
+
 const LogLine = styled.div<{ $isError?: boolean }>`
   margin-bottom: 4px;
   color: ${(props) => (props.$isError ? "#f48771" : "#d4d4d4")};
@@ -82,13 +88,37 @@ const StyledEmpty = styled(Empty)`
   }
 `;
 
-// ==================== 日志行数据接口 ====================
-
-interface LogLine {
-  command_id: number;
-  line: string;
-  stream: string;
-}
+// ==================== ANSI 颜色 ====================
+
+// 标准 16 色（与 VS Code 深色终端主题一致）
+const ANSI_COLORS: Record<string, string> = {
+  black: "#000000",
+  red: "#cd3131",
+  green: "#0dbc79",
+  yellow: "#e5e510",
+  blue: "#2472c8",
+  magenta: "#bc3fbc",
+  cyan: "#11a8cd",
+  white: "#e5e5e5",
+  "bright-black": "#666666",
+  "bright-red": "#f14c4c",
+  "bright-green": "#23d18b",
+  "bright-yellow": "#f5f543",
+  "bright-blue": "#3b8eea",
+  "bright-magenta": "#d670d6",
+  "bright-cyan": "#29b8db",
+  "bright-white": "#e5e5e5",
+};
+
+const resolveColor = (color?: string) =>
+  color ? ANSI_COLORS[color] ?? color : undefined;
+
+const segmentStyle = (segment: StyledSegment): CSSProperties => ({
+  color: resolveColor(segment.fg),
+  backgroundColor: resolveColor(segment.bg),
+  fontWeight: segment.bold ? "bold" : undefined,
+  textDecoration: segment.underline ? "underline" : undefined,
+});
 
 // ==================== 主组件 ====================
 
@@ -97,8 +127,13 @@ function CommandLog() {
   const { commandId } = useParams<{ commandId: string }>();
   const [searchParams] = useSearchParams();
   const commandName = searchParams.get("name") || t("pages.commandLog.unknownCommand");
+  // 指定 runId 时显示该次运行的历史日志文件，不接收实时日志
+  const runIdParam = searchParams.get("runId");
+  const runId = runIdParam ? parseInt(runIdParam) : null;
 
-  const [logs, setLogs] = useState<string[]>([]);
+  const [logs, setLogs] = useState<LogLineData[]>([]);
+  // 已接收的最后一行日志序号，用于去重和补齐丢失的事件
+  const lastSeqRef = useRef<number | null>(null);
   const logContentRef = useRef<HTMLDivElement>(null);
   const shouldAutoScrollRef = useRef(true);
   const wasAtBottomRef = useRef(true);
@@ -132,7 +167,8 @@ function CommandLog() {
   // 监听语言变化，更新窗口标题
   useEffect(() => {
     const updateWindowTitle = async () => {
-      if (commandId && commandName) {
+      // 历史日志窗口的标题在创建时设置
+      if (commandId && commandName && runId === null) {
         try {
           await invoke("update_log_window_title", {
             commandId: parseInt(commandId),
@@ -145,7 +181,7 @@ function CommandLog() {
     };
 
     updateWindowTitle();
-  }, [commandId, commandName, i18n.language]);
+  }, [commandId, commandName, runId, i18n.language]);
 
   // 判断是否在底部（使用5px阈值，更精确）
   const isAtBottom = (element: HTMLDivElement): boolean => {
@@ -161,13 +197,41 @@ function CommandLog() {
 
   // 加载历史日志
   useEffect(() => {
+    const loadRunLogs = async (id: number) => {
+      try {
+        // 逐页读取整个运行日志文件
+        const lines: LogLineData[] = [];
+        let offset = 0;
+        for (;;) {
+          const page = await commandExecutionApi.getRunLogs(id, offset);
+          lines.push(
+            ...page.lines.map((line, index) => ({
+              ...line,
+              command_id: parseInt(commandId ?? "0"),
+              run_id: id,
+              seq: page.offset + index,
+            }))
+          );
+          offset = page.offset + page.lines.length;
+          if (page.lines.length === 0 || offset >= page.total) {
+            break;
+          }
+        }
+        setLogs(lines);
+        wasAtBottomRef.current = true;
+      } catch (error) {
+        console.error(t("pages.commandLog.loadLogsFailed"), error);
+      }
+    };
+
     const loadLogs = async () => {
-      if (commandId) {
+      if (runId !== null) {
+        await loadRunLogs(runId);
+      } else if (commandId) {
         try {
-          const historicalLogs = await commandExecutionApi.getLogs(
-            parseInt(commandId)
-          );
-          setLogs(historicalLogs);
+          const page = await commandExecutionApi.getLogs(parseInt(commandId));
+          setLogs(page.lines);
+          lastSeqRef.current = page.next_cursor - 1;
           // 初始加载后，默认滚动到底部
           wasAtBottomRef.current = true;
         } catch (error) {
@@ -177,21 +241,50 @@ function CommandLog() {
     };
 
     loadLogs();
-  }, [commandId]);
+  }, [commandId, runId]);
 
-  // 监听实时日志更新
+  // 监听实时日志更新（历史日志窗口不监听）
   useEffect(() => {
+    if (runId !== null) {
+      return;
+    }
+
     let unlistenFn: (() => void) | null = null;
     let cancelled = false;
 
-    const unlistenPromise = listen<LogLine>("command-log-update", (event) => {
+    const appendLines = (lines: LogLineData[]) => {
+      const lastSeq = lastSeqRef.current ?? -1;
+      const newLines = lines.filter((line) => line.seq > lastSeq);
+      if (newLines.length === 0) {
+        return;
+      }
+      lastSeqRef.current = newLines[newLines.length - 1].seq;
+      if (logContentRef.current) {
+        wasAtBottomRef.current = isAtBottom(logContentRef.current);
+      }
+      setLogs((prev) => [...prev, ...newLines]);
+    };
+
+    const unlistenPromise = listen<LogLineData>("command-log-update", async (event) => {
       const logLine = event.payload;
-      if (commandId && logLine.command_id === parseInt(commandId)) {
-        if (logContentRef.current) {
-          wasAtBottomRef.current = isAtBottom(logContentRef.current);
+      if (!commandId || logLine.command_id !== parseInt(commandId)) {
+        return;
+      }
+
+      const lastSeq = lastSeqRef.current;
+      if (lastSeq !== null && logLine.seq > lastSeq + 1) {
+        // 有事件丢失，从上次的位置重新拉取
+        try {
+          const page = await commandExecutionApi.getLogs(
+            parseInt(commandId),
+            lastSeq + 1
+          );
+          appendLines(page.lines);
+        } catch (error) {
+          console.error(t("pages.commandLog.loadLogsFailed"), error);
         }
-        setLogs((prev) => [...prev, logLine.line]);
       }
+      appendLines([logLine]);
     }).then((fn) => {
       unlistenFn = fn;
       if (cancelled) {
@@ -208,7 +301,7 @@ function CommandLog() {
         unlistenPromise.then((fn) => fn());
       }
     };
-  }, [commandId]);
+  }, [commandId, runId]);
 
   // 自动滚动到底部
   useEffect(() => {
@@ -309,9 +402,10 @@ function CommandLog() {
     })();
   };
 
-  // 判断是否是错误日志
-  const isErrorLog = (log: string) => {
-    return log.startsWith("[stderr]");
+  // 格式化日志时间（只显示时分秒和毫秒）
+  const formatTime = (timestamp: string) => {
+    const match = timestamp.match(/T(\d{2}:\d{2}:\d{2}\.\d{3})/);
+    return match ? match[1] : timestamp;
   };
 
   return (
@@ -332,13 +426,15 @@ function CommandLog() {
             title={t("pages.commandLog.scrollToBottom")}
             style={{ color: "#d4d4d4" }}
           />
-          <Button
-            type="text"
-            icon={<DeleteOutlined />}
-            onClick={handleClearLogs}
-            title={t("pages.commandLog.clearLogs")}
-            style={{ color: "#d4d4d4" }}
-          />
+          {runId === null && (
+            <Button
+              type="text"
+              icon={<DeleteOutlined />}
+              onClick={handleClearLogs}
+              title={t("pages.commandLog.clearLogs")}
+              style={{ color: "#d4d4d4" }}
+            />
+          )}
         </ButtonGroup>
       </LogHeader>
       <LogContent ref={logContentRef} onScroll={handleScroll}>
@@ -350,9 +446,16 @@ function CommandLog() {
             />
           </EmptyContainer>
         ) : (
-          logs.map((log, index) => (
-            <LogLine key={index} $isError={isErrorLog(log)}>
-              {log}
+          logs.map((log) => (
+            <LogLine key={log.seq} $isError={log.stream === "stderr"}>
+              <LogTime>{formatTime(log.timestamp)}</LogTime>
+              {log.segments
+                ? log.segments.map((segment, index) => (
+                    <span key={index} style={segmentStyle(segment)}>
+                      {segment.text}
+                    </span>
+                  ))
+                : log.text}
             </LogLine>
           ))
         )}
@@ -9,7 +9,8 @@ import { invoke } from "@tauri-apps/api/core";
 import StatusBar from "../components/StatusBar";
 import ToolBar from "../components/ToolBar";
 import CommandList from "../components/CommandList";
-import type { CommandItem } from "../types";
+import RunHistory from "../components/RunHistory";
+import type { CommandItem, CommandRestartingEvent } from "../types";
 import { AppRoutes } from "../constants/routes";
 import { commandApi, commandExecutionApi, CommandState } from "../api/database";
 
@@ -39,6 +40,8 @@ function Home() {
   const { t } = useTranslation();
   const navigate = useNavigate();
   const [commands, setCommands] = useState<CommandItem[]>([]);
+  // 正在查看运行历史的命令
+  const [historyCommand, setHistoryCommand] = useState<CommandItem | null>(null);
   const pendingStartRef = useRef<Map<number, { timerId: number }>>(new Map());
 
   const clearPendingStart = (commandId: number) => {
@@ -57,9 +60,9 @@ function Home() {
     // 监听命令状态变化事件
     const unlisten = listen<CommandState>("command-status-changed", (event) => {
       const state = event.payload;
-      updateCommandStatus(state.command_id, state.status);
+      updateCommandStatus(state);
 
-      if (state.status === "failed") {
+      if (state.status === "failed" || state.status === "timedout") {
         clearPendingStart(state.command_id);
         const exitCodeText =
           typeof state.exit_code === "number" ? ` (退出码: ${state.exit_code})` : "";
@@ -72,8 +75,17 @@ function Home() {
       }
     });
 
+    // 监听自动重启事件
+    const unlistenRestarting = listen<CommandRestartingEvent>("command-restarting", (event) => {
+      const { restart_count, delay_secs } = event.payload;
+      message.info(
+        t("components.commandItem.restarting", { count: restart_count, seconds: delay_secs })
+      );
+    });
+
     return () => {
       unlisten.then((fn) => fn());
+      unlistenRestarting.then((fn) => fn());
     };
   }, []);
 
@@ -102,12 +114,16 @@ function Home() {
       setCommands((prev) =>
         prev.map((cmd) => {
           const state = states[parseInt(cmd.id)];
-          const isRunning = state?.status === "running";
-          const hasLogs = isRunning || (state?.status === "success" || state?.status === "failed");
+          const isRunning =
+            !!state && ["starting", "running", "ready", "stopping"].includes(state.status);
+          const hasLogs =
+            isRunning || ["success", "failed", "timedout"].includes(state?.status ?? "idle");
           return {
             ...cmd,
             isRunning,
             hasLogs,
+            restartCount: state?.restart_count,
+            nextRetryAt: state?.next_retry_at,
           };
         })
       );
@@ -117,13 +133,20 @@ function Home() {
   };
 
   // 更新单个命令的状态
-  const updateCommandStatus = (commandId: number, status: string) => {
+  const updateCommandStatus = (state: CommandState) => {
+    const { status } = state;
     setCommands((prev) =>
       prev.map((cmd) => {
-        if (parseInt(cmd.id) === commandId) {
-          const isRunning = status === "running";
-          const hasLogs = isRunning || status === "success" || status === "failed";
-          return { ...cmd, isRunning, hasLogs };
+        if (parseInt(cmd.id) === state.command_id) {
+          const isRunning = ["starting", "running", "ready", "stopping"].includes(status);
+          const hasLogs = isRunning || ["success", "failed", "timedout"].includes(status);
+          return {
+            ...cmd,
+            isRunning,
+            hasLogs,
+            restartCount: state.restart_count,
+            nextRetryAt: state.next_retry_at,
+          };
         }
         return cmd;
       })
@@ -178,7 +201,7 @@ function Home() {
         const timerId = window.setTimeout(async () => {
           try {
             const state = await commandExecutionApi.getState(commandId);
-            if (!state || state.status === "running") {
+            if (!state || ["starting", "running", "ready"].includes(state.status)) {
               message.success(t("components.commandItem.executeSuccess") || "命令已启动");
             }
           } finally {
@@ -355,6 +378,11 @@ function Home() {
     }
   };
 
+  // 处理查看运行历史
+  const handleViewHistory = (id: string) => {
+    setHistoryCommand(commands.find((cmd) => cmd.id === id) ?? null);
+  };
+
   return (
     <HomeContainer>
       <MainContent>
@@ -371,9 +399,16 @@ function Home() {
           onCopyCommand={handleCopyCommand}
           onDeleteCommand={handleDeleteCommand}
           onViewLogs={handleViewLogs}
+          onViewHistory={handleViewHistory}
           onReorder={handleReorder}
         />
       </MainContent>
+      <RunHistory
+        open={historyCommand !== null}
+        commandId={historyCommand ? parseInt(historyCommand.id) : undefined}
+        commandName={historyCommand?.name}
+        onClose={() => setHistoryCommand(null)}
+      />
       <StatusBar />
     </HomeContainer>
   );
@@ -50,7 +50,22 @@ export interface ImportResult {
 /**
  * 命令执行状态
  */
-export type CommandStatus = "idle" | "running" | "success" | "failed" | "stopped";
+export type CommandStatus =
+  | "idle"
+  | "queued"
+  | "starting"
+  | "running"
+  | "ready"
+  | "success"
+  | "failed"
+  | "stopping"
+  | "stopped"
+  | "timedout";
+
+/**
+ * 停止结果：收到停止请求后自行退出，或被强制终止
+ */
+export type StopResult = "exited" | "force_killed";
 
 /**
  * 命令状态信息
@@ -61,6 +76,255 @@ export interface CommandState {
   pid?: number;
   start_time?: string;
   exit_code?: number;
+  /** 运行 ID（无法记录运行历史时为负数的临时 ID，排队中为排队 ID） */
+  run_id: number;
+  /** 仅在命令被停止（手动或超时）后有值 */
+  stop_result?: StopResult;
+  /** 连续自动重启的次数 */
+  restart_count: number;
+  /** 下一次自动重启的时间（等待重启时有值） */
+  next_retry_at?: string;
+  /** 健康检查结果（未配置或尚未得出结果时为空） */
+  health?: HealthStatus;
+  /** 按成功判定规则判定为失败的原因 */
+  failure_reason?: FailureReason;
+  /** 排队位置（从 1 开始，仅排队中有值） */
+  queue_position?: number;
+}
+
+/**
+ * 命令将要自动重启（command-restarting 事件）
+ */
+export interface CommandRestartingEvent {
+  command_id: number;
+  restart_count: number;
+  /** 0 表示不限制 */
+  max_retries: number;
+  delay_secs: number;
+  next_retry_at: string;
+}
+
+/**
+ * 命令进程树的资源占用（command-metrics 事件，同一命令的所有运行实例合计）
+ */
+export interface CommandMetrics {
+  command_id: number;
+  /** 运行中的实例（运行 ID） */
+  run_ids: number[];
+  process_count: number;
+  /** CPU 占用百分比（占全部 CPU） */
+  cpu_usage: number;
+  /** 常驻内存（字节） */
+  memory_rss: number;
+  thread_count: number;
+  /** 打开的句柄数（Unix 为文件描述符数） */
+  handle_count: number;
+}
+
+/**
+ * 排队中的运行（run-queue-changed 事件）
+ */
+export interface QueuedRun {
+  queue_id: number;
+  command_id: number;
+  command_name: string;
+  concurrency_group?: string;
+  /** 排队位置（从 1 开始） */
+  position: number;
+  queued_at: string;
+  trigger: RunTrigger;
+}
+
+/**
+ * 判定为失败的原因
+ */
+export type FailureReason =
+  | { type: "exit-code"; code?: number }
+  | { type: "stderr-matched"; line: string }
+  | { type: "stdout-missing" }
+  | { type: "trigger" }
+  | { type: "resource-limit"; resource: "memory" | "processes" };
+
+/**
+ * 健康检查结果
+ */
+export type HealthStatus = "healthy" | "unhealthy";
+
+/**
+ * 定时执行状态
+ */
+export interface ScheduleStatus {
+  command_id: number;
+  expression: string;
+  /** 下一次执行时间（RFC 3339） */
+  next_run_at?: string;
+  /** 本次启动以来最近一次定时触发的时间 */
+  last_run_at?: string;
+  /** 是否在等待上一次运行结束 */
+  pending: boolean;
+  /** 表达式解析错误 */
+  error?: string;
+}
+
+/**
+ * 流水线步骤依赖条件
+ */
+export type StepCondition = "after-success" | "after-start" | "always";
+
+/**
+ * 流水线步骤
+ */
+export interface PipelineStep {
+  command_id: number;
+  /** 依赖的前置步骤（step 为步骤序号，只能依赖排在前面的步骤） */
+  depends_on: { step: number; condition: StepCondition }[];
+}
+
+/**
+ * 流水线
+ */
+export interface Pipeline {
+  id: number;
+  name: string;
+  steps: PipelineStep[];
+  created_at: string;
+  updated_at: string;
+}
+
+export type StepStatus = "pending" | "running" | "succeeded" | "failed" | "stopped" | "skipped";
+
+export type PipelineStatus = "running" | "succeeded" | "failed" | "stopped";
+
+/**
+ * 流水线步骤运行状态
+ */
+export interface StepState {
+  command_id: number;
+  status: StepStatus;
+  run_id?: number;
+  /** 是否已就绪 */
+  ready: boolean;
+  error?: string;
+}
+
+/**
+ * 流水线运行状态（pipeline-status 事件）
+ */
+export interface PipelineRunState {
+  pipeline_id: number;
+  pipeline_name: string;
+  status: PipelineStatus;
+  steps: StepState[];
+  started_at: string;
+  ended_at?: string;
+}
+
+/**
+ * 日志输出流
+ */
+export type LogStream = "stdout" | "stderr" | "stdin";
+
+/**
+ * ANSI 样式片段（颜色为标准色名称或 #rrggbb）
+ */
+export interface StyledSegment {
+  text: string;
+  fg?: string;
+  bg?: string;
+  bold?: boolean;
+  underline?: boolean;
+}
+
+/**
+ * 命令日志行
+ */
+export interface LogLine {
+  command_id: number;
+  /** 所属运行实例的运行 ID */
+  run_id?: number;
+  /** 单调递增的序号（同一命令的所有运行实例共用），用于增量读取和去重 */
+  seq: number;
+  timestamp: string;
+  stream: LogStream;
+  text: string;
+  /** ANSI 样式片段，整行无样式时省略 */
+  segments?: StyledSegment[];
+}
+
+/**
+ * 命令日志分页结果
+ */
+export interface LogPage {
+  lines: LogLine[];
+  cursor: number;
+  next_cursor: number;
+  has_more: boolean;
+  dropped: number;
+}
+
+/**
+ * 运行触发方式
+ */
+export type RunTrigger = "manual" | "restart" | "schedule" | "pipeline" | "trigger";
+
+/**
+ * 运行历史记录
+ */
+export interface CommandRun {
+  id: number;
+  command_id: number;
+  command_name: string;
+  /** 实际执行的命令文本（参数已替换） */
+  command_text: string;
+  trigger: RunTrigger;
+  status: CommandStatus;
+  exit_code?: number;
+  pid?: number;
+  started_at: string;
+  ended_at?: string;
+  duration_ms?: number;
+  /** 结束时最后若干行日志 */
+  log_excerpt?: string;
+}
+
+/**
+ * 运行历史查询条件
+ */
+export interface CommandRunFilter {
+  command_id?: number;
+  status?: CommandStatus;
+  trigger?: RunTrigger;
+  /** 开始时间下限（RFC 3339，包含） */
+  started_after?: string;
+  /** 开始时间上限（RFC 3339，不包含） */
+  started_before?: string;
+}
+
+/**
+ * 运行历史分页结果
+ */
+export interface CommandRunPage {
+  runs: CommandRun[];
+  total: number;
+}
+
+/**
+ * 运行日志文件中的一行
+ */
+export interface RunLogLine {
+  timestamp: string;
+  stream: LogStream;
+  text: string;
+}
+
+/**
+ * 运行日志分页结果
+ */
+export interface RunLogPage {
+  run_id: number;
+  offset: number;
+  total: number;
+  lines: RunLogLine[];
 }
 
 /**
@@ -84,5 +348,9 @@ export interface CommandItem {
   isRunning: boolean;
   url?: string;
   hasLogs?: boolean;
+  /** 连续自动重启的次数 */
+  restartCount?: number;
+  /** 下一次自动重启的时间（等待重启时有值） */
+  nextRetryAt?: string;
 }
 